@@ -122,11 +122,8 @@ fn run(
                     device_id: _, position, ..
                 } => {
                     if dragging {
-                        let p0 = canvas.transform().inversed().transform_point(mousex, mousey);
-                        let p1 = canvas
-                            .transform()
-                            .inversed()
-                            .transform_point(position.x as f32, position.y as f32);
+                        let p0 = canvas.screen_to_canvas(mousex, mousey);
+                        let p1 = canvas.screen_to_canvas(position.x as f32, position.y as f32);
 
                         canvas.translate(p1.0 - p0.0, p1.1 - p0.1);
                     }
@@ -139,7 +136,7 @@ fn run(
                     delta: winit::event::MouseScrollDelta::LineDelta(_, y),
                     ..
                 } => {
-                    let pt = canvas.transform().inversed().transform_point(mousex, mousey);
+                    let pt = canvas.screen_to_canvas(mousex, mousey);
                     canvas.translate(pt.0, pt.1);
                     canvas.scale(1.0 + (y / 10.0), 1.0 + (y / 10.0));
                     canvas.translate(-pt.0, -pt.1);
@@ -166,7 +163,8 @@ fn run(
                     }
 
                     if let Ok(image) = canvas.screenshot() {
-                        screenshot_image_id = Some(canvas.create_image(image.as_ref(), ImageFlags::empty()).unwrap());
+                        screenshot_image_id =
+                            Some(canvas.create_image(image.as_ref(), ImageFlags::PREMULTIPLIED).unwrap());
                     }
                 }
                 WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
@@ -190,7 +188,7 @@ fn run(
                 let height = size.height as f32;
                 let width = size.width as f32;
 
-                let pt = canvas.transform().inversed().transform_point(mousex, mousey);
+                let pt = canvas.screen_to_canvas(mousex, mousey);
                 let rel_mousex = pt.0;
                 let rel_mousey = pt.1;
 
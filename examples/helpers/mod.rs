@@ -97,7 +97,7 @@ pub fn start(
         let gl_context = not_current_gl_context.take().unwrap().make_current(&surface).unwrap();
 
         let renderer = unsafe { OpenGl::new_from_function_cstr(|s| gl_display.get_proc_address(s) as *const _) }
-            .expect("Cannot create renderer");
+            .unwrap_or_else(|err| panic!("Cannot create renderer: {err}"));
 
         let mut canvas = Canvas::new(renderer).expect("Cannot create canvas");
         canvas.set_size(width, height, window.scale_factor() as f32);
@@ -120,7 +120,8 @@ pub fn start(
 
         use winit::platform::web::WindowBuilderExtWebSys;
 
-        let renderer = OpenGl::new_from_html_canvas(&canvas).expect("Cannot create renderer");
+        let renderer =
+            OpenGl::new_from_html_canvas(&canvas).unwrap_or_else(|err| panic!("Cannot create renderer: {err}"));
 
         let window = WindowBuilder::new()
             .with_canvas(Some(canvas))
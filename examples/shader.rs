@@ -98,7 +98,7 @@ fn main() {
     unsafe {
         context = glow::Context::from_loader_function_cstr(|symbol| display.get_proc_address(symbol) as *const _);
         renderer = OpenGl::new_from_function_cstr(|s| display.get_proc_address(s) as *const _)
-            .expect("Cannot create renderer");
+            .unwrap_or_else(|err| panic!("Cannot create renderer: {err}"));
 
         shader_program = create_shader_program(&context);
         (framebuffer, texture_colorbuffer) = create_framebuffer_colorbuffer(&context);
@@ -1,4 +1,4 @@
-use femtovg::{renderer::OpenGl, Canvas, Color, FillRule, ImageFlags, Paint, Path};
+use femtovg::{renderer::OpenGl, Canvas, Color, FillRule, ImageFlags, Paint, Path, Transform2D};
 use instant::Instant;
 use resource::resource;
 use usvg::TreeParsing;
@@ -90,11 +90,8 @@ fn run(
                     device_id: _, position, ..
                 } => {
                     if dragging {
-                        let p0 = canvas.transform().inversed().transform_point(mousex, mousey);
-                        let p1 = canvas
-                            .transform()
-                            .inversed()
-                            .transform_point(position.x as f32, position.y as f32);
+                        let p0 = canvas.screen_to_canvas(mousex, mousey);
+                        let p1 = canvas.screen_to_canvas(position.x as f32, position.y as f32);
 
                         canvas.translate(p1.0 - p0.0, p1.1 - p0.1);
                     }
@@ -107,7 +104,7 @@ fn run(
                     delta: winit::event::MouseScrollDelta::LineDelta(_, y),
                     ..
                 } => {
-                    let pt = canvas.transform().inversed().transform_point(mousex, mousey);
+                    let pt = canvas.screen_to_canvas(mousex, mousey);
                     canvas.translate(pt.0, pt.1);
                     canvas.scale(1.0 + (y / 10.0), 1.0 + (y / 10.0));
                     canvas.translate(-pt.0, -pt.1);
@@ -126,7 +123,8 @@ fn run(
                     }
 
                     if let Ok(image) = canvas.screenshot() {
-                        screenshot_image_id = Some(canvas.create_image(image.as_ref(), ImageFlags::empty()).unwrap());
+                        screenshot_image_id =
+                            Some(canvas.create_image(image.as_ref(), ImageFlags::PREMULTIPLIED).unwrap());
                     }
                 }
                 WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
@@ -145,31 +143,28 @@ fn run(
                 canvas.set_size(size.width, size.height, dpi_factor as f32);
                 canvas.clear_rect(0, 0, size.width, size.height, Color::rgbf(0.3, 0.3, 0.32));
 
-                canvas.save();
-                canvas.translate(200.0, 200.0);
+                canvas.save_with(|canvas| {
+                    canvas.translate(200.0, 200.0);
 
-                for (path, fill, stroke) in &paths {
-                    if let Some(fill) = fill {
-                        canvas.fill_path(path, fill);
-                    }
+                    for (path, fill, stroke) in &paths {
+                        if let Some(fill) = fill {
+                            canvas.fill_path(path, fill);
+                        }
 
-                    if let Some(stroke) = stroke {
-                        canvas.stroke_path(path, stroke);
-                    }
+                        if let Some(stroke) = stroke {
+                            canvas.stroke_path(path, stroke);
+                        }
 
-                    if canvas.contains_point(path, mousex, mousey, FillRule::NonZero) {
-                        let mut paint = Paint::color(Color::rgb(32, 240, 32));
-                        paint.set_line_width(1.0);
-                        canvas.stroke_path(path, &paint);
+                        if canvas.contains_point(path, mousex, mousey, FillRule::NonZero) {
+                            canvas.stroke_path(path, &Paint::stroke(Color::rgb(32, 240, 32), 1.0));
+                        }
                     }
-                }
+                });
 
-                canvas.restore();
-
-                canvas.save();
-                canvas.reset();
-                perf.render(&mut canvas, 5.0, 5.0);
-                canvas.restore();
+                canvas.save_with(|canvas| {
+                    canvas.reset();
+                    perf.render(canvas, 5.0, 5.0);
+                });
 
                 canvas.flush();
                 #[cfg(not(target_arch = "wasm32"))]
@@ -182,49 +177,88 @@ fn run(
 }
 
 fn render_svg(svg: usvg::Tree) -> Vec<(Path, Option<Paint>, Option<Paint>)> {
+    let mut paths = Vec::new();
+
+    collect_paths(&svg.root, Transform2D::identity(), 1.0, &mut paths);
+
+    paths
+}
+
+// Walks the tree, baking the accumulated group transforms into the path coordinates and the
+// accumulated group opacity into the paint colors.
+fn collect_paths(
+    node: &usvg::Node,
+    parent_transform: Transform2D,
+    parent_opacity: f32,
+    paths: &mut Vec<(Path, Option<Paint>, Option<Paint>)>,
+) {
     use usvg::NodeKind;
     use usvg::PathSegment;
 
-    let mut paths = Vec::new();
+    let to_transform =
+        |t: &usvg::Transform| Transform2D([t.a as f32, t.b as f32, t.c as f32, t.d as f32, t.e as f32, t.f as f32]);
+
+    match &*node.borrow() {
+        NodeKind::Group(group) => {
+            let mut transform = to_transform(&group.transform);
+            transform.multiply(&parent_transform);
+
+            let opacity = parent_opacity * group.opacity.get() as f32;
+
+            for child in node.children() {
+                collect_paths(&child, transform, opacity, paths);
+            }
+        }
+        NodeKind::Path(svg_path) => {
+            let mut transform = to_transform(&svg_path.transform);
+            transform.multiply(&parent_transform);
 
-    for node in svg.root.descendants() {
-        if let NodeKind::Path(svg_path) = &*node.borrow() {
             let mut path = Path::new();
 
             for command in svg_path.data.segments() {
                 match command {
-                    PathSegment::MoveTo { x, y } => path.move_to(x as f32, y as f32),
-                    PathSegment::LineTo { x, y } => path.line_to(x as f32, y as f32),
+                    PathSegment::MoveTo { x, y } => {
+                        let (x, y) = transform.transform_point(x as f32, y as f32);
+                        path.move_to(x, y)
+                    }
+                    PathSegment::LineTo { x, y } => {
+                        let (x, y) = transform.transform_point(x as f32, y as f32);
+                        path.line_to(x, y)
+                    }
                     PathSegment::CurveTo { x1, y1, x2, y2, x, y } => {
-                        path.bezier_to(x1 as f32, y1 as f32, x2 as f32, y2 as f32, x as f32, y as f32)
+                        let (x1, y1) = transform.transform_point(x1 as f32, y1 as f32);
+                        let (x2, y2) = transform.transform_point(x2 as f32, y2 as f32);
+                        let (x, y) = transform.transform_point(x as f32, y as f32);
+                        path.bezier_to(x1, y1, x2, y2, x, y)
                     }
                     PathSegment::ClosePath => path.close(),
                 }
             }
 
-            let to_femto_color = |usvg_paint: &usvg::Paint| match usvg_paint {
-                usvg::Paint::Color(usvg::Color { red, green, blue }) => Some(Color::rgb(*red, *green, *blue)),
+            let to_femto_color = |usvg_paint: &usvg::Paint, opacity: f32| match usvg_paint {
+                usvg::Paint::Color(usvg::Color { red, green, blue }) => {
+                    let mut color = Color::rgb(*red, *green, *blue);
+                    color.set_alphaf(opacity * parent_opacity);
+                    Some(color)
+                }
                 _ => None,
             };
 
             let fill = svg_path
                 .fill
                 .as_ref()
-                .and_then(|fill| to_femto_color(&fill.paint))
-                .map(|col| Paint::color(col).with_anti_alias(true));
+                .and_then(|fill| to_femto_color(&fill.paint, fill.opacity.get() as f32))
+                .map(|col| Paint::fill(col).with_anti_alias(true));
 
             let stroke = svg_path.stroke.as_ref().and_then(|stroke| {
-                to_femto_color(&stroke.paint).map(|paint| {
-                    let mut stroke_paint = Paint::color(paint);
-                    stroke_paint.set_line_width(stroke.width.get() as f32);
-                    stroke_paint.set_anti_alias(true);
-                    stroke_paint
+                to_femto_color(&stroke.paint, stroke.opacity.get() as f32).map(|color| {
+                    // The transform is baked into the path, so scale the width along with it
+                    Paint::stroke(color, stroke.width.get() as f32 * transform.average_scale()).with_anti_alias(true)
                 })
             });
 
             paths.push((path, fill, stroke))
         }
+        _ => {}
     }
-
-    paths
 }
@@ -1,7 +1,19 @@
-use std::u8;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    u8,
+};
 
 /// Struct for representing colors.
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+///
+/// The color components are not premultiplied with the alpha value, the renderers take care of
+/// premultiplying them before blending. Use [`Color::rgba_premultiplied`] to create a color from
+/// premultiplied components, like the pixels returned by [`Canvas::screenshot`](crate::Canvas::screenshot).
+///
+/// Colors compare and hash by the bits of their components, with `-0.0` treated as `0.0` and all NaN
+/// values treated as equal, so they can be used as keys in maps. They are ordered component by
+/// component in the same way, with NaN after all numbers.
+#[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Color {
     pub r: f32,
@@ -31,6 +43,22 @@ impl Color {
         Self { r, g, b, a }
     }
 
+    /// Returns a color value from red, green, blue and alpha char values where the color components
+    /// are already multiplied by the alpha value.
+    pub fn rgba_premultiplied(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::rgbaf_premultiplied(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0)
+    }
+
+    /// Returns a color value from red, green, blue and alpha float values where the color components
+    /// are already multiplied by the alpha value.
+    pub fn rgbaf_premultiplied(r: f32, g: f32, b: f32, a: f32) -> Self {
+        if a <= 0.0 {
+            return Self::rgbaf(0.0, 0.0, 0.0, 0.0);
+        }
+
+        Self::rgbaf((r / a).min(1.0), (g / a).min(1.0), (b / a).min(1.0), a)
+    }
+
     /// Returns color value specified by hue, saturation and lightness.
     /// HSL values are all in range [0..1], alpha will be set to 1.0.
     pub fn hsl(h: f32, s: f32, l: f32) -> Self {
@@ -80,6 +108,24 @@ impl Color {
         }
     }
 
+    /// Returns the color for a CSS/SVG color keyword like `"rebeccapurple"`, ignoring case. `"transparent"`
+    /// gives transparent black. Returns `None` for unknown names.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+
+        if name == "transparent" {
+            return Some(Self::rgbaf(0.0, 0.0, 0.0, 0.0));
+        }
+
+        NAMED_COLORS
+            .binary_search_by_key(&name.as_str(), |(name, _)| name)
+            .ok()
+            .map(|index| {
+                let [r, g, b] = NAMED_COLORS[index].1;
+                Self::rgb(r, g, b)
+            })
+    }
+
     /// Returns a white color
     pub const fn white() -> Self {
         Self::rgbaf(1.0, 1.0, 1.0, 1.0)
@@ -100,6 +146,7 @@ impl Color {
         self.a = a;
     }
 
+    /// Returns the color with the color components multiplied by the alpha value.
     pub fn premultiplied(self) -> Self {
         Self {
             r: self.r * self.a,
@@ -109,6 +156,55 @@ impl Color {
         }
     }
 
+    /// Returns the color between this one and `other`, where a `t` of 0.0 gives this color and 1.0
+    /// gives `other`. `t` is clamped to [0..1].
+    ///
+    /// The components, including alpha, are interpolated as they are. Use [`Color::lerp_linear`] for a
+    /// perceptually smoother blend between saturated colors.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+
+        Self::rgbaf(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+            mix(self.a, other.a),
+        )
+    }
+
+    /// Like [`Color::lerp`], but interpolates the color components in linear light instead of in sRGB
+    /// space, which avoids the dark band in the middle of e.g. a red to green blend.
+    pub fn lerp_linear(&self, other: &Self, t: f32) -> Self {
+        self.to_linear().lerp(&other.to_linear(), t).to_srgb()
+    }
+
+    // Converts the sRGB encoded color components to linear light, leaving the alpha as it is
+    pub(crate) fn to_linear(self) -> Self {
+        let decode = |c: f32| {
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        Self::rgbaf(decode(self.r), decode(self.g), decode(self.b), self.a)
+    }
+
+    // Converts linear light color components back to sRGB encoding, the inverse of `to_linear`
+    fn to_srgb(self) -> Self {
+        let encode = |c: f32| {
+            if c <= 0.003_130_8 {
+                c * 12.92
+            } else {
+                1.055 * c.powf(1.0 / 2.4) - 0.055
+            }
+        };
+
+        Self::rgbaf(encode(self.r), encode(self.g), encode(self.b), self.a)
+    }
+
     pub const fn to_array(self) -> [f32; 4] {
         [self.r, self.g, self.b, self.a]
     }
@@ -116,6 +212,42 @@ impl Color {
     pub fn is_black(&self) -> bool {
         self.r == 0.0 && self.g == 0.0 && self.b == 0.0 && self.a == 0.0
     }
+
+    // Components with the zeros and NaNs folded into one value each
+    fn canonical(&self) -> [f32; 4] {
+        self.to_array().map(|c| if c.is_nan() { f32::NAN } else { c + 0.0 })
+    }
+
+    fn key(&self) -> [u32; 4] {
+        self.canonical().map(f32::to_bits)
+    }
+}
+
+impl PartialEq for Color {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+// Orders the components like `f32::total_cmp`, which agrees with the equality above
+impl PartialOrd for Color {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let ord = self
+            .canonical()
+            .iter()
+            .zip(&other.canonical())
+            .fold(Ordering::Equal, |ord, (a, b)| ord.then(a.total_cmp(b)));
+
+        Some(ord)
+    }
+}
+
+impl Eq for Color {}
+
+impl Hash for Color {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
 }
 
 impl Default for Color {
@@ -154,3 +286,155 @@ fn hue(mut h: f32, m1: f32, m2: f32) -> f32 {
 fn hex_to_u8(hex_string: &str) -> u8 {
     u8::from_str_radix(hex_string, 16).unwrap_or(0)
 }
+
+// The CSS/SVG color keywords, sorted by name
+const NAMED_COLORS: [(&str, [u8; 3]); 148] = [
+    ("aliceblue", [240, 248, 255]),
+    ("antiquewhite", [250, 235, 215]),
+    ("aqua", [0, 255, 255]),
+    ("aquamarine", [127, 255, 212]),
+    ("azure", [240, 255, 255]),
+    ("beige", [245, 245, 220]),
+    ("bisque", [255, 228, 196]),
+    ("black", [0, 0, 0]),
+    ("blanchedalmond", [255, 235, 205]),
+    ("blue", [0, 0, 255]),
+    ("blueviolet", [138, 43, 226]),
+    ("brown", [165, 42, 42]),
+    ("burlywood", [222, 184, 135]),
+    ("cadetblue", [95, 158, 160]),
+    ("chartreuse", [127, 255, 0]),
+    ("chocolate", [210, 105, 30]),
+    ("coral", [255, 127, 80]),
+    ("cornflowerblue", [100, 149, 237]),
+    ("cornsilk", [255, 248, 220]),
+    ("crimson", [220, 20, 60]),
+    ("cyan", [0, 255, 255]),
+    ("darkblue", [0, 0, 139]),
+    ("darkcyan", [0, 139, 139]),
+    ("darkgoldenrod", [184, 134, 11]),
+    ("darkgray", [169, 169, 169]),
+    ("darkgreen", [0, 100, 0]),
+    ("darkgrey", [169, 169, 169]),
+    ("darkkhaki", [189, 183, 107]),
+    ("darkmagenta", [139, 0, 139]),
+    ("darkolivegreen", [85, 107, 47]),
+    ("darkorange", [255, 140, 0]),
+    ("darkorchid", [153, 50, 204]),
+    ("darkred", [139, 0, 0]),
+    ("darksalmon", [233, 150, 122]),
+    ("darkseagreen", [143, 188, 143]),
+    ("darkslateblue", [72, 61, 139]),
+    ("darkslategray", [47, 79, 79]),
+    ("darkslategrey", [47, 79, 79]),
+    ("darkturquoise", [0, 206, 209]),
+    ("darkviolet", [148, 0, 211]),
+    ("deeppink", [255, 20, 147]),
+    ("deepskyblue", [0, 191, 255]),
+    ("dimgray", [105, 105, 105]),
+    ("dimgrey", [105, 105, 105]),
+    ("dodgerblue", [30, 144, 255]),
+    ("firebrick", [178, 34, 34]),
+    ("floralwhite", [255, 250, 240]),
+    ("forestgreen", [34, 139, 34]),
+    ("fuchsia", [255, 0, 255]),
+    ("gainsboro", [220, 220, 220]),
+    ("ghostwhite", [248, 248, 255]),
+    ("gold", [255, 215, 0]),
+    ("goldenrod", [218, 165, 32]),
+    ("gray", [128, 128, 128]),
+    ("green", [0, 128, 0]),
+    ("greenyellow", [173, 255, 47]),
+    ("grey", [128, 128, 128]),
+    ("honeydew", [240, 255, 240]),
+    ("hotpink", [255, 105, 180]),
+    ("indianred", [205, 92, 92]),
+    ("indigo", [75, 0, 130]),
+    ("ivory", [255, 255, 240]),
+    ("khaki", [240, 230, 140]),
+    ("lavender", [230, 230, 250]),
+    ("lavenderblush", [255, 240, 245]),
+    ("lawngreen", [124, 252, 0]),
+    ("lemonchiffon", [255, 250, 205]),
+    ("lightblue", [173, 216, 230]),
+    ("lightcoral", [240, 128, 128]),
+    ("lightcyan", [224, 255, 255]),
+    ("lightgoldenrodyellow", [250, 250, 210]),
+    ("lightgray", [211, 211, 211]),
+    ("lightgreen", [144, 238, 144]),
+    ("lightgrey", [211, 211, 211]),
+    ("lightpink", [255, 182, 193]),
+    ("lightsalmon", [255, 160, 122]),
+    ("lightseagreen", [32, 178, 170]),
+    ("lightskyblue", [135, 206, 250]),
+    ("lightslategray", [119, 136, 153]),
+    ("lightslategrey", [119, 136, 153]),
+    ("lightsteelblue", [176, 196, 222]),
+    ("lightyellow", [255, 255, 224]),
+    ("lime", [0, 255, 0]),
+    ("limegreen", [50, 205, 50]),
+    ("linen", [250, 240, 230]),
+    ("magenta", [255, 0, 255]),
+    ("maroon", [128, 0, 0]),
+    ("mediumaquamarine", [102, 205, 170]),
+    ("mediumblue", [0, 0, 205]),
+    ("mediumorchid", [186, 85, 211]),
+    ("mediumpurple", [147, 112, 219]),
+    ("mediumseagreen", [60, 179, 113]),
+    ("mediumslateblue", [123, 104, 238]),
+    ("mediumspringgreen", [0, 250, 154]),
+    ("mediumturquoise", [72, 209, 204]),
+    ("mediumvioletred", [199, 21, 133]),
+    ("midnightblue", [25, 25, 112]),
+    ("mintcream", [245, 255, 250]),
+    ("mistyrose", [255, 228, 225]),
+    ("moccasin", [255, 228, 181]),
+    ("navajowhite", [255, 222, 173]),
+    ("navy", [0, 0, 128]),
+    ("oldlace", [253, 245, 230]),
+    ("olive", [128, 128, 0]),
+    ("olivedrab", [107, 142, 35]),
+    ("orange", [255, 165, 0]),
+    ("orangered", [255, 69, 0]),
+    ("orchid", [218, 112, 214]),
+    ("palegoldenrod", [238, 232, 170]),
+    ("palegreen", [152, 251, 152]),
+    ("paleturquoise", [175, 238, 238]),
+    ("palevioletred", [219, 112, 147]),
+    ("papayawhip", [255, 239, 213]),
+    ("peachpuff", [255, 218, 185]),
+    ("peru", [205, 133, 63]),
+    ("pink", [255, 192, 203]),
+    ("plum", [221, 160, 221]),
+    ("powderblue", [176, 224, 230]),
+    ("purple", [128, 0, 128]),
+    ("rebeccapurple", [102, 51, 153]),
+    ("red", [255, 0, 0]),
+    ("rosybrown", [188, 143, 143]),
+    ("royalblue", [65, 105, 225]),
+    ("saddlebrown", [139, 69, 19]),
+    ("salmon", [250, 128, 114]),
+    ("sandybrown", [244, 164, 96]),
+    ("seagreen", [46, 139, 87]),
+    ("seashell", [255, 245, 238]),
+    ("sienna", [160, 82, 45]),
+    ("silver", [192, 192, 192]),
+    ("skyblue", [135, 206, 235]),
+    ("slateblue", [106, 90, 205]),
+    ("slategray", [112, 128, 144]),
+    ("slategrey", [112, 128, 144]),
+    ("snow", [255, 250, 250]),
+    ("springgreen", [0, 255, 127]),
+    ("steelblue", [70, 130, 180]),
+    ("tan", [210, 180, 140]),
+    ("teal", [0, 128, 128]),
+    ("thistle", [216, 191, 216]),
+    ("tomato", [255, 99, 71]),
+    ("turquoise", [64, 224, 208]),
+    ("violet", [238, 130, 238]),
+    ("wheat", [245, 222, 179]),
+    ("white", [255, 255, 255]),
+    ("whitesmoke", [245, 245, 245]),
+    ("yellow", [255, 255, 0]),
+    ("yellowgreen", [154, 205, 50]),
+];
@@ -0,0 +1,413 @@
+//! Recording of drawing operations for later execution on a canvas.
+
+use crate::{
+    paint::PaintFlavor, path::PathCache, BlendFactor, Canvas, CompositeOperation, CompositeOperationState, ErrorKind,
+    FillRule, ImageId, LineJoin, Paint, Path, Renderer, Transform2D,
+};
+
+// A fill or stroke flattened and tessellated when it was recorded, in the coordinates of the canvas
+// transform at the start of the execution
+#[derive(Clone, Debug)]
+struct Geometry {
+    cache: PathCache,
+    flavor: PaintFlavor,
+    // Alpha emulating the coverage of strokes thinner than a pixel
+    alpha: f32,
+}
+
+#[derive(Clone, Debug)]
+enum ListCommand {
+    Save,
+    Restore,
+    ResetTransform,
+    Transform(Transform2D),
+    Scissor(f32, f32, f32, f32, f32),
+    IntersectScissor(f32, f32, f32, f32),
+    ResetScissor,
+    MaskImage(ImageId, Transform2D),
+    ClearMask,
+    GlobalAlpha(f32),
+    CompositeOperation(CompositeOperationState),
+    Fill {
+        geometry: Box<Geometry>,
+        fringe_width: f32,
+        fill_rule: FillRule,
+    },
+    Stroke {
+        geometry: Box<Geometry>,
+        line_width: f32,
+        stencil_strokes: bool,
+    },
+    ImageSourceRect(ImageId, [f32; 4], [f32; 4], f32),
+    ImageNineSlice(ImageId, [f32; 4], [f32; 4]),
+    FillText(f32, f32, String, Paint),
+    StrokeText(f32, f32, String, Paint),
+}
+
+/// A recorded list of drawing operations that can be executed on a canvas with [`Canvas::execute`].
+///
+/// Unlike the canvas, a draw list doesn't hold on to a renderer or fonts and is `Send`, so scenes
+/// can be built on worker threads and submitted on the thread that owns the canvas. The methods
+/// mirror the ones of the canvas and behave the same when the list is executed.
+///
+/// Paths are flattened and tessellated while they are recorded, with the transform of the list at
+/// that point, so executing the list only copies the vertices into the canvas. They are tessellated
+/// for the device pixel ratio the list was created with, see [`DrawList::with_device_pixel_ratio`].
+/// The vertices are mapped by the canvas transform at the start of the execution, so a list executed
+/// under a scaling transform has its curves and anti-aliased edges scaled along. Text needs the fonts
+/// of the canvas and is shaped during execution.
+///
+/// # Example
+/// ```
+/// use femtovg::{renderer::Void, Canvas, Color, DrawList, Paint, Path};
+///
+/// let list = std::thread::spawn(|| {
+///     let mut list = DrawList::new();
+///     let mut path = Path::new();
+///     path.rect(0.0, 0.0, 10.0, 10.0);
+///
+///     list.translate(5.0, 5.0);
+///     list.fill_path(&path, &Paint::color(Color::black()));
+///     list
+/// })
+/// .join()
+/// .unwrap();
+///
+/// let mut canvas = Canvas::new(Void).expect("Cannot create canvas");
+/// canvas.execute(&list).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct DrawList {
+    commands: Vec<ListCommand>,
+    // Transform paths are tessellated with, and the ones of the recorded saves
+    transform: Transform2D,
+    transform_stack: Vec<Transform2D>,
+    fringe_width: f32,
+    tess_tol: f32,
+    dist_tol: f32,
+}
+
+impl Default for DrawList {
+    fn default() -> Self {
+        Self::with_device_pixel_ratio(1.0)
+    }
+}
+
+impl DrawList {
+    /// Creates an empty draw list that tessellates paths for a device pixel ratio of 1.0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty draw list that tessellates paths for the device pixel ratio of the canvas it
+    /// is going to be executed on, like the one passed to [`Canvas::set_size`].
+    pub fn with_device_pixel_ratio(dpi: f32) -> Self {
+        Self {
+            commands: Vec::new(),
+            transform: Transform2D::identity(),
+            transform_stack: Vec::new(),
+            fringe_width: 1.0 / dpi,
+            tess_tol: 0.25 / dpi,
+            dist_tol: 0.01 / dpi,
+        }
+    }
+
+    /// Returns the number of recorded operations.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if no operations have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Removes all recorded operations, keeping the allocated memory.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.transform = Transform2D::identity();
+        self.transform_stack.clear();
+    }
+
+    /// Records a [`Canvas::save`].
+    pub fn save(&mut self) {
+        self.transform_stack.push(self.transform);
+        self.commands.push(ListCommand::Save);
+    }
+
+    /// Records a [`Canvas::restore`]. Restores without a matching save in the list are ignored.
+    pub fn restore(&mut self) {
+        if let Some(transform) = self.transform_stack.pop() {
+            self.transform = transform;
+        }
+
+        self.commands.push(ListCommand::Restore);
+    }
+
+    /// Records a [`Canvas::reset_transform`]. The transform is reset to the one the canvas had when
+    /// the list started executing.
+    pub fn reset_transform(&mut self) {
+        self.transform = Transform2D::identity();
+        self.commands.push(ListCommand::ResetTransform);
+    }
+
+    /// Records a [`Canvas::set_transform`].
+    pub fn set_transform(&mut self, transform: &Transform2D) {
+        self.transform.premultiply(transform);
+        self.commands.push(ListCommand::Transform(*transform));
+    }
+
+    /// Records a [`Canvas::translate`].
+    pub fn translate(&mut self, x: f32, y: f32) {
+        let mut t = Transform2D::identity();
+        t.translate(x, y);
+        self.set_transform(&t);
+    }
+
+    /// Records a [`Canvas::rotate`].
+    pub fn rotate(&mut self, angle: f32) {
+        let mut t = Transform2D::identity();
+        t.rotate(angle);
+        self.set_transform(&t);
+    }
+
+    /// Records a [`Canvas::scale`].
+    pub fn scale(&mut self, x: f32, y: f32) {
+        let mut t = Transform2D::identity();
+        t.scale(x, y);
+        self.set_transform(&t);
+    }
+
+    /// Records a [`Canvas::scissor`].
+    pub fn scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.scissor_feathered(x, y, w, h, 0.0);
+    }
+
+    /// Records a [`Canvas::scissor_feathered`].
+    pub fn scissor_feathered(&mut self, x: f32, y: f32, w: f32, h: f32, feather: f32) {
+        self.commands.push(ListCommand::Scissor(x, y, w, h, feather));
+    }
+
+    /// Records a [`Canvas::intersect_scissor`].
+    pub fn intersect_scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.commands.push(ListCommand::IntersectScissor(x, y, w, h));
+    }
+
+    /// Records a [`Canvas::reset_scissor`].
+    pub fn reset_scissor(&mut self) {
+        self.commands.push(ListCommand::ResetScissor);
+    }
+
+    /// Records a [`Canvas::set_mask_image`]. Executing the list fails if the image doesn't exist.
+    pub fn set_mask_image(&mut self, id: ImageId, transform: &Transform2D) {
+        self.commands.push(ListCommand::MaskImage(id, *transform));
+    }
+
+    /// Records a [`Canvas::clear_mask`].
+    pub fn clear_mask(&mut self) {
+        self.commands.push(ListCommand::ClearMask);
+    }
+
+    /// Records a [`Canvas::set_global_alpha`].
+    pub fn set_global_alpha(&mut self, alpha: f32) {
+        self.commands.push(ListCommand::GlobalAlpha(alpha));
+    }
+
+    /// Records a [`Canvas::global_composite_operation`].
+    pub fn global_composite_operation(&mut self, op: CompositeOperation) {
+        self.commands
+            .push(ListCommand::CompositeOperation(CompositeOperationState::new(op)));
+    }
+
+    /// Records a [`Canvas::global_composite_blend_func`].
+    pub fn global_composite_blend_func(&mut self, src_factor: BlendFactor, dst_factor: BlendFactor) {
+        self.commands.push(ListCommand::CompositeOperation(
+            CompositeOperationState::with_blend_factors(src_factor, dst_factor),
+        ));
+    }
+
+    /// Records a [`Canvas::global_composite_blend_func_separate`].
+    pub fn global_composite_blend_func_separate(
+        &mut self,
+        src_rgb: BlendFactor,
+        dst_rgb: BlendFactor,
+        src_alpha: BlendFactor,
+        dst_alpha: BlendFactor,
+    ) {
+        self.commands
+            .push(ListCommand::CompositeOperation(CompositeOperationState {
+                src_rgb,
+                src_alpha,
+                dst_rgb,
+                dst_alpha,
+            }));
+    }
+
+    /// Records a [`Canvas::fill_path`].
+    pub fn fill_path(&mut self, path: &Path, paint: &Paint) {
+        let mut cache = PathCache::new(path.verbs(), &self.transform, self.tess_tol, self.dist_tol);
+
+        let fringe_width = if paint.shape_anti_alias { self.fringe_width } else { 0.0 };
+        cache.expand_fill(fringe_width, LineJoin::Miter, 2.4);
+
+        self.commands.push(ListCommand::Fill {
+            geometry: Box::new(Geometry {
+                cache,
+                flavor: paint.flavor.clone(),
+                alpha: 1.0,
+            }),
+            fringe_width,
+            fill_rule: paint.fill_rule,
+        });
+    }
+
+    /// Records a [`Canvas::stroke_path`]. Pixel snapping isn't applied to recorded strokes.
+    pub fn stroke_path(&mut self, path: &Path, paint: &Paint) {
+        let stroke = &paint.stroke;
+
+        let dashed_path = stroke.dashed_path(path, &self.transform, self.tess_tol);
+        let path = dashed_path.as_deref().unwrap_or(path);
+        let mut cache = PathCache::new(path.verbs(), &self.transform, self.tess_tol, self.dist_tol);
+
+        // Same coverage emulation for thin lines as in Canvas::stroke_path
+        let mut line_width = stroke.device_width(&self.transform);
+        let mut alpha = 1.0;
+
+        if line_width < self.fringe_width {
+            alpha = (line_width / self.fringe_width).powi(2);
+            line_width = self.fringe_width;
+        }
+
+        let fringe_width = if paint.shape_anti_alias { self.fringe_width } else { 0.0 };
+        cache.expand_stroke(
+            line_width * 0.5,
+            fringe_width,
+            stroke.line_cap_start,
+            stroke.line_cap_end,
+            stroke.line_join,
+            stroke.miter_limit,
+            self.tess_tol,
+        );
+
+        self.commands.push(ListCommand::Stroke {
+            geometry: Box::new(Geometry {
+                cache,
+                flavor: paint.flavor.clone(),
+                alpha,
+            }),
+            line_width,
+            stencil_strokes: stroke.stencil_strokes,
+        });
+    }
+
+    /// Records a [`Canvas::draw_image_source_rect`]. Executing the list fails if the image doesn't exist.
+    pub fn draw_image_source_rect(&mut self, id: ImageId, src: [f32; 4], dst: [f32; 4], alpha: f32) {
+        self.commands.push(ListCommand::ImageSourceRect(id, src, dst, alpha));
+    }
+
+    /// Records a [`Canvas::draw_image_nine_slice`]. Executing the list fails if the image doesn't exist.
+    pub fn draw_image_nine_slice(&mut self, id: ImageId, x: f32, y: f32, width: f32, height: f32, insets: [f32; 4]) {
+        self.commands
+            .push(ListCommand::ImageNineSlice(id, [x, y, width, height], insets));
+    }
+
+    /// Records a [`Canvas::fill_text`].
+    pub fn fill_text<S: AsRef<str>>(&mut self, x: f32, y: f32, text: S, paint: &Paint) {
+        self.commands
+            .push(ListCommand::FillText(x, y, text.as_ref().to_owned(), paint.clone()));
+    }
+
+    /// Records a [`Canvas::stroke_text`].
+    pub fn stroke_text<S: AsRef<str>>(&mut self, x: f32, y: f32, text: S, paint: &Paint) {
+        self.commands
+            .push(ListCommand::StrokeText(x, y, text.as_ref().to_owned(), paint.clone()));
+    }
+
+    pub(crate) fn execute<T: Renderer>(&self, canvas: &mut Canvas<T>) -> Result<(), ErrorKind> {
+        let save_depth = canvas.save_depth();
+        let result = self.execute_commands(canvas);
+
+        // A failing command stops the list before its restores, so unwind what it saved
+        canvas.restore_to(save_depth);
+
+        result
+    }
+
+    fn execute_commands<T: Renderer>(&self, canvas: &mut Canvas<T>) -> Result<(), ErrorKind> {
+        let base_transform = canvas.transform();
+        let vertex_transform = (base_transform != Transform2D::identity()).then_some(&base_transform);
+        let mut depth = 0;
+
+        // The paint of recorded geometry gets the global alpha and color space of the canvas applied
+        // like any other paint
+        let paint_flavor = |canvas: &Canvas<T>, geometry: &Geometry| {
+            let mut flavor = geometry.flavor.clone();
+            flavor.mul_alpha(geometry.alpha * canvas.state().alpha);
+            canvas.convert_color_space(&mut flavor);
+            flavor
+        };
+
+        for command in &self.commands {
+            match command {
+                ListCommand::Save => {
+                    canvas.save();
+                    depth += 1;
+                }
+                ListCommand::Restore => {
+                    if depth > 0 {
+                        canvas.restore();
+                        depth -= 1;
+                    }
+                }
+                ListCommand::ResetTransform => {
+                    canvas.reset_transform();
+                    canvas.set_transform(&base_transform);
+                }
+                ListCommand::Transform(transform) => canvas.set_transform(transform),
+                ListCommand::Scissor(x, y, w, h, feather) => canvas.scissor_feathered(*x, *y, *w, *h, *feather),
+                ListCommand::IntersectScissor(x, y, w, h) => canvas.intersect_scissor(*x, *y, *w, *h),
+                ListCommand::ResetScissor => canvas.reset_scissor(),
+                ListCommand::MaskImage(id, transform) => canvas.set_mask_image(*id, transform)?,
+                ListCommand::ClearMask => canvas.clear_mask(),
+                ListCommand::GlobalAlpha(alpha) => canvas.set_global_alpha(*alpha),
+                ListCommand::CompositeOperation(op) => canvas.state_mut().composite_operation = *op,
+                ListCommand::Fill {
+                    geometry,
+                    fringe_width,
+                    fill_rule,
+                } => {
+                    let flavor = paint_flavor(canvas, geometry);
+                    canvas.append_fill_cmd(&geometry.cache, &flavor, *fringe_width, *fill_rule, vertex_transform);
+                }
+                ListCommand::Stroke {
+                    geometry,
+                    line_width,
+                    stencil_strokes,
+                } => {
+                    let flavor = paint_flavor(canvas, geometry);
+                    canvas.append_stroke_cmd(
+                        &geometry.cache,
+                        &flavor,
+                        *line_width,
+                        *stencil_strokes,
+                        vertex_transform,
+                    );
+                }
+                ListCommand::ImageSourceRect(id, src, dst, alpha) => {
+                    canvas.draw_image_source_rect(*id, *src, *dst, *alpha)?;
+                }
+                ListCommand::ImageNineSlice(id, [x, y, width, height], insets) => {
+                    canvas.draw_image_nine_slice(*id, *x, *y, *width, *height, *insets)?;
+                }
+                ListCommand::FillText(x, y, text, paint) => {
+                    canvas.fill_text(*x, *y, text, paint)?;
+                }
+                ListCommand::StrokeText(x, y, text, paint) => {
+                    canvas.stroke_text(*x, *y, text, paint)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -18,6 +18,7 @@ pub enum ErrorKind {
     NoFontFound,
     FontInfoExtractionError,
     FontSizeTooLargeForAtlas,
+    TextNotSupported,
     ShaderCompileError(String),
     ShaderLinkError(String),
     RenderTargetError(String),
@@ -25,11 +26,35 @@ pub enum ErrorKind {
     ImageUpdateOutOfBounds,
     ImageUpdateWithDifferentFormat,
     UnsupportedImageFormat,
+    TextureAllocationError(String),
+    InvalidPathData,
 }
 
 impl Display for ErrorKind {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "canvas error")
+        match self {
+            Self::UnknownError => write!(f, "unknown error"),
+            Self::GeneralError(message) => write!(f, "{message}"),
+            #[cfg(feature = "image-loading")]
+            Self::ImageError(error) => write!(f, "image error: {error}"),
+            Self::IoError(error) => write!(f, "io error: {error}"),
+            Self::FontParseError => write!(f, "failed to parse font data"),
+            Self::NoFontFound => write!(f, "no font found"),
+            Self::FontInfoExtractionError => write!(f, "failed to extract font information"),
+            Self::FontSizeTooLargeForAtlas => write!(f, "font size is too large for the glyph atlas"),
+            Self::TextNotSupported => write!(f, "text support is not compiled in, enable the `text` feature"),
+            Self::ShaderCompileError(message) => write!(f, "failed to compile shader: {message}"),
+            Self::ShaderLinkError(message) => write!(f, "failed to link shader program: {message}"),
+            Self::RenderTargetError(message) => write!(f, "render target error: {message}"),
+            Self::ImageIdNotFound => write!(f, "image id not found"),
+            Self::ImageUpdateOutOfBounds => write!(f, "image update is out of bounds"),
+            Self::ImageUpdateWithDifferentFormat => {
+                write!(f, "image update has a different pixel format than the image")
+            }
+            Self::UnsupportedImageFormat => write!(f, "unsupported image format"),
+            Self::TextureAllocationError(message) => write!(f, "failed to allocate texture: {message}"),
+            Self::InvalidPathData => write!(f, "invalid path data"),
+        }
     }
 }
 
@@ -52,4 +77,13 @@ impl From<NulError> for ErrorKind {
     }
 }
 
-impl Error for ErrorKind {}
+impl Error for ErrorKind {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "image-loading")]
+            Self::ImageError(error) => Some(error),
+            Self::IoError(error) => Some(error),
+            _ => None,
+        }
+    }
+}
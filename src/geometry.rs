@@ -412,6 +412,10 @@ impl Rect {
     }
 }
 
+/// An axis-aligned bounding box, like the one returned by [`Canvas::path_bbox`](crate::Canvas::path_bbox).
+///
+/// The default bounds are inverted, with the minimum above the maximum, so that growing them by any
+/// point gives the bounds of just that point.
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
 pub struct Bounds {
     pub minx: f32,
@@ -140,5 +140,13 @@ fn linear_gradient_stops(gradient: &MultiStopGradient) -> imgref::Img<Vec<rgb::R
             break;
         };
     }
+
+    // Pad the gradient after the last stop.
+    if let Some(GradientStop(s0, color0)) = gradient.last() {
+        if s0 < 1.0 {
+            gradient_span(&mut dest, color0, color0, s0, 1.0);
+        }
+    }
+
     imgref::Img::new(dest.to_vec(), 256, 1)
 }
@@ -31,8 +31,10 @@ bitflags! {
         const GENERATE_MIPMAPS = 1;     // Generate mipmaps during creation of the image.
         const REPEAT_X = 1 << 1;        // Repeat image in X direction.
         const REPEAT_Y = 1 << 2;        // Repeat image in Y direction.
-        const FLIP_Y = 1 << 3;          // Flips (inverses) image in Y direction when rendered.
-        const PREMULTIPLIED = 1 << 4;   // Image data has premultiplied alpha.
+        /// Flips (inverses) image in Y direction when rendered, for bottom-up data like GL textures.
+        /// Repeats tile the flipped image.
+        const FLIP_Y = 1 << 3;
+        const PREMULTIPLIED = 1 << 4;   // Image data has premultiplied alpha, like screenshots.
         const NEAREST = 1 << 5;         // Image interpolation is Nearest instead Linear
     }
 }
@@ -70,6 +72,17 @@ impl ImageSource<'_> {
             Self::HtmlImageElement(element) => Size::new(element.width() as usize, element.height() as usize),
         }
     }
+
+    /// Distance in pixels between the starts of two consecutive rows of the source.
+    pub(crate) fn stride(&self) -> usize {
+        match self {
+            Self::Rgb(imgref) => imgref.stride(),
+            Self::Rgba(imgref) => imgref.stride(),
+            Self::Gray(imgref) => imgref.stride(),
+            #[cfg(target_arch = "wasm32")]
+            Self::HtmlImageElement(element) => element.width() as usize,
+        }
+    }
 }
 
 impl<'a> From<ImgRef<'a, RGB8>> for ImageSource<'a> {
@@ -182,7 +195,10 @@ impl ImageInfo {
     }
 }
 
-pub struct ImageStore<T>(SlotMap<DefaultKey, (ImageInfo, T)>);
+pub struct ImageStore<T> {
+    images: SlotMap<DefaultKey, (ImageInfo, T)>,
+    uploads: usize,
+}
 
 impl<T> Default for ImageStore<T> {
     fn default() -> Self {
@@ -192,12 +208,15 @@ impl<T> Default for ImageStore<T> {
 
 impl<T> ImageStore<T> {
     pub fn new() -> Self {
-        Self(SlotMap::new())
+        Self {
+            images: SlotMap::new(),
+            uploads: 0,
+        }
     }
 
     pub fn alloc<R: Renderer<Image = T>>(&mut self, renderer: &mut R, info: ImageInfo) -> Result<ImageId, ErrorKind> {
         let image = renderer.alloc_image(info)?;
-        Ok(ImageId(self.0.insert((info, image))))
+        Ok(ImageId(self.images.insert((info, image))))
     }
 
     pub fn register_native_texture<R: Renderer<Image = T>>(
@@ -207,7 +226,7 @@ impl<T> ImageStore<T> {
         info: ImageInfo,
     ) -> Result<ImageId, ErrorKind> {
         let image = renderer.create_image_from_native_texture(texture, info)?;
-        Ok(ImageId(self.0.insert((info, image))))
+        Ok(ImageId(self.images.insert((info, image))))
     }
 
     ///
@@ -219,7 +238,7 @@ impl<T> ImageStore<T> {
         id: ImageId,
         info: ImageInfo,
     ) -> Result<(), ErrorKind> {
-        if let Some(old) = self.0.get_mut(id.0) {
+        if let Some(old) = self.images.get_mut(id.0) {
             let new = renderer.alloc_image(info)?;
             old.0 = info;
             old.1 = new;
@@ -230,11 +249,11 @@ impl<T> ImageStore<T> {
     }
 
     pub fn get(&self, id: ImageId) -> Option<&T> {
-        self.0.get(id.0).map(|inner| &inner.1)
+        self.images.get(id.0).map(|inner| &inner.1)
     }
 
     pub fn get_mut(&mut self, id: ImageId) -> Option<&mut T> {
-        self.0.get_mut(id.0).map(|inner| &mut inner.1)
+        self.images.get_mut(id.0).map(|inner| &mut inner.1)
     }
 
     pub fn update<R: Renderer<Image = T>>(
@@ -245,26 +264,32 @@ impl<T> ImageStore<T> {
         x: usize,
         y: usize,
     ) -> Result<(), ErrorKind> {
-        if let Some(image) = self.0.get_mut(id.0) {
+        if let Some(image) = self.images.get_mut(id.0) {
             renderer.update_image(&mut image.1, data, x, y)?;
+            self.uploads += 1;
             Ok(())
         } else {
             Err(ErrorKind::ImageIdNotFound)
         }
     }
 
+    /// Number of successful image updates since the store was created.
+    pub(crate) fn upload_count(&self) -> usize {
+        self.uploads
+    }
+
     pub fn info(&self, id: ImageId) -> Option<ImageInfo> {
-        self.0.get(id.0).map(|inner| inner.0)
+        self.images.get(id.0).map(|inner| inner.0)
     }
 
     pub fn remove<R: Renderer<Image = T>>(&mut self, renderer: &mut R, id: ImageId) {
-        if let Some(image) = self.0.remove(id.0) {
+        if let Some(image) = self.images.remove(id.0) {
             renderer.delete_image(image.1, id);
         }
     }
 
     pub fn clear<R: Renderer<Image = T>>(&mut self, renderer: &mut R) {
-        for (idx, image) in self.0.drain() {
+        for (idx, image) in self.images.drain() {
             renderer.delete_image(image.1, ImageId(idx));
         }
     }
@@ -17,10 +17,10 @@ TODO:
 #[macro_use]
 extern crate serde;
 
-use std::{cell::RefCell, ops::Range, path::Path as FilePath, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, ops::Range, path::Path as FilePath, rc::Rc};
 
-use imgref::ImgVec;
-use rgb::RGBA8;
+use imgref::{ImgRef, ImgVec};
+use rgb::{FromSlice, RGBA8};
 
 mod utils;
 
@@ -30,11 +30,11 @@ mod error;
 pub use error::ErrorKind;
 
 pub use text::{
-    Align, Atlas, Baseline, DrawCommand, FontId, FontMetrics, GlyphDrawCommands, Quad, RenderMode, TextContext,
-    TextMetrics,
+    Align, Atlas, Baseline, DrawCommand, FontId, FontMetrics, GlyphDrawCommands, GlyphMetrics, Quad, RenderMode,
+    ShapedGlyph, ShapedRun, TextContext, TextMetrics, TextRendering,
 };
 
-use text::{GlyphAtlas, TextContextImpl};
+use text::{GlyphAtlas, SdfAtlas, TextContextImpl};
 
 mod image;
 use crate::image::ImageStore;
@@ -44,25 +44,35 @@ mod color;
 pub use color::Color;
 
 pub mod renderer;
-pub use renderer::{RenderTarget, Renderer};
+pub use renderer::{Capabilities, RenderTarget, Renderer};
 
 use renderer::{Command, CommandType, Drawable, Params, ShaderType, Vertex};
 
 pub(crate) mod geometry;
-pub use geometry::Transform2D;
 use geometry::*;
+pub use geometry::{Bounds, Transform2D};
 
 mod paint;
 pub use paint::Paint;
-use paint::{GlyphTexture, PaintFlavor, StrokeSettings};
+use paint::{GlyphTexture, PaintFlavor, StrokeSettings, TextShadow};
 
 mod path;
-use path::Convexity;
+use path::{Convexity, PathCache, PathMeasure};
 pub use path::{Path, PathIter, Solidity, Verb};
 
 mod gradient_store;
 use gradient_store::GradientStore;
 
+mod draw_list;
+pub use draw_list::DrawList;
+
+#[cfg(feature = "svg")]
+mod svg;
+#[cfg(feature = "svg")]
+pub use svg::render_svg_tree;
+
+pub mod testing;
+
 /// The fill rule used when filling paths: `EvenOdd`, `NonZero` (default).
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -182,10 +192,50 @@ impl Default for CompositeOperationState {
     }
 }
 
+/// Debug visualizations of the submitted geometry, see [`Canvas::set_debug_mode`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum DebugMode {
+    /// Render normally.
+    #[default]
+    Off,
+    /// Draw the edges of all triangles instead of filling them.
+    Wireframe,
+    /// Draw every fragment with an additive tint, so areas that are drawn more often are brighter.
+    Overdraw,
+}
+
+/// The color space the canvas renders in, see [`Canvas::set_color_space`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ColorSpace {
+    /// Colors are used as they are, so blending and gradients interpolate sRGB encoded values.
+    #[default]
+    Srgb,
+    /// Colors are converted from sRGB to linear light, so blending and gradients are gamma correct.
+    ///
+    /// The screen has to store sRGB encoded colors and convert to them when written, like an sRGB
+    /// framebuffer or swapchain format does.
+    Linear,
+}
+
+/// Counters of the work submitted to the renderer, see [`Canvas::render_stats`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RenderStats {
+    /// Number of draw calls, counted the way the OpenGL renderer issues them.
+    pub draw_calls: usize,
+    /// Number of triangles drawn, including the anti-aliasing fringes and stencil passes.
+    pub triangles: usize,
+    /// Number of image uploads, including glyph atlas and gradient updates.
+    pub texture_uploads: usize,
+    /// Number of calls to [`Canvas::flush`].
+    pub flushes: usize,
+}
+
 #[derive(Copy, Clone, Debug, Default)]
 struct Scissor {
     transform: Transform2D,
     extent: Option<[f32; 2]>,
+    // Width of the soft edge inside the rectangle, in the same units as the extent
+    feather: f32,
 }
 
 impl Scissor {
@@ -196,6 +246,11 @@ impl Scissor {
             None => return Some(Rect::new(0., 0., canvas_width, canvas_height)),
         };
 
+        // Abort if the edges are soft
+        if self.feather > 0.0 {
+            return None;
+        }
+
         // Abort if we're skewing (usually doesn't happen)
         if self.transform[1] != 0.0 || self.transform[2] != 0.0 {
             return None;
@@ -263,11 +318,20 @@ impl Default for LineJoin {
     }
 }
 
+// Image whose alpha is multiplied into everything that's drawn, see `Canvas::set_mask_image`
+#[derive(Copy, Clone, Debug)]
+struct Mask {
+    image: ImageId,
+    // Maps the pixel coordinates of the image to device space
+    transform: Transform2D,
+}
+
 #[derive(Copy, Clone, Debug)]
 struct State {
     composite_operation: CompositeOperationState,
     transform: Transform2D,
     scissor: Scissor,
+    mask: Option<Mask>,
     alpha: f32,
 }
 
@@ -277,6 +341,7 @@ impl Default for State {
             composite_operation: Default::default(),
             transform: Transform2D::identity(),
             scissor: Default::default(),
+            mask: None,
             alpha: 1.0,
         }
     }
@@ -291,16 +356,38 @@ pub struct Canvas<T: Renderer> {
     glyph_atlas: Rc<GlyphAtlas>,
     // Glyph atlas used for direct rendering of color glyphs, dropped after flush()
     ephemeral_glyph_atlas: Option<Rc<GlyphAtlas>>,
+    sdf_atlas: Rc<SdfAtlas>,
+    text_rendering: TextRendering,
     current_render_target: RenderTarget,
+    // Render target at the end of the last flush, which the next batch of commands starts with
+    flushed_render_target: RenderTarget,
+    // Union of the dirty rects added since the last flush, in screen pixels
+    dirty_bounds: Option<Bounds>,
+    // Whether a flush done internally, like the one of screenshot(), drew something that the next
+    // call to flush() still has to report
+    unreported_drawing: bool,
     state_stack: Vec<State>,
+    check_balanced_saves: bool,
     commands: Vec<Command>,
     verts: Vec<Vertex>,
     images: ImageStore<T::Image>,
     fringe_width: f32,
+    antialias_feather: f32,
+    pixel_snapping: bool,
     device_px_ratio: f32,
     tess_tol: f32,
     dist_tol: f32,
     gradients: GradientStore,
+    render_stats: RenderStats,
+    // Upload count of the image store when the stats were last reset
+    stats_upload_base: usize,
+    debug_mode: DebugMode,
+    color_space: ColorSpace,
+    // Images that are only needed until the next flush, like the layers of blurred text shadows
+    temporary_images: Vec<ImageId>,
+    // Limit set with set_max_texture_size, on top of the one of the renderer
+    max_texture_size: Option<usize>,
+    screenshot_premultiplied: bool,
 }
 
 impl<T> Canvas<T>
@@ -316,16 +403,31 @@ where
             text_context: Default::default(),
             glyph_atlas: Default::default(),
             ephemeral_glyph_atlas: Default::default(),
+            sdf_atlas: Default::default(),
+            text_rendering: TextRendering::Raster,
             current_render_target: RenderTarget::Screen,
+            flushed_render_target: RenderTarget::Screen,
+            dirty_bounds: None,
+            unreported_drawing: false,
             state_stack: Default::default(),
+            check_balanced_saves: false,
             commands: Default::default(),
             verts: Default::default(),
             images: ImageStore::new(),
             fringe_width: 1.0,
+            antialias_feather: 1.0,
+            pixel_snapping: false,
             device_px_ratio: 1.0,
             tess_tol: 0.25,
             dist_tol: 0.01,
             gradients: GradientStore::new(),
+            render_stats: Default::default(),
+            stats_upload_base: 0,
+            debug_mode: DebugMode::Off,
+            color_space: ColorSpace::Srgb,
+            temporary_images: Vec::new(),
+            max_texture_size: None,
+            screenshot_premultiplied: true,
         };
 
         canvas.save();
@@ -344,16 +446,31 @@ where
             text_context: text_context.0,
             glyph_atlas: Default::default(),
             ephemeral_glyph_atlas: Default::default(),
+            sdf_atlas: Default::default(),
+            text_rendering: TextRendering::Raster,
             current_render_target: RenderTarget::Screen,
+            flushed_render_target: RenderTarget::Screen,
+            dirty_bounds: None,
+            unreported_drawing: false,
             state_stack: Default::default(),
+            check_balanced_saves: false,
             commands: Default::default(),
             verts: Default::default(),
             images: ImageStore::new(),
             fringe_width: 1.0,
+            antialias_feather: 1.0,
+            pixel_snapping: false,
             device_px_ratio: 1.0,
             tess_tol: 0.25,
             dist_tol: 0.01,
             gradients: GradientStore::new(),
+            render_stats: Default::default(),
+            stats_upload_base: 0,
+            debug_mode: DebugMode::Off,
+            color_space: ColorSpace::Srgb,
+            temporary_images: Vec::new(),
+            max_texture_size: None,
+            screenshot_premultiplied: true,
         };
 
         canvas.save();
@@ -365,7 +482,7 @@ where
     pub fn set_size(&mut self, width: u32, height: u32, dpi: f32) {
         self.width = width;
         self.height = height;
-        self.fringe_width = 1.0 / dpi;
+        self.fringe_width = self.antialias_feather / dpi;
         self.tess_tol = 0.25 / dpi;
         self.dist_tol = 0.01 / dpi;
         self.device_px_ratio = dpi;
@@ -375,8 +492,24 @@ where
         self.append_cmd(Command::new(CommandType::SetRenderTarget(RenderTarget::Screen)));
     }
 
+    /// Sets the size of the default framebuffer from a size in logical pixels.
+    ///
+    /// The physical size passed on to [`Canvas::set_size`] is the logical size multiplied by `dpi`,
+    /// rounded to the nearest pixel. Drawing still happens in physical pixels, so scale the transform
+    /// by `dpi` to draw in logical units.
+    pub fn set_logical_size(&mut self, width: f32, height: f32, dpi: f32) {
+        let physical = |logical: f32| (logical * dpi).round().max(0.0) as u32;
+
+        self.set_size(physical(width), physical(height), dpi);
+    }
+
     /// Clears the rectangle area defined by left upper corner (x,y), width and height with the provided color.
     pub fn clear_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Color) {
+        let color = match self.color_space {
+            ColorSpace::Srgb => color,
+            ColorSpace::Linear => color.to_linear(),
+        };
+
         let cmd = Command::new(CommandType::ClearRect {
             x,
             y,
@@ -388,7 +521,39 @@ where
         self.append_cmd(cmd);
     }
 
+    /// Clears the rectangle at fractional coordinates with the provided color, anti-aliasing its edges.
+    ///
+    /// Like [`Canvas::clear_rect`] the rectangle is given in pixels of the render target and replaces
+    /// what was there before, ignoring the current transform, scissor, global alpha and composite
+    /// operation. Partially covered pixels at the edges get a mix of the old content and the color.
+    /// Unlike [`Canvas::clear_rect`] this draws geometry, so it's slower.
+    pub fn clear_rect_f(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        let mut path = Path::new();
+        path.rect(x, y, width, height);
+
+        self.save();
+        self.reset();
+
+        // Remove the old content by the coverage of each pixel, then add the color by the same coverage
+        self.global_composite_operation(CompositeOperation::DestinationOut);
+        self.fill_path(&path, &Paint::color(Color::black()));
+        self.global_composite_operation(CompositeOperation::Lighter);
+        self.fill_path(&path, &Paint::color(color));
+
+        self.restore();
+    }
+
+    /// Clears the whole current render target, the screen or an image, with the provided color.
+    ///
+    /// Like [`Canvas::clear_rect`] this doesn't draw any geometry and ignores the current transform,
+    /// scissor and composite operation.
+    pub fn clear(&mut self, color: Color) {
+        self.clear_rect(0, 0, self.width(), self.height(), color);
+    }
+
     /// Returns the width of the current render target.
+    ///
+    /// For the screen, this is the width last passed to [`Canvas::set_size`].
     pub fn width(&self) -> u32 {
         match self.current_render_target {
             RenderTarget::Image(id) => self.image_info(id).map(|info| info.width() as u32).unwrap_or(0),
@@ -397,6 +562,8 @@ where
     }
 
     /// Returns the height of the current render target.
+    ///
+    /// For the screen, this is the height last passed to [`Canvas::set_size`].
     pub fn height(&self) -> u32 {
         match self.current_render_target {
             RenderTarget::Image(id) => self.image_info(id).map(|info| info.height() as u32).unwrap_or(0),
@@ -404,23 +571,399 @@ where
         }
     }
 
+    /// Returns the device pixel ratio last passed to [`Canvas::set_size`].
+    pub fn device_pixel_ratio(&self) -> f32 {
+        self.device_px_ratio
+    }
+
+    /// Limits the width and height of the images the canvas allocates, in addition to the limit of the renderer.
+    ///
+    /// Glyph atlases are allocated no larger than this and spread glyphs over more atlas pages instead.
+    /// Creating images larger than the limit fails with [`ErrorKind::TextureAllocationError`]. Pass `None`
+    /// to only apply the limit of the renderer.
+    pub fn set_max_texture_size(&mut self, size: Option<usize>) {
+        self.max_texture_size = size;
+    }
+
+    /// Returns the largest width and height of images the canvas allocates, if there is a limit.
+    ///
+    /// This is the smaller of the limit set with [`Canvas::set_max_texture_size`] and the one reported
+    /// by the renderer, like `GL_MAX_TEXTURE_SIZE` for OpenGL.
+    pub fn max_texture_size(&self) -> Option<usize> {
+        match (self.max_texture_size, self.renderer.max_texture_size()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Returns the features supported by the renderer, like the maximum texture size and multisampling.
+    ///
+    /// The maximum texture size includes the limit set with [`Canvas::set_max_texture_size`].
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            max_texture_size: self.max_texture_size(),
+            ..self.renderer.capabilities()
+        }
+    }
+
+    /// Sets the width of the anti-aliased edge of shapes in device pixels. The default is `1.0`.
+    ///
+    /// Smaller values give crisper edges, larger values softer ones. Strokes thinner than the edge
+    /// width are emulated by fading them out, so reducing it keeps thin hairlines from fading. The
+    /// width is clamped to be at least `0.01`; to turn anti-aliasing off use [`Paint::set_anti_alias`].
+    pub fn set_antialias_feather(&mut self, width: f32) {
+        self.antialias_feather = width.max(0.01);
+        self.fringe_width = self.antialias_feather / self.device_px_ratio;
+    }
+
+    /// Returns the width of the anti-aliased edge of shapes in device pixels.
+    pub fn antialias_feather(&self) -> f32 {
+        self.antialias_feather
+    }
+
+    /// Sets whether horizontal and vertical stroke segments are snapped to the pixel grid.
+    ///
+    /// Snapping happens in device pixels, after the current transform (including any scaling for the
+    /// device pixel ratio) has been applied. Segments of strokes with an odd width in device pixels are
+    /// centered on pixel centers, others on pixel edges, which keeps thin grid and table lines crisp.
+    /// Other segments and fills are not affected. Disabled by default.
+    pub fn set_pixel_snapping(&mut self, enabled: bool) {
+        self.pixel_snapping = enabled;
+    }
+
+    /// Returns whether horizontal and vertical stroke segments are snapped to the pixel grid.
+    pub fn pixel_snapping(&self) -> bool {
+        self.pixel_snapping
+    }
+
+    /// Sets how filled text is rasterized. The default is [`TextRendering::Raster`].
+    ///
+    /// With [`TextRendering::Sdf`] each glyph is rendered only once and stays smooth at any scale,
+    /// which avoids re-rendering the glyphs for every size while text is zoomed or animated.
+    pub fn set_text_rendering(&mut self, text_rendering: TextRendering) {
+        self.text_rendering = text_rendering;
+    }
+
+    /// Returns how filled text is rasterized.
+    pub fn text_rendering(&self) -> TextRendering {
+        self.text_rendering
+    }
+
     /// Tells the renderer to execute all drawing commands and clears the current internal state
     ///
-    /// Call this at the end of each frame.
-    pub fn flush(&mut self) {
-        self.renderer
-            .render(&mut self.images, &self.verts, std::mem::take(&mut self.commands));
+    /// Call this at the end of each frame. Returns whether anything was drawn or cleared since the
+    /// previous flush, so presenting an unchanged frame can be skipped. Drawing flushed by
+    /// [`Self::screenshot`] is reported by the next call to this.
+    pub fn flush(&mut self) -> bool {
+        let drawn = self.flush_commands();
+
+        std::mem::take(&mut self.unreported_drawing) || drawn
+    }
+
+    fn flush_commands(&mut self) -> bool {
+        if self.check_balanced_saves && self.save_depth() > 0 {
+            panic!(
+                "Canvas::flush called with {} save() call(s) not matched by restore()",
+                self.save_depth()
+            );
+        }
+
+        let mut commands = std::mem::take(&mut self.commands);
+
+        if let Some(bounds) = self.dirty_bounds.take() {
+            commands = self.clip_to_dirty_bounds(commands, bounds);
+        }
+        self.flushed_render_target = self.current_render_target;
+
+        let drawn = commands
+            .iter()
+            .any(|cmd| !matches!(cmd.cmd_type, CommandType::SetRenderTarget(_)));
+
+        for cmd in &commands {
+            let (draw_calls, triangles) = cmd.draw_stats();
+            self.render_stats.draw_calls += draw_calls;
+            self.render_stats.triangles += triangles;
+        }
+        self.render_stats.flushes += 1;
+
+        if self.debug_mode != DebugMode::Off {
+            commands = self.debug_commands(commands);
+        }
+
+        self.renderer.render(&mut self.images, &self.verts, commands);
         self.verts.clear();
         self.gradients
             .release_old_gradients(&mut self.images, &mut self.renderer);
         if let Some(atlas) = self.ephemeral_glyph_atlas.take() {
             atlas.clear(self);
         }
+        for id in std::mem::take(&mut self.temporary_images) {
+            self.delete_image(id);
+        }
+
+        drawn
+    }
+
+    /// Returns the work submitted to the renderer since the last call to [`Self::reset_render_stats`],
+    /// or since the canvas was created.
+    ///
+    /// Call [`Self::reset_render_stats`] at the start of each frame to get per-frame numbers.
+    pub fn render_stats(&self) -> RenderStats {
+        RenderStats {
+            texture_uploads: self.images.upload_count() - self.stats_upload_base,
+            ..self.render_stats
+        }
+    }
+
+    /// Resets all counters returned by [`Self::render_stats`] to zero.
+    pub fn reset_render_stats(&mut self) {
+        self.render_stats = RenderStats::default();
+        self.stats_upload_base = self.images.upload_count();
+    }
+
+    /// Sets a debug visualization that is applied to all drawing when flushing.
+    ///
+    /// This works with any renderer, as the recorded commands are rewritten before they are handed to it.
+    /// [`DebugMode::Off`], the default, leaves them untouched.
+    pub fn set_debug_mode(&mut self, mode: DebugMode) {
+        self.debug_mode = mode;
+    }
+
+    /// Returns the current debug visualization.
+    pub fn debug_mode(&self) -> DebugMode {
+        self.debug_mode
+    }
+
+    /// Sets the color space colors are blended and interpolated in.
+    ///
+    /// [`ColorSpace::Srgb`], the default, blends the sRGB encoded colors directly, which is what most
+    /// applications expect but makes gradients and anti-aliased edges look too dark. Use
+    /// [`ColorSpace::Linear`] for gamma correct rendering onto an sRGB framebuffer. Colors of paints
+    /// and clears are converted when they are drawn, image data is used as it is. Set it before
+    /// drawing a frame.
+    pub fn set_color_space(&mut self, color_space: ColorSpace) {
+        self.color_space = color_space;
+        self.renderer.set_color_space(color_space);
+    }
+
+    /// Returns the color space colors are blended in.
+    pub fn color_space(&self) -> ColorSpace {
+        self.color_space
+    }
+
+    /// Marks a rectangle of the screen as changed.
+    ///
+    /// Once a dirty rectangle has been added, the next [`Canvas::flush`] only renders to the screen
+    /// within the union of all dirty rectangles added since the previous flush, see [`Canvas::dirty_bounds`].
+    /// Clears and drawing outside of it leave the screen untouched, so a mostly static scene can be
+    /// redrawn as a whole while only the changed parts are rendered, as long as the screen contents
+    /// are preserved between frames. Rendering to images is not affected.
+    ///
+    /// The rectangle is transformed by the current transform and extended to whole pixels.
+    pub fn add_dirty_rect(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        let transform = self.state().transform;
+        let mut bounds = self.dirty_bounds.unwrap_or_default();
+
+        for (x, y) in [(x, y), (x + w, y), (x + w, y + h), (x, y + h)] {
+            let (x, y) = transform.transform_point(x, y);
+            bounds.minx = bounds.minx.min(x.floor());
+            bounds.miny = bounds.miny.min(y.floor());
+            bounds.maxx = bounds.maxx.max(x.ceil());
+            bounds.maxy = bounds.maxy.max(y.ceil());
+        }
+
+        self.dirty_bounds = Some(bounds);
+    }
+
+    /// Returns the union of the dirty rectangles added since the last flush in screen pixels, or
+    /// `None` if the whole screen is rendered. This can be used to tell the platform which part of
+    /// the window to present.
+    pub fn dirty_bounds(&self) -> Option<Bounds> {
+        self.dirty_bounds
+    }
+
+    fn clip_to_dirty_bounds(&self, commands: Vec<Command>, bounds: Bounds) -> Vec<Command> {
+        let rect = Rect::new(
+            bounds.minx,
+            bounds.miny,
+            bounds.maxx - bounds.minx,
+            bounds.maxy - bounds.miny,
+        );
+
+        let mut target = self.flushed_render_target;
+
+        commands
+            .into_iter()
+            .filter_map(|mut cmd| {
+                if let CommandType::SetRenderTarget(new_target) = cmd.cmd_type {
+                    target = new_target;
+                }
+
+                if target != RenderTarget::Screen {
+                    return Some(cmd);
+                }
+
+                let visible = match &mut cmd.cmd_type {
+                    CommandType::ClearRect {
+                        x, y, width, height, ..
+                    } => {
+                        let clear = Rect::new(*x as f32, *y as f32, *width as f32, *height as f32).intersect(rect);
+                        (*x, *y, *width, *height) = (clear.x as u32, clear.y as u32, clear.w as u32, clear.h as u32);
+                        !clear.is_empty()
+                    }
+                    CommandType::ConvexFill { params }
+                    | CommandType::Stroke { params }
+                    | CommandType::Triangles { params } => params.clip_scissor(rect, self.fringe_width),
+                    CommandType::ConcaveFill {
+                        stencil_params,
+                        fill_params,
+                    } => {
+                        stencil_params.clip_scissor(rect, self.fringe_width)
+                            && fill_params.clip_scissor(rect, self.fringe_width)
+                    }
+                    CommandType::StencilStroke { params1, params2 } => {
+                        params1.clip_scissor(rect, self.fringe_width) && params2.clip_scissor(rect, self.fringe_width)
+                    }
+                    CommandType::SetRenderTarget(_) | CommandType::RenderFilteredImage { .. } => true,
+                };
+
+                visible.then_some(cmd)
+            })
+            .collect()
+    }
+
+    fn debug_commands(&mut self, commands: Vec<Command>) -> Vec<Command> {
+        match self.debug_mode {
+            DebugMode::Off => commands,
+            DebugMode::Overdraw => {
+                let tint = Color::rgbaf(1.0, 0.4, 0.1, 0.15).premultiplied().to_array();
+                let apply_tint = |params: &mut Params| {
+                    if params.shader_type != ShaderType::Stencil {
+                        params.shader_type = ShaderType::FillColor;
+                        params.inner_col = tint;
+                        params.outer_col = tint;
+                    }
+                };
+
+                commands
+                    .into_iter()
+                    .map(|mut cmd| {
+                        match &mut cmd.cmd_type {
+                            CommandType::ConvexFill { params }
+                            | CommandType::Stroke { params }
+                            | CommandType::Triangles { params } => apply_tint(params),
+                            CommandType::ConcaveFill { fill_params, .. } => apply_tint(fill_params),
+                            CommandType::StencilStroke { params1, params2 } => {
+                                apply_tint(params1);
+                                apply_tint(params2);
+                            }
+                            _ => return cmd,
+                        }
+                        cmd.composite_operation = CompositeOperationState::new(CompositeOperation::Lighter);
+                        cmd
+                    })
+                    .collect()
+            }
+            DebugMode::Wireframe => {
+                let params = Params::new(
+                    &self.images,
+                    &Transform2D::identity(),
+                    &PaintFlavor::Color(Color::rgb(0, 255, 0)),
+                    &GlyphTexture::None,
+                    &Scissor::default(),
+                    1.0,
+                    1.0,
+                    -1.0,
+                );
+
+                let mut triangles = Vec::new();
+
+                commands
+                    .into_iter()
+                    .map(|cmd| {
+                        triangles.clear();
+
+                        let range = |(start, count): (usize, usize)| &self.verts[start..start + count];
+
+                        match cmd.cmd_type {
+                            CommandType::ConvexFill { .. }
+                            | CommandType::ConcaveFill { .. }
+                            | CommandType::Stroke { .. }
+                            | CommandType::StencilStroke { .. } => {
+                                for drawable in &cmd.drawables {
+                                    if let Some(fill) = drawable.fill_verts {
+                                        fan_to_triangles(range(fill), &mut triangles);
+                                    }
+                                    if let Some(stroke) = drawable.stroke_verts {
+                                        strip_to_triangles(range(stroke), &mut triangles);
+                                    }
+                                }
+                            }
+                            CommandType::Triangles { .. } => {
+                                if let Some(verts) = cmd.triangles_verts {
+                                    triangles.extend_from_slice(range(verts));
+                                }
+                            }
+                            _ => return cmd,
+                        }
+
+                        let offset = self.verts.len();
+
+                        for triangle in triangles.chunks_exact(3) {
+                            for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+                                push_line_quad(&mut self.verts, triangle[a], triangle[b]);
+                            }
+                        }
+
+                        let mut wireframe = Command::new(CommandType::Triangles { params });
+                        wireframe.triangles_verts = Some((offset, self.verts.len() - offset));
+                        wireframe
+                    })
+                    .collect()
+            }
+        }
     }
 
+    /// Flushes the canvas and reads back the pixels of the screen.
+    ///
+    /// The pixels hold the blended result of the drawing, so by default their colors are premultiplied
+    /// with their alpha. Create images from them with [`ImageFlags::PREMULTIPLIED`] to composite them
+    /// back onto the canvas without dark fringes, or see [`Canvas::set_screenshot_premultiplied`].
     pub fn screenshot(&mut self) -> Result<ImgVec<RGBA8>, ErrorKind> {
-        self.flush();
-        self.renderer.screenshot()
+        if self.flush_commands() {
+            self.unreported_drawing = true;
+        }
+
+        let mut image = self.renderer.screenshot()?;
+
+        if !self.screenshot_premultiplied {
+            for pixel in image.pixels_mut() {
+                if pixel.a > 0 && pixel.a < 255 {
+                    let a = u32::from(pixel.a);
+                    let unpremultiply = |c: u8| ((u32::from(c) * 255 + a / 2) / a).min(255) as u8;
+                    pixel.r = unpremultiply(pixel.r);
+                    pixel.g = unpremultiply(pixel.g);
+                    pixel.b = unpremultiply(pixel.b);
+                }
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Sets whether [`Canvas::screenshot`] returns colors premultiplied with their alpha. Enabled by default.
+    ///
+    /// Premultiplied screenshots are read back as they are and must be turned into images with
+    /// [`ImageFlags::PREMULTIPLIED`]. When disabled, the colors of translucent pixels are divided by
+    /// their alpha, so the screenshot can be saved to a file or passed to [`Canvas::create_image`]
+    /// without flags. Pixels with little alpha lose color precision that way.
+    pub fn set_screenshot_premultiplied(&mut self, premultiplied: bool) {
+        self.screenshot_premultiplied = premultiplied;
+    }
+
+    /// Returns whether [`Canvas::screenshot`] returns colors premultiplied with their alpha.
+    pub fn screenshot_premultiplied(&self) -> bool {
+        self.screenshot_premultiplied
     }
 
     // State Handling
@@ -450,15 +993,47 @@ where
         *self.state_mut() = Default::default();
     }
 
-    /// Saves the current state before calling the callback and restores it afterwards
+    /// Returns the number of states saved with [`Canvas::save`] that haven't been restored yet.
+    pub fn save_depth(&self) -> usize {
+        self.state_stack.len().saturating_sub(1)
+    }
+
+    /// Restores saved states until [`Canvas::save_depth`] is `depth`, like calling [`Canvas::restore`]
+    /// as many times. Does nothing if the stack isn't deeper than that.
+    ///
+    /// Remember the depth before drawing code that may save a varying number of states to unwind to
+    /// it afterwards.
+    pub fn restore_to(&mut self, depth: usize) {
+        self.state_stack.truncate(depth.saturating_add(1));
+    }
+
+    /// Sets whether [`Canvas::flush`] panics if there are saved states that haven't been restored.
+    ///
+    /// A `save()` without matching `restore()` makes the transform and other state drift from frame to
+    /// frame. Enabling this check, e.g. in debug builds, catches that at the end of the frame. Disabled by
+    /// default.
+    pub fn set_check_balanced_saves(&mut self, enabled: bool) {
+        self.check_balanced_saves = enabled;
+    }
+
+    /// Returns whether [`Canvas::flush`] panics if there are saved states that haven't been restored.
+    pub fn check_balanced_saves(&self) -> bool {
+        self.check_balanced_saves
+    }
+
+    /// Saves the current state before calling the callback and restores it afterwards, returning the
+    /// result of the callback.
     ///
-    /// This is less error prone than remembering to match save() -> restore() calls
-    pub fn save_with(&mut self, mut callback: impl FnMut(&mut Self)) {
+    /// This is less error prone than remembering to match save() -> restore() calls. The state stack is
+    /// restored to the depth it had before the call even if the callback leaves saved states behind or
+    /// panics.
+    pub fn save_with<R>(&mut self, callback: impl FnOnce(&mut Self) -> R) -> R {
+        let depth = self.state_stack.len();
         self.save();
 
-        callback(self);
+        let guard = RestoreGuard { canvas: self, depth };
 
-        self.restore();
+        callback(&mut *guard.canvas)
     }
 
     // Render styles
@@ -504,10 +1079,52 @@ where
         }
     }
 
-    fn append_cmd(&mut self, cmd: Command) {
+    fn append_cmd(&mut self, mut cmd: Command) {
+        if let Some(mask) = self.state().mask {
+            self.apply_mask(&mut cmd, mask);
+        }
+
         self.commands.push(cmd);
     }
 
+    fn apply_mask(&self, cmd: &mut Command, mask: Mask) {
+        let info = match self.images.info(mask.image) {
+            Some(info) => info,
+            None => return,
+        };
+
+        // Maps device space to texture coordinates of the mask
+        let mut transform = mask.transform.inversed();
+        let mut normalize = Transform2D::identity();
+        normalize.scale(1.0 / info.width() as f32, 1.0 / info.height() as f32);
+        transform.multiply(&normalize);
+
+        if info.flags().contains(ImageFlags::FLIP_Y) {
+            transform.multiply(&Transform2D([1.0, 0.0, 0.0, -1.0, 0.0, 1.0]));
+        }
+
+        let mask_type = if info.format() == PixelFormat::Gray8 { 2.0 } else { 1.0 };
+
+        let apply = |params: &mut Params| {
+            params.mask_mat = transform.to_mat3x4();
+            params.mask_type = mask_type;
+        };
+
+        match &mut cmd.cmd_type {
+            CommandType::ConvexFill { params } | CommandType::Stroke { params } | CommandType::Triangles { params } => {
+                apply(params)
+            }
+            CommandType::ConcaveFill { fill_params, .. } => apply(fill_params),
+            CommandType::StencilStroke { params1, params2 } => {
+                apply(params1);
+                apply(params2);
+            }
+            _ => return,
+        }
+
+        cmd.mask_image = Some(mask.image);
+    }
+
     // Images
 
     /// Allocates an empty image with the provided domensions and format.
@@ -518,6 +1135,14 @@ where
         format: PixelFormat,
         flags: ImageFlags,
     ) -> Result<ImageId, ErrorKind> {
+        if let Some(max_size) = self.max_texture_size() {
+            if width > max_size || height > max_size {
+                return Err(ErrorKind::TextureAllocationError(format!(
+                    "image size {width}x{height} exceeds the maximum texture size of {max_size}"
+                )));
+            }
+        }
+
         let info = ImageInfo::new(flags, width, height, format);
 
         self.images.alloc(&mut self.renderer, info)
@@ -551,6 +1176,62 @@ where
         Ok(id)
     }
 
+    /// Creates an RGBA image from a raw byte buffer whose rows may be padded.
+    ///
+    /// `stride` is the distance in bytes between the starts of two consecutive rows and must be
+    /// at least `width * 4`. The buffer must hold at least `stride * height` bytes. Empty images and
+    /// sizes that overflow are rejected with an error.
+    pub fn create_image_from_rgba(
+        &mut self,
+        width: usize,
+        height: usize,
+        stride: usize,
+        data: &[u8],
+        flags: ImageFlags,
+    ) -> Result<ImageId, ErrorKind> {
+        if width == 0 || height == 0 {
+            return Err(ErrorKind::GeneralError(format!("image size {width}x{height} is empty")));
+        }
+
+        let row_size = width
+            .checked_mul(4)
+            .ok_or_else(|| ErrorKind::GeneralError(format!("image width {width} is too large")))?;
+
+        if stride < row_size {
+            return Err(ErrorKind::GeneralError(format!(
+                "image stride {stride} is smaller than the row size {row_size}"
+            )));
+        }
+
+        let size = stride
+            .checked_mul(height)
+            .ok_or_else(|| ErrorKind::GeneralError(format!("image stride {stride} times height {height} overflows")))?;
+
+        if data.len() < size {
+            return Err(ErrorKind::GeneralError(format!(
+                "image data has {} bytes, expected at least {size}",
+                data.len()
+            )));
+        }
+
+        let data = &data[..size];
+
+        match stride % 4 {
+            0 => {
+                let src = ImgRef::new_stride(data.as_rgba(), width, height, stride / 4);
+                self.create_image(src, flags)
+            }
+            _ => {
+                // Padding that isn't a whole number of pixels can't be described by an ImgRef, so repack the rows
+                let pixels: Vec<RGBA8> = data
+                    .chunks(stride)
+                    .flat_map(|row| row[..row_size].as_rgba().iter().copied())
+                    .collect();
+                self.create_image(ImgRef::new(&pixels, width, height), flags)
+            }
+        }
+    }
+
     /// Returns the native texture of an image given its ID.
     pub fn get_native_texture(&self, id: ImageId) -> Result<T::NativeTexture, ErrorKind> {
         self.get_image(id)
@@ -580,24 +1261,47 @@ where
     }
 
     /// Decode an image from file
+    ///
+    /// The image format is detected from the file contents. WebP and AVIF are supported when the
+    /// `image-webp` and `image-avif` features are enabled.
     #[cfg(feature = "image-loading")]
     pub fn load_image_file<P: AsRef<FilePath>>(
         &mut self,
         filename: P,
         flags: ImageFlags,
     ) -> Result<ImageId, ErrorKind> {
-        let image = ::image::open(filename)?;
-
-        let src = ImageSource::try_from(&image)?;
+        // Pick the decoder by looking at the contents rather than trusting the file extension
+        let image = ::image::io::Reader::open(filename)?.with_guessed_format()?.decode()?;
 
-        self.create_image(src, flags)
+        self.create_image_from_decoded(image, flags)
     }
 
     /// Decode an image from memory
+    ///
+    /// The image format is detected from the data. WebP and AVIF are supported when the
+    /// `image-webp` and `image-avif` features are enabled.
     #[cfg(feature = "image-loading")]
     pub fn load_image_mem(&mut self, data: &[u8], flags: ImageFlags) -> Result<ImageId, ErrorKind> {
         let image = ::image::load_from_memory(data)?;
 
+        self.create_image_from_decoded(image, flags)
+    }
+
+    #[cfg(feature = "image-loading")]
+    fn create_image_from_decoded(
+        &mut self,
+        image: ::image::DynamicImage,
+        flags: ImageFlags,
+    ) -> Result<ImageId, ErrorKind> {
+        use ::image::DynamicImage;
+
+        // Decoders like AVIF or 16 bit PNG produce layouts we can't upload directly. We own the decoded
+        // image here, so convert those to RGBA8 instead of failing.
+        let image = match image {
+            DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_) | DynamicImage::ImageRgba8(_) => image,
+            other => DynamicImage::ImageRgba8(other.to_rgba8()),
+        };
+
         let src = ImageSource::try_from(&image)?;
 
         self.create_image(src, flags)
@@ -619,7 +1323,11 @@ where
         self.images.remove(&mut self.renderer, id);
     }
 
-    /// Returns image info
+    /// Returns the [`ImageInfo`] of the image with the specified id.
+    ///
+    /// The info holds the dimensions, [`PixelFormat`] and [`ImageFlags`] the image was created with,
+    /// which is handy for setting up an image pattern paint without tracking these separately.
+    /// Returns [`ErrorKind::ImageIdNotFound`] if the id does not refer to a live image.
     pub fn image_info(&self, id: ImageId) -> Result<ImageInfo, ErrorKind> {
         if let Some(info) = self.images.info(id) {
             Ok(info)
@@ -679,6 +1387,28 @@ where
         self.append_cmd(cmd)
     }
 
+    /// Returns a new image with a gaussian blurred copy of the given image.
+    ///
+    /// The standard deviation of the blur is half the `radius`, capped at 8 pixels. Pixels outside
+    /// of the image are taken from the nearest edge, unless the image was created with one of the
+    /// `REPEAT` flags. The blur runs as two passes over the whole image when [`Self::flush()`] is
+    /// called, each sampling up to three times the standard deviation in both directions, so the
+    /// cost grows with both the image size and the radius.
+    ///
+    /// The returned image holds premultiplied colors and is owned by the caller, who has to delete
+    /// it once it's no longer needed.
+    pub fn blur_image(&mut self, id: ImageId, radius: f32) -> Result<ImageId, ErrorKind> {
+        let info = self.image_info(id)?;
+        let flags = info.flags() | ImageFlags::PREMULTIPLIED;
+        let target = self.create_image_empty(info.width(), info.height(), PixelFormat::Rgba8, flags)?;
+
+        // A vanishing deviation keeps the filter from dividing by zero and amounts to a copy
+        let sigma = (radius / 2.0).clamp(0.1, 8.0);
+        self.filter_image(target, ImageFilter::GaussianBlur { sigma }, id);
+
+        Ok(target)
+    }
+
     // Transforms
 
     /// Resets current transform to a identity matrix.
@@ -706,6 +1436,13 @@ where
         self.state_mut().transform.premultiply(&t);
     }
 
+    /// Rotates the current coordinate system around the point (cx, cy). Angle is specified in radians.
+    pub fn rotate_around(&mut self, cx: f32, cy: f32, angle: f32) {
+        self.translate(cx, cy);
+        self.rotate(angle);
+        self.translate(-cx, -cy);
+    }
+
     /// Skews the current coordinate system along X axis. Angle is specified in radians.
     pub fn skew_x(&mut self, angle: f32) {
         let mut t = Transform2D::identity();
@@ -732,12 +1469,37 @@ where
         self.state().transform
     }
 
+    /// Returns the coefficients `[a, b, c, d, e, f]` of the current transformation matrix, which maps
+    /// (x, y) to (a * x + c * y + e, b * x + d * y + f). Useful for logging transforms.
+    pub fn current_transform_matrix(&self) -> [f32; 6] {
+        self.state().transform.0
+    }
+
+    /// Maps a point from screen space, like a mouse position, into the space the current transform draws in.
+    pub fn screen_to_canvas(&self, x: f32, y: f32) -> (f32, f32) {
+        self.state().transform.inversed().transform_point(x, y)
+    }
+
+    /// Maps a point from the space the current transform draws in to screen space.
+    pub fn canvas_to_screen(&self, x: f32, y: f32) -> (f32, f32) {
+        self.state().transform.transform_point(x, y)
+    }
+
     // Scissoring
 
     /// Sets the current scissor rectangle.
     ///
     /// The scissor rectangle is transformed by the current transform.
     pub fn scissor(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        self.scissor_feathered(x, y, w, h, 0.0);
+    }
+
+    /// Sets the current scissor rectangle with soft edges, see [`Canvas::scissor`].
+    ///
+    /// Drawing fades out over `feather` units towards the edges of the rectangle, e.g. to fade out
+    /// the content of a scrolling list at its borders. The feather is transformed along with the
+    /// rectangle and kept by [`Canvas::intersect_scissor`].
+    pub fn scissor_feathered(&mut self, x: f32, y: f32, w: f32, h: f32, feather: f32) {
         let state = self.state_mut();
 
         let w = w.max(0.0);
@@ -748,6 +1510,7 @@ where
         state.scissor.transform = transform;
 
         state.scissor.extent = Some([w * 0.5, h * 0.5]);
+        state.scissor.feather = feather.max(0.0);
     }
 
     /// Intersects current scissor rectangle with the specified rectangle.
@@ -787,7 +1550,10 @@ where
         let rect = Rect::new(pxform[4] - tex, pxform[5] - tey, tex * 2.0, tey * 2.0);
         let res = rect.intersect(Rect::new(x, y, w, h));
 
-        self.scissor(res.x, res.y, res.w, res.h);
+        // Keep the width of the feather in device space
+        let feather = state.scissor.feather * state.scissor.transform.average_scale() / state.transform.average_scale();
+
+        self.scissor_feathered(res.x, res.y, res.w, res.h, feather);
     }
 
     /// Reset and disables scissoring.
@@ -795,6 +1561,31 @@ where
         self.state_mut().scissor = Scissor::default();
     }
 
+    /// Masks everything drawn afterwards with the image, multiplying the alpha of each pixel with
+    /// the one of the mask at that position. Gray images are used as they are.
+    ///
+    /// Unlike the scissor, the mask can have soft edges, like for a vignette. `transform` maps the
+    /// pixel coordinates of the image into the current coordinate system, beyond the image its edge
+    /// pixels are repeated. The mask is part of the state that is saved and restored and replaces
+    /// any previous one, see [`Canvas::clear_mask`].
+    pub fn set_mask_image(&mut self, id: ImageId, transform: &Transform2D) -> Result<(), ErrorKind> {
+        if self.images.info(id).is_none() {
+            return Err(ErrorKind::ImageIdNotFound);
+        }
+
+        let state = self.state_mut();
+        let mut transform = *transform;
+        transform.multiply(&state.transform);
+        state.mask = Some(Mask { image: id, transform });
+
+        Ok(())
+    }
+
+    /// Removes the mask set with [`Canvas::set_mask_image`].
+    pub fn clear_mask(&mut self) {
+        self.state_mut().mask = None;
+    }
+
     // Paths
 
     /// Returns true if the specified point (x,y) is in the provided path, and false otherwise.
@@ -816,6 +1607,28 @@ where
         path_cache.contains_point(x, y, fill_rule)
     }
 
+    /// Returns the index of the sub-path of the path that contains the point, or `None` if the point
+    /// is outside of the path when filled with the given fill rule.
+    ///
+    /// Sub-paths are numbered in the order they were started, see [`Path::is_clockwise`]. When
+    /// sub-paths overlap, the one drawn last that isn't a hole wins, so points in islands inside of
+    /// holes report the island. Like [`Canvas::contains_point`], the point is in screen coordinates
+    /// and the current transform is applied to the path.
+    pub fn hit_test_subpath(&self, path: &Path, x: f32, y: f32, fill_rule: FillRule) -> Option<usize> {
+        let transform = self.state().transform;
+        let path_cache = path.cache(&transform, self.tess_tol, self.dist_tol);
+
+        if path_cache.bounds.maxx < 0.0
+            || path_cache.bounds.minx > self.width() as f32
+            || path_cache.bounds.maxy < 0.0
+            || path_cache.bounds.miny > self.height() as f32
+        {
+            return None;
+        }
+
+        path_cache.subpath_at(x, y, fill_rule)
+    }
+
     /// Return the bounding box for a Path
     pub fn path_bbox(&self, path: &Path) -> Bounds {
         let transform = self.state().transform;
@@ -826,19 +1639,87 @@ where
         path_cache.bounds
     }
 
-    /// Fills the provided Path with the specified Paint.
-    pub fn fill_path(&mut self, path: &Path, paint: &Paint) {
-        self.fill_path_internal(path, &paint.flavor, paint.shape_anti_alias, paint.fill_rule);
-    }
-
-    fn fill_path_internal(&mut self, path: &Path, paint_flavor: &PaintFlavor, anti_alias: bool, fill_rule: FillRule) {
-        let mut paint_flavor = paint_flavor.clone();
+    /// Returns the triangles [`Self::fill_path`] would render for the path with the current transform,
+    /// filled with the given fill rule.
+    ///
+    /// The result is a plain triangle list in canvas pixel coordinates: first the triangles covering
+    /// the interior of every sub-path, followed by the anti-aliasing fringe. `u` ramps from 0 at the
+    /// outer edge of the fringe to 0.5 where the shape is fully covered and `v` is 1, so coverage can
+    /// be computed as `min(1, 1 - |2u - 1|)`. The interior triangles are the ones with `u` at 0.5 for
+    /// all of their vertices.
+    ///
+    /// The fill rule is applied, so the interior triangles don't overlap and can be drawn without a
+    /// stencil buffer. A single convex sub-path is returned as a triangle fan, while other paths are
+    /// split into horizontal bands at every vertex and edge crossing.
+    pub fn tessellate_fill(&self, path: &Path, fill_rule: FillRule) -> Vec<Vertex> {
         let transform = self.state().transform;
-
-        // The path cache saves a flattened and transformed version of the path.
         let mut path_cache = path.cache(&transform, self.tess_tol, self.dist_tol);
 
-        let canvas_width = self.width();
+        path_cache.expand_fill(self.fringe_width, LineJoin::Miter, 2.4);
+
+        let mut verts = Vec::new();
+
+        if path_cache.contours.len() == 1 && path_cache.contours[0].convexity == Convexity::Convex {
+            fan_to_triangles(&path_cache.contours[0].fill, &mut verts);
+        } else {
+            let rings = path_cache.contours.iter().map(|contour| contour.fill.as_slice());
+            fill_rule_to_triangles(rings, fill_rule, &mut verts);
+        }
+
+        for contour in &path_cache.contours {
+            strip_to_triangles(&contour.stroke, &mut verts);
+        }
+
+        verts
+    }
+
+    /// Returns the triangles [`Self::stroke_path`] would render for the path with the current transform.
+    ///
+    /// The result is a plain triangle list in canvas pixel coordinates. `u` goes from 0 to 1 across
+    /// the stroke and `v` from 0 to 1 over the length of butt and square caps; the built-in shader
+    /// derives the anti-aliasing coverage from these.
+    pub fn tessellate_stroke(&self, path: &Path, paint: &Paint) -> Vec<Vertex> {
+        let transform = self.state().transform;
+
+        let dashed_path = paint.stroke.dashed_path(path, &transform, self.tess_tol);
+        let path = dashed_path.as_deref().unwrap_or(path);
+        let mut path_cache = path.cache(&transform, self.tess_tol, self.dist_tol);
+
+        let line_width = paint.stroke.device_width(&transform).max(self.fringe_width);
+        let fringe_width = if paint.shape_anti_alias { self.fringe_width } else { 0.0 };
+
+        path_cache.expand_stroke(
+            line_width * 0.5,
+            fringe_width,
+            paint.stroke.line_cap_start,
+            paint.stroke.line_cap_end,
+            paint.stroke.line_join,
+            paint.stroke.miter_limit,
+            self.tess_tol,
+        );
+
+        let mut verts = Vec::new();
+
+        for contour in &path_cache.contours {
+            strip_to_triangles(&contour.stroke, &mut verts);
+        }
+
+        verts
+    }
+
+    /// Fills the provided Path with the specified Paint.
+    pub fn fill_path(&mut self, path: &Path, paint: &Paint) {
+        self.fill_path_internal(path, &paint.flavor, paint.shape_anti_alias, paint.fill_rule);
+    }
+
+    fn fill_path_internal(&mut self, path: &Path, paint_flavor: &PaintFlavor, anti_alias: bool, fill_rule: FillRule) {
+        let mut paint_flavor = paint_flavor.clone();
+        let transform = self.state().transform;
+
+        // The path cache saves a flattened and transformed version of the path.
+        let mut path_cache = path.cache(&transform, self.tess_tol, self.dist_tol);
+
+        let canvas_width = self.width();
         let canvas_height = self.height();
 
         // Early out if path is outside the canvas bounds
@@ -852,6 +1733,7 @@ where
 
         // Apply global alpha
         paint_flavor.mul_alpha(self.state().alpha);
+        self.convert_color_space(&mut paint_flavor);
 
         let scissor = self.state().scissor;
 
@@ -863,10 +1745,11 @@ where
 
         // Detect if this path fill is in fact just an unclipped image copy
 
-        if let (Some(path_rect), Some(scissor_rect), true) = (
+        if let (Some(path_rect), Some(scissor_rect), true, None) = (
             path_cache.path_fill_is_rect(),
             scissor.as_rect(canvas_width as f32, canvas_height as f32),
             paint_flavor.is_straight_tinted_image(anti_alias),
+            self.state().mask,
         ) {
             if scissor_rect.contains_rect(&path_rect) {
                 self.render_unclipped_image_blit(&path_rect, &transform, &paint_flavor);
@@ -879,12 +1762,28 @@ where
             }
         }
 
+        self.append_fill_cmd(&path_cache, &paint_flavor, fringe_width, fill_rule, None);
+    }
+
+    // Appends the command for a fill expanded with `expand_fill`, using the current transform for the paint.
+    // `vertex_transform` maps vertices that weren't tessellated for the current transform.
+    fn append_fill_cmd(
+        &mut self,
+        path_cache: &PathCache,
+        paint_flavor: &PaintFlavor,
+        fringe_width: f32,
+        fill_rule: FillRule,
+        vertex_transform: Option<&Transform2D>,
+    ) {
+        let transform = self.state().transform;
+        let scissor = self.state().scissor;
+
         // GPU uniforms
         let flavor = if path_cache.contours.len() == 1 && path_cache.contours[0].convexity == Convexity::Convex {
             let params = Params::new(
                 &self.images,
                 &transform,
-                &paint_flavor,
+                paint_flavor,
                 &Default::default(),
                 &scissor,
                 self.fringe_width,
@@ -903,7 +1802,7 @@ where
             let fill_params = Params::new(
                 &self.images,
                 &transform,
-                &paint_flavor,
+                paint_flavor,
                 &Default::default(),
                 &scissor,
                 self.fringe_width,
@@ -922,7 +1821,7 @@ where
         cmd.fill_rule = fill_rule;
         cmd.composite_operation = self.state().composite_operation;
 
-        if let PaintFlavor::Image { id, .. } = paint_flavor {
+        if let &PaintFlavor::Image { id, .. } = paint_flavor {
             cmd.image = Some(id);
         } else if let Some(paint::GradientColors::MultiStop { stops }) = paint_flavor.gradient_colors() {
             cmd.image = self
@@ -944,13 +1843,13 @@ where
 
             if !contour.fill.is_empty() {
                 drawable.fill_verts = Some((offset, contour.fill.len()));
-                self.verts.extend_from_slice(&contour.fill);
+                self.push_verts(&contour.fill, vertex_transform);
                 offset += contour.fill.len();
             }
 
             if !contour.stroke.is_empty() {
                 drawable.stroke_verts = Some((offset, contour.stroke.len()));
-                self.verts.extend_from_slice(&contour.stroke);
+                self.push_verts(&contour.stroke, vertex_transform);
                 offset += contour.stroke.len();
             }
 
@@ -961,30 +1860,31 @@ where
             // Concave shapes are first filled by writing to a stencil buffer and then drawing a quad
             // over the shape area with stencil test enabled to produce the final fill. These are
             // the verts needed for the covering quad
+            let bounds = match vertex_transform {
+                Some(vertex_transform) => transform_bounds(&path_cache.bounds, vertex_transform),
+                None => path_cache.bounds,
+            };
+
             self.verts.push(Vertex::new(
-                path_cache.bounds.maxx + fringe_width,
-                path_cache.bounds.maxy + fringe_width,
-                0.5,
-                1.0,
-            ));
-            self.verts.push(Vertex::new(
-                path_cache.bounds.maxx + fringe_width,
-                path_cache.bounds.miny - fringe_width,
+                bounds.maxx + fringe_width,
+                bounds.maxy + fringe_width,
                 0.5,
                 1.0,
             ));
             self.verts.push(Vertex::new(
-                path_cache.bounds.minx - fringe_width,
-                path_cache.bounds.maxy + fringe_width,
+                bounds.maxx + fringe_width,
+                bounds.miny - fringe_width,
                 0.5,
                 1.0,
             ));
             self.verts.push(Vertex::new(
-                path_cache.bounds.minx - fringe_width,
-                path_cache.bounds.miny,
+                bounds.minx - fringe_width,
+                bounds.maxy + fringe_width,
                 0.5,
                 1.0,
             ));
+            self.verts
+                .push(Vertex::new(bounds.minx - fringe_width, bounds.miny, 0.5, 1.0));
 
             cmd.triangles_verts = Some((offset, 4));
         }
@@ -992,11 +1892,173 @@ where
         self.append_cmd(cmd);
     }
 
+    /// Fills the provided Path with the fill paint and then strokes its outline with the stroke paint on top.
+    ///
+    /// This is a shorthand for [`Self::fill_path`] followed by [`Self::stroke_path`].
+    pub fn fill_and_stroke_path(&mut self, path: &Path, fill_paint: &Paint, stroke_paint: &Paint) {
+        self.fill_path(path, fill_paint);
+        self.stroke_path(path, stroke_paint);
+    }
+
+    /// Executes the operations recorded in the draw list, see [`DrawList`].
+    ///
+    /// The operations are applied on top of the current state, which is restored afterwards. Stops at
+    /// the first operation that fails and returns its error.
+    pub fn execute(&mut self, list: &DrawList) -> Result<(), ErrorKind> {
+        self.save_with(|canvas| list.execute(canvas))
+    }
+
     /// Strokes the provided Path with the specified Paint.
     pub fn stroke_path(&mut self, path: &Path, paint: &Paint) {
         self.stroke_path_internal(path, &paint.flavor, paint.shape_anti_alias, &paint.stroke);
     }
 
+    /// Fills a disk of the given radius around (x, y), see [`Canvas::draw_points`].
+    pub fn draw_point(&mut self, x: f32, y: f32, radius: f32, paint: &Paint) {
+        self.draw_points(&[(x, y)], radius, paint);
+    }
+
+    /// Fills a disk of the given radius around each of the points, all in a single draw call.
+    ///
+    /// The disks are tessellated directly instead of going through a path, which makes this much cheaper
+    /// than filling a circle path per point, like for the dots of a scatter plot. The radius is scaled by
+    /// the average scale of the current transform, so the disks stay round. Disks smaller than the
+    /// anti-aliased edge fade out instead of shrinking further.
+    pub fn draw_points(&mut self, points: &[(f32, f32)], radius: f32, paint: &Paint) {
+        use std::f32::consts::TAU;
+
+        let transform = self.state().transform;
+        let radius = radius * transform.average_scale();
+
+        if points.is_empty() || radius <= 0.0 {
+            return;
+        }
+
+        let mut paint_flavor = paint.flavor.clone();
+        paint_flavor.mul_alpha(self.state().alpha);
+
+        // Like for path fills, the anti-aliased fringe is centered on the outline
+        let fringe_width = if paint.shape_anti_alias { self.fringe_width } else { 0.0 };
+        let min_radius = fringe_width * 0.5;
+
+        if radius < min_radius {
+            paint_flavor.mul_alpha((radius / min_radius).powi(2));
+        }
+
+        let radius = radius.max(min_radius);
+        let inner = radius - fringe_width * 0.5;
+        let outer = radius + fringe_width * 0.5;
+
+        let angle_step = (outer / (outer + self.tess_tol)).acos() * 2.0;
+        let segments = ((TAU / angle_step).ceil() as usize).clamp(8, 128);
+        let directions: Vec<(f32, f32)> = (0..=segments)
+            .map(|i| {
+                let angle = i as f32 / segments as f32 * TAU;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+
+        let triangles_per_point = if fringe_width > 0.0 { 3 } else { 1 };
+        let mut verts = Vec::with_capacity(points.len() * segments * triangles_per_point * 3);
+
+        for &(x, y) in points {
+            let (cx, cy) = transform.transform_point(x, y);
+            let vertex = |(dx, dy): (f32, f32), r: f32, u: f32| Vertex::new(cx + dx * r, cy + dy * r, u, 1.0);
+
+            for pair in directions.windows(2) {
+                let (inner0, inner1) = (vertex(pair[0], inner, 0.5), vertex(pair[1], inner, 0.5));
+                verts.extend_from_slice(&[Vertex::new(cx, cy, 0.5, 1.0), inner0, inner1]);
+
+                if fringe_width > 0.0 {
+                    let (outer0, outer1) = (vertex(pair[0], outer, 0.0), vertex(pair[1], outer, 0.0));
+                    verts.extend_from_slice(&[inner0, outer0, inner1, inner1, outer0, outer1]);
+                }
+            }
+        }
+
+        self.convert_color_space(&mut paint_flavor);
+        self.render_triangles(&verts, &transform, &paint_flavor, GlyphTexture::None);
+    }
+
+    /// Strokes each of the line segments, given as (x0, y0, x1, y1), all in a single draw call.
+    ///
+    /// All segments share the line width and caps of the paint, which makes this much cheaper than
+    /// stroking a path per segment, like for the grid lines of a chart. Segments are independent, so
+    /// there are no joins, round caps are drawn as square caps and the line dash is ignored.
+    pub fn draw_lines(&mut self, lines: &[(f32, f32, f32, f32)], paint: &Paint) {
+        let transform = self.state().transform;
+        let stroke = &paint.stroke;
+        let mut line_width = stroke.device_width(&transform);
+
+        if lines.is_empty() || line_width <= 0.0 {
+            return;
+        }
+
+        let mut paint_flavor = paint.flavor.clone();
+        paint_flavor.mul_alpha(self.state().alpha);
+
+        // Same coverage emulation for thin lines as in stroke_path
+        if line_width < self.fringe_width {
+            let alpha = line_width / self.fringe_width;
+            paint_flavor.mul_alpha(alpha * alpha);
+            line_width = self.fringe_width;
+        }
+
+        let fringe_width = if paint.shape_anti_alias { self.fringe_width } else { 0.0 };
+        let half_width = line_width * 0.5;
+        let cap_extent = |cap: LineCap| if cap == LineCap::Butt { 0.0 } else { half_width };
+        let (start_extent, end_extent) = (cap_extent(stroke.line_cap_start), cap_extent(stroke.line_cap_end));
+
+        // Offsets across the line and along it, relative to the start point, with the anti-aliased
+        // fringe centered on the outline. u fades out across the line and v along it.
+        let across = [
+            (-half_width - fringe_width * 0.5, 0.0),
+            (-half_width + fringe_width * 0.5, 0.5),
+            (half_width - fringe_width * 0.5, 0.5),
+            (half_width + fringe_width * 0.5, 0.0),
+        ];
+
+        let mut verts = Vec::with_capacity(lines.len() * if fringe_width > 0.0 { 54 } else { 6 });
+
+        for &(x0, y0, x1, y1) in lines {
+            let (x0, y0) = transform.transform_point(x0, y0);
+            let (x1, y1) = transform.transform_point(x1, y1);
+
+            let length = ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt();
+            let (dx, dy) = if length > 0.0 {
+                ((x1 - x0) / length, (y1 - y0) / length)
+            } else {
+                (1.0, 0.0)
+            };
+
+            let along = [
+                (-start_extent - fringe_width * 0.5, 0.0),
+                (-start_extent + fringe_width * 0.5, 1.0),
+                (length + end_extent - fringe_width * 0.5, 1.0),
+                (length + end_extent + fringe_width * 0.5, 0.0),
+            ];
+
+            let vertex =
+                |(a, u): (f32, f32), (b, v): (f32, f32)| Vertex::new(x0 + dx * b - dy * a, y0 + dy * b + dx * a, u, v);
+
+            // Without anti-aliasing only the inner quad is drawn, otherwise it's surrounded by the fringe
+            let range = if fringe_width > 0.0 { 0..3 } else { 1..2 };
+
+            for i in range.clone() {
+                for j in range.clone() {
+                    let v00 = vertex(across[i], along[j]);
+                    let v10 = vertex(across[i + 1], along[j]);
+                    let v01 = vertex(across[i], along[j + 1]);
+                    let v11 = vertex(across[i + 1], along[j + 1]);
+                    verts.extend_from_slice(&[v00, v10, v11, v00, v11, v01]);
+                }
+            }
+        }
+
+        self.convert_color_space(&mut paint_flavor);
+        self.render_triangles(&verts, &transform, &paint_flavor, GlyphTexture::None);
+    }
+
     fn stroke_path_internal(
         &mut self,
         path: &Path,
@@ -1007,8 +2069,20 @@ where
         let mut paint_flavor = paint_flavor.clone();
         let transform = self.state().transform;
 
-        // The path cache saves a flattened and transformed version of the path.
-        let mut path_cache = path.cache(&transform, self.tess_tol, self.dist_tol);
+        let dashed_path = stroke.dashed_path(path, &transform, self.tess_tol);
+        let path = dashed_path.as_deref().unwrap_or(path);
+
+        // The path cache saves a flattened and transformed version of the path. Pixel snapping moves the
+        // points, so it works on a copy that doesn't end up in the cache.
+        let mut snapped_cache;
+        let mut cached;
+        let path_cache = if self.pixel_snapping {
+            snapped_cache = PathCache::new(path.verbs(), &transform, self.tess_tol, self.dist_tol);
+            &mut snapped_cache
+        } else {
+            cached = path.cache(&transform, self.tess_tol, self.dist_tol);
+            &mut *cached
+        };
 
         // Early out if path is outside the canvas bounds
         if path_cache.bounds.maxx < 0.0
@@ -1019,14 +2093,12 @@ where
             return;
         }
 
-        let scissor = self.state().scissor;
-
         // Scale stroke width by current transform scale.
         // Note: I don't know why the original author clamped the max stroke width to 200, but it didn't
         // look correct when zooming in. There was probably a good reson for doing so and I may have
         // introduced a bug by removing the upper bound.
         //paint.set_stroke_width((paint.stroke_width() * transform.average_scale()).max(0.0).min(200.0));
-        let mut line_width = (stroke.line_width * transform.average_scale()).max(0.0);
+        let mut line_width = stroke.device_width(&transform);
 
         if line_width < self.fringe_width {
             // If the stroke width is less than pixel size, use alpha to emulate coverage.
@@ -1039,6 +2111,11 @@ where
 
         // Apply global alpha
         paint_flavor.mul_alpha(self.state().alpha);
+        self.convert_color_space(&mut paint_flavor);
+
+        if self.pixel_snapping {
+            path_cache.snap_to_pixels(line_width);
+        }
 
         // Calculate stroke vertices.
         // expand_stroke will fill path_cache.contours[].stroke with vertex data for the GPU
@@ -1053,11 +2130,27 @@ where
             self.tess_tol,
         );
 
+        self.append_stroke_cmd(path_cache, &paint_flavor, line_width, stroke.stencil_strokes, None);
+    }
+
+    // Appends the command for a stroke expanded with `expand_stroke`, using the current transform for the
+    // paint. `vertex_transform` maps vertices that weren't tessellated for the current transform.
+    fn append_stroke_cmd(
+        &mut self,
+        path_cache: &PathCache,
+        paint_flavor: &PaintFlavor,
+        line_width: f32,
+        stencil_strokes: bool,
+        vertex_transform: Option<&Transform2D>,
+    ) {
+        let transform = self.state().transform;
+        let scissor = self.state().scissor;
+
         // GPU uniforms
         let params = Params::new(
             &self.images,
             &transform,
-            &paint_flavor,
+            paint_flavor,
             &Default::default(),
             &scissor,
             line_width,
@@ -1065,11 +2158,11 @@ where
             -1.0,
         );
 
-        let flavor = if stroke.stencil_strokes {
+        let flavor = if stencil_strokes {
             let params2 = Params::new(
                 &self.images,
                 &transform,
-                &paint_flavor,
+                paint_flavor,
                 &Default::default(),
                 &scissor,
                 line_width,
@@ -1089,7 +2182,7 @@ where
         let mut cmd = Command::new(flavor);
         cmd.composite_operation = self.state().composite_operation;
 
-        if let PaintFlavor::Image { id, .. } = paint_flavor {
+        if let &PaintFlavor::Image { id, .. } = paint_flavor {
             cmd.image = Some(id);
         } else if let Some(paint::GradientColors::MultiStop { stops }) = paint_flavor.gradient_colors() {
             cmd.image = self
@@ -1108,7 +2201,7 @@ where
 
             if !contour.stroke.is_empty() {
                 drawable.stroke_verts = Some((offset, contour.stroke.len()));
-                self.verts.extend_from_slice(&contour.stroke);
+                self.push_verts(&contour.stroke, vertex_transform);
                 offset += contour.stroke.len();
             }
 
@@ -1118,6 +2211,17 @@ where
         self.append_cmd(cmd);
     }
 
+    // Appends vertices to the vertex buffer, mapped by the transform if there is one
+    fn push_verts(&mut self, verts: &[Vertex], transform: Option<&Transform2D>) {
+        match transform {
+            Some(transform) => self.verts.extend(verts.iter().map(|vert| {
+                let (x, y) = transform.transform_point(vert.x, vert.y);
+                Vertex::new(x, y, vert.u, vert.v)
+            })),
+            None => self.verts.extend_from_slice(verts),
+        }
+    }
+
     fn render_unclipped_image_blit(&mut self, target_rect: &Rect, transform: &Transform2D, paint_flavor: &PaintFlavor) {
         let scissor = self.state().scissor;
 
@@ -1182,18 +2286,162 @@ where
         self.verts.extend_from_slice(&verts);
     }
 
+    /// Draws the image stretched into the rectangle at (x, y) while keeping its corners unscaled.
+    ///
+    /// The `insets` give the size of the left, top, right and bottom border of the image in pixels.
+    /// They split the image into nine regions: the corners are drawn at their original size, the
+    /// edges are stretched along the border and the center is stretched in both directions. If the
+    /// rectangle is too small for the corners, they are scaled down to fit.
+    ///
+    /// The regions are drawn without anti-aliasing, so that no seams show between them.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_image_nine_slice(
+        &mut self,
+        id: ImageId,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        insets: [f32; 4],
+    ) -> Result<(), ErrorKind> {
+        let (image_width, image_height) = self.image_size(id)?;
+        let (image_width, image_height) = (image_width as f32, image_height as f32);
+
+        let [left, top, right, bottom] = insets.map(|inset| inset.max(0.0));
+        let left = left.min(image_width);
+        let right = right.min(image_width - left);
+        let top = top.min(image_height);
+        let bottom = bottom.min(image_height - top);
+
+        let fit_x = (width / (left + right)).min(1.0);
+        let fit_y = (height / (top + bottom)).min(1.0);
+
+        let src_x = [0.0, left, image_width - right, image_width];
+        let src_y = [0.0, top, image_height - bottom, image_height];
+        let dst_x = [x, x + left * fit_x, x + width - right * fit_x, x + width];
+        let dst_y = [y, y + top * fit_y, y + height - bottom * fit_y, y + height];
+
+        for row in 0..3 {
+            for col in 0..3 {
+                let (src_w, src_h) = (src_x[col + 1] - src_x[col], src_y[row + 1] - src_y[row]);
+                let (dst_w, dst_h) = (dst_x[col + 1] - dst_x[col], dst_y[row + 1] - dst_y[row]);
+
+                self.draw_image_source_rect(
+                    id,
+                    [src_x[col], src_y[row], src_w, src_h],
+                    [dst_x[col], dst_y[row], dst_w, dst_h],
+                    1.0,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws the `src` region of the image stretched into the `dst` rectangle, with `alpha` applied.
+    ///
+    /// Both rectangles are given as `[x, y, width, height]`. The source rectangle is in image pixels and
+    /// gets clamped to the image bounds; nothing is drawn if either rectangle ends up empty. The region is
+    /// drawn without anti-aliasing, so the fringe doesn't pick up pixels from outside of it, like the
+    /// neighbouring cells of a sprite atlas.
+    pub fn draw_image_source_rect(
+        &mut self,
+        id: ImageId,
+        src: [f32; 4],
+        dst: [f32; 4],
+        alpha: f32,
+    ) -> Result<(), ErrorKind> {
+        let (image_width, image_height) = self.image_size(id)?;
+        let (image_width, image_height) = (image_width as f32, image_height as f32);
+
+        let src_x = src[0].clamp(0.0, image_width);
+        let src_y = src[1].clamp(0.0, image_height);
+        let src_w = (src[0] + src[2]).clamp(0.0, image_width) - src_x;
+        let src_h = (src[1] + src[3]).clamp(0.0, image_height) - src_y;
+        let [dst_x, dst_y, dst_w, dst_h] = dst;
+
+        if src_w <= 0.0 || src_h <= 0.0 || dst_w <= 0.0 || dst_h <= 0.0 {
+            return Ok(());
+        }
+
+        // Position and size the whole image so that the region lands on its destination
+        let (scale_x, scale_y) = (dst_w / src_w, dst_h / src_h);
+        let paint = Paint::image(
+            id,
+            dst_x - src_x * scale_x,
+            dst_y - src_y * scale_y,
+            image_width * scale_x,
+            image_height * scale_y,
+            0.0,
+            alpha,
+        )
+        .with_anti_alias(false);
+
+        let mut path = Path::new();
+        path.rect(dst_x, dst_y, dst_w, dst_h);
+        self.fill_path(&path, &paint);
+
+        Ok(())
+    }
+
+    /// Draws a soft shadow of the rounded rectangle at (x, y) with the given size and corner radius.
+    ///
+    /// The shadow fades out like the rectangle blurred with a gaussian filter whose standard deviation is
+    /// half of `blur`, similar to CSS `box-shadow`. Unlike [`Canvas::blur_image`] no image is rendered,
+    /// the falloff is computed analytically while filling. The shadow is drawn with the current transform,
+    /// global alpha and composite operation.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_box_shadow(&mut self, x: f32, y: f32, width: f32, height: f32, radius: f32, blur: f32, color: Color) {
+        let mut path = Path::new();
+
+        if blur <= 0.0 {
+            path.rounded_rect(x, y, width, height, radius);
+            self.fill_path(&path, &Paint::color(color));
+            return;
+        }
+
+        // The shadow extends three standard deviations beyond the rectangle
+        let spread = 1.5 * blur;
+        path.rect(x - spread, y - spread, width + 2.0 * spread, height + 2.0 * spread);
+
+        self.fill_path(&path, &Paint::box_shadow(x, y, width, height, radius, blur, color));
+    }
+
     // Text
 
-    /// Adds a font file to the canvas
+    /// Adds a font file to the canvas by reading it from the specified path.
+    ///
+    /// This is a convenience over [`Canvas::add_font_mem`] for fonts that aren't embedded in the
+    /// binary. Failing to read the file results in [`ErrorKind::IoError`].
     pub fn add_font<P: AsRef<FilePath>>(&mut self, file_path: P) -> Result<FontId, ErrorKind> {
         self.text_context.borrow_mut().add_font_file(file_path)
     }
 
     /// Adds a font to the canvas by reading it from the specified chunk of memory.
+    ///
+    /// Without the `text` feature there is nothing to parse fonts with, so this and the other font
+    /// and text functions return [`ErrorKind::TextNotSupported`].
     pub fn add_font_mem(&mut self, data: &[u8]) -> Result<FontId, ErrorKind> {
         self.text_context.borrow_mut().add_font_mem(data)
     }
 
+    /// Adds the face with the given index from a font collection (.ttc/.otc) in memory to the canvas.
+    ///
+    /// Use [`Canvas::count_faces`] to find out how many faces the collection contains. For plain
+    /// font files, use 0 as index.
+    pub fn add_font_mem_face(&mut self, data: &[u8], face_index: u32) -> Result<FontId, ErrorKind> {
+        self.text_context.borrow_mut().add_font_mem_with_index(data, face_index)
+    }
+
+    /// Returns the number of faces in the font data.
+    ///
+    /// This is the number of faces in a font collection, 1 for a plain font file and 0 if the data
+    /// isn't a font. The fonts registered with the canvas are not involved, so this can be called before
+    /// creating one. Without the `text` feature this is always 0.
+    pub fn count_faces(data: &[u8]) -> u32 {
+        text::count_faces(data)
+    }
+
     /// Adds all .ttf files from a directory
     pub fn add_font_dir<P: AsRef<FilePath>>(&mut self, dir_path: P) -> Result<Vec<FontId>, ErrorKind> {
         self.text_context.borrow_mut().add_font_dir(dir_path)
@@ -1212,6 +2460,8 @@ where
         let mut text_settings = paint.text.clone();
         text_settings.font_size *= scale;
         text_settings.letter_spacing *= scale;
+        text_settings.word_spacing *= scale;
+        text_settings.tab_width *= scale;
 
         let scale = self.font_scale() * self.device_px_ratio;
         let invscale = 1.0 / scale;
@@ -1225,6 +2475,55 @@ where
             })
     }
 
+    /// Shapes the text with the font settings of the paint, without drawing it.
+    ///
+    /// The returned glyphs carry their glyph ids, advances, offsets and the byte offset of the
+    /// cluster in `text` they were shaped from, which is what caret placement and selection
+    /// across ligatures and complex scripts need. Positions are relative to an origin at 0, 0 on
+    /// the alphabetic baseline, in canvas units. They are not rounded to whole pixels, that is left to
+    /// [`Canvas::fill_glyph_run`].
+    pub fn shape_text<S: AsRef<str>>(&self, text: S, paint: &Paint) -> Result<ShapedRun, ErrorKind> {
+        let text = text.as_ref();
+        let scale = self.font_scale() * self.device_px_ratio;
+        let invscale = 1.0 / scale;
+
+        let mut text_settings = paint.text.clone();
+        text_settings.font_size *= scale;
+        text_settings.letter_spacing *= scale;
+        text_settings.word_spacing *= scale;
+        text_settings.tab_width *= scale;
+        text_settings.text_align = Align::Left;
+        text_settings.text_baseline = Baseline::Alphabetic;
+        // Glyphs are snapped to whole pixels when the run is drawn, where its origin is known
+        text_settings.subpixel_positioning = true;
+
+        let mut metrics = self
+            .text_context
+            .borrow_mut()
+            .measure_text(0.0, 0.0, text, &text_settings)?;
+        metrics.scale(invscale);
+
+        for glyph in &mut metrics.glyphs {
+            glyph.advance_x *= invscale;
+            glyph.advance_y *= invscale;
+            glyph.offset_x *= invscale;
+            glyph.offset_y *= invscale;
+            glyph.bearing_x *= invscale;
+            glyph.bearing_y *= invscale;
+        }
+
+        Ok(ShapedRun::new(metrics, text.len()))
+    }
+
+    /// Returns the registered font of the given family that best matches the requested weight and style.
+    ///
+    /// The family name is compared case-insensitively. Faces with the requested style (italic or
+    /// oblique vs. upright) are preferred over any other, and among those the face with the closest
+    /// weight wins. Returns `None` if no font of that family is registered.
+    pub fn find_font(&self, family: &str, weight: u16, italic: bool) -> Option<FontId> {
+        self.text_context.borrow().find_font_by_style(family, weight, italic)
+    }
+
     /// Returns font metrics for a particular Paint.
     pub fn measure_font(&self, paint: &Paint) -> Result<FontMetrics, ErrorKind> {
         let scale = self.font_scale() * self.device_px_ratio;
@@ -1234,6 +2533,85 @@ where
             .measure_font(paint.text.font_size * scale, &paint.text.font_ids)
     }
 
+    /// Returns the metrics of a registered font at the given font size, or `None` if there is no
+    /// font with this id.
+    ///
+    /// Unlike [`Canvas::measure_font`], the metrics are not affected by the current transform or
+    /// the device pixel ratio.
+    pub fn font_metrics(&self, id: FontId, size: f32) -> Option<FontMetrics> {
+        self.text_context.borrow().font(id).map(|font| font.metrics(size))
+    }
+
+    /// Returns the full human-readable name of a registered font, like "Roboto Bold Italic", or
+    /// `None` if there is no font with this id or it has no name.
+    pub fn font_name(&self, id: FontId) -> Option<String> {
+        self.text_context.borrow().font(id).and_then(|font| font.full_name())
+    }
+
+    /// Returns the family name of a registered font, like "Roboto", or `None` if there is no font
+    /// with this id or it has no name.
+    ///
+    /// This is the name [`Canvas::find_font`] matches against.
+    pub fn font_family(&self, id: FontId) -> Option<String> {
+        self.text_context
+            .borrow()
+            .font(id)
+            .and_then(|font| font.family().map(str::to_owned))
+    }
+
+    /// Rasterizes the outline of a glyph at the given font size into an 8-bit coverage bitmap.
+    ///
+    /// `codepoint` is the index of the glyph in the font, like [`ShapedGlyph::codepoint`] of shaped text.
+    /// The returned metrics describe the bitmap, which has a pixel of room around the anti-aliased edge:
+    /// its size and the position of its top left corner relative to the glyph origin. Returns `None` if
+    /// the font doesn't exist or the glyph has no outline, like spaces or color bitmap glyphs.
+    pub fn rasterize_glyph(&self, font_id: FontId, codepoint: u32, size: f32) -> Option<(GlyphMetrics, ImgVec<u8>)> {
+        let (path, metrics, scale) = {
+            let text_context = self.text_context.borrow();
+            let font = text_context.font(font_id)?;
+            let face = font.face_ref();
+            let glyph = font.glyph(&face, codepoint as u16)?;
+
+            (glyph.path.clone()?, glyph.metrics, font.scale(size))
+        };
+
+        let left = (metrics.bearing_x * scale).floor() - 1.0;
+        let top = (metrics.bearing_y * scale).ceil() + 1.0;
+        let right = ((metrics.bearing_x + metrics.width) * scale).ceil() + 1.0;
+        let bottom = ((metrics.bearing_y - metrics.height) * scale).floor() - 1.0;
+        let (width, height) = ((right - left) as u32, (top - bottom) as u32);
+
+        let mut canvas = Canvas::new(renderer::Software::new()).ok()?;
+        canvas.set_size(width, height, 1.0);
+        canvas.clear(Color::rgba(0, 0, 0, 0));
+
+        // Glyph outlines are in font units with y pointing up
+        let mut transform = Transform2D::identity();
+        transform.scale(scale, -scale);
+        let mut offset = Transform2D::identity();
+        offset.translate(-left, top);
+        transform.multiply(&offset);
+
+        canvas.set_transform(&transform);
+        canvas.fill_path(&path, &Paint::color(Color::white()).with_fill_rule(FillRule::EvenOdd));
+
+        let coverage = canvas.screenshot().ok()?;
+        let coverage = ImgVec::new(
+            coverage.pixels().map(|pixel| pixel.a).collect(),
+            coverage.width(),
+            coverage.height(),
+        );
+
+        let metrics = GlyphMetrics {
+            width: width as f32,
+            height: height as f32,
+            bearing_x: left,
+            bearing_y: top,
+        };
+
+        Some((metrics, coverage))
+    }
+
     /// Returns the maximum index-th byte of text that will fit inside max_width.
     ///
     /// The retuned index will always lie at the start and/or end of a UTF-8 code point sequence or at the start or end of the text
@@ -1243,6 +2621,8 @@ where
         let mut text_settings = paint.text.clone();
         text_settings.font_size *= scale;
         text_settings.letter_spacing *= scale;
+        text_settings.word_spacing *= scale;
+        text_settings.tab_width *= scale;
 
         let max_width = max_width * scale;
 
@@ -1263,6 +2643,8 @@ where
         let mut text_settings = paint.text.clone();
         text_settings.font_size *= scale;
         text_settings.letter_spacing *= scale;
+        text_settings.word_spacing *= scale;
+        text_settings.tab_width *= scale;
 
         let max_width = max_width * scale;
 
@@ -1272,6 +2654,9 @@ where
     }
 
     /// Fills the provided string with the specified Paint.
+    ///
+    /// Returns the metrics of the drawn text, [`TextMetrics::next_x`] gives the position to continue
+    /// drawing at without measuring the text separately.
     pub fn fill_text<S: AsRef<str>>(
         &mut self,
         x: f32,
@@ -1282,6 +2667,103 @@ where
         self.draw_text(x, y, text.as_ref(), paint, RenderMode::Fill)
     }
 
+    /// Fills the provided string like [`Canvas::fill_text`], but if it's wider than `max_width`, as
+    /// much of it as fits is drawn followed by an ellipsis ("…").
+    ///
+    /// Nothing is drawn if not even the ellipsis fits. Returns the metrics of the text that was drawn.
+    pub fn fill_text_ellipsized<S: AsRef<str>>(
+        &mut self,
+        x: f32,
+        y: f32,
+        max_width: f32,
+        text: S,
+        paint: &Paint,
+    ) -> Result<TextMetrics, ErrorKind> {
+        const ELLIPSIS: &str = "\u{2026}";
+
+        let text = text.as_ref();
+        let metrics = self.measure_text(x, y, text, paint)?;
+
+        if metrics.width() <= max_width {
+            return self.fill_text(x, y, text, paint);
+        }
+
+        let available = max_width - self.measure_text(x, y, ELLIPSIS, paint)?.width();
+
+        if available < 0.0 {
+            return self.draw_text(x, y, "", paint, RenderMode::Fill);
+        }
+
+        // Cut the text before the first character, in logical order, whose glyph ends past the available width
+        let mut pen_x = metrics.x;
+        let mut end = text.len();
+
+        for glyph in &metrics.glyphs {
+            let glyph_x = glyph.x - glyph.offset_x - glyph.bearing_x;
+            pen_x = pen_x.max(glyph_x + glyph.advance_x);
+
+            if pen_x - metrics.x > available {
+                end = end.min(glyph.byte_index);
+            }
+        }
+
+        let truncated = format!("{}{ELLIPSIS}", text[..end].trim_end());
+
+        self.fill_text(x, y, truncated, paint)
+    }
+
+    /// Fills glyphs that were shaped before, like the ones of a [`ShapedRun`] returned by [`Canvas::shape_text`],
+    /// with the origin of the run at (x, y).
+    ///
+    /// This skips shaping, so text that's drawn every frame only needs to be shaped once. Each glyph is drawn
+    /// with its own font at the font size of the paint, which has to be the size the glyphs were shaped with.
+    /// Text shadows and decorations of the paint aren't drawn.
+    pub fn fill_glyph_run(&mut self, x: f32, y: f32, glyphs: &[ShapedGlyph], paint: &Paint) -> Result<(), ErrorKind> {
+        self.draw_glyph_run(x, y, glyphs, paint, RenderMode::Fill)
+    }
+
+    fn draw_glyph_run(
+        &mut self,
+        x: f32,
+        y: f32,
+        glyphs: &[ShapedGlyph],
+        paint: &Paint,
+        render_mode: RenderMode,
+    ) -> Result<(), ErrorKind> {
+        let scale = self.font_scale() * self.device_px_ratio;
+        let glyphs = glyphs
+            .iter()
+            .map(|glyph| {
+                let mut glyph = ShapedGlyph {
+                    x: (x + glyph.x) * scale,
+                    y: (y + glyph.y) * scale,
+                    width: glyph.width * scale,
+                    height: glyph.height * scale,
+                    advance_x: glyph.advance_x * scale,
+                    advance_y: glyph.advance_y * scale,
+                    offset_x: glyph.offset_x * scale,
+                    offset_y: glyph.offset_y * scale,
+                    bearing_x: glyph.bearing_x * scale,
+                    bearing_y: glyph.bearing_y * scale,
+                    ..*glyph
+                };
+
+                // Like the text layout, round the pen position and the baseline to whole pixels
+                if !paint.text.subpixel_positioning {
+                    glyph.x = (glyph.x - glyph.bearing_x).round() + glyph.bearing_x;
+                    glyph.y = (glyph.y + glyph.bearing_y - glyph.offset_y).round() + glyph.offset_y - glyph.bearing_y;
+                }
+
+                glyph
+            })
+            .collect();
+
+        let layout = TextMetrics::from_glyphs(glyphs);
+        let font_size = paint.text.font_size * scale;
+
+        self.draw_glyph_layout(&layout, paint, &paint.stroke, font_size, scale, render_mode)
+    }
+
     /// Strokes the provided string with the specified Paint.
     pub fn stroke_text<S: AsRef<str>>(
         &mut self,
@@ -1293,42 +2775,107 @@ where
         self.draw_text(x, y, text.as_ref(), paint, RenderMode::Stroke)
     }
 
+    /// Fills the provided string along the path, starting `offset` units from the start of the path.
+    ///
+    /// Each glyph is centered on the path at its position in the line of text and rotated to follow
+    /// the direction of the path there, with the baseline of the paint on the path. The text align
+    /// of the paint aligns the text relative to `offset`. Glyphs that don't fit on the path are left out,
+    /// sub-paths are followed one after the other.
+    ///
+    /// The text is shaped as a whole before its glyphs are placed, so kerning, ligatures and joined
+    /// scripts look the same as with [`Canvas::fill_text`]. Text shadows and decorations of the paint
+    /// aren't drawn.
+    pub fn fill_text_on_path<S: AsRef<str>>(
+        &mut self,
+        text: S,
+        path: &Path,
+        paint: &Paint,
+        offset: f32,
+    ) -> Result<(), ErrorKind> {
+        self.draw_text_on_path(text.as_ref(), path, paint, offset, RenderMode::Fill)
+    }
+
+    /// Strokes the provided string along the path, see [`Canvas::fill_text_on_path`].
+    pub fn stroke_text_on_path<S: AsRef<str>>(
+        &mut self,
+        text: S,
+        path: &Path,
+        paint: &Paint,
+        offset: f32,
+    ) -> Result<(), ErrorKind> {
+        self.draw_text_on_path(text.as_ref(), path, paint, offset, RenderMode::Stroke)
+    }
+
+    fn draw_text_on_path(
+        &mut self,
+        text: &str,
+        path: &Path,
+        paint: &Paint,
+        offset: f32,
+        render_mode: RenderMode,
+    ) -> Result<(), ErrorKind> {
+        // The text is shaped once, so kerning, ligatures and joining of scripts like Arabic are kept
+        let metrics = self.measure_text(0.0, 0.0, text, paint)?;
+
+        // Glyphs of the same cluster, like a base character with combining marks, are placed together
+        let mut clusters: Vec<(Range<usize>, f32, f32)> = Vec::new();
+
+        for (index, glyph) in metrics.glyphs.iter().enumerate() {
+            let pen_x = glyph.x - glyph.offset_x - glyph.bearing_x;
+
+            match clusters.last_mut() {
+                Some((range, _, advance)) if metrics.glyphs[range.start].byte_index == glyph.byte_index => {
+                    range.end = index + 1;
+                    *advance += glyph.advance_x;
+                }
+                _ => clusters.push((index..index + 1, pen_x, glyph.advance_x)),
+            }
+        }
+
+        let scale = self.state().transform.average_scale().max(f32::EPSILON);
+        let measure = PathMeasure::new(path, self.tess_tol / scale);
+
+        for (range, pen_x, advance) in clusters {
+            let center = pen_x + advance / 2.0;
+
+            let (position, angle) = match measure.sample(offset + center) {
+                Some(sample) => sample,
+                None => continue,
+            };
+
+            // Rotate the line of text around the center of the cluster, keeping the glyph positions as
+            // they were shaped, so that a straight path draws the text like fill_text does
+            let origin_x = position.x - angle.cos() * center;
+            let origin_y = position.y - angle.sin() * center;
+
+            self.save_with(|canvas| {
+                canvas.translate(origin_x, origin_y);
+                canvas.rotate(angle);
+                canvas.draw_glyph_run(0.0, 0.0, &metrics.glyphs[range], paint, render_mode)
+            })?;
+        }
+
+        Ok(())
+    }
+
     /// Dispatch an explicit set of GlyphDrawCommands to the renderer. Use this only if you are
     /// using a custom font rasterizer/layout.
     pub fn draw_glyph_commands(&mut self, draw_commands: GlyphDrawCommands, paint: &Paint, scale: f32) {
         let transform = self.state().transform;
-        let invscale = 1.0 / scale;
-        let create_vertices = |quads: &Vec<text::Quad>| {
-            let mut verts = Vec::with_capacity(quads.len() * 6);
-
-            for quad in quads {
-                let (p0, p1) = transform.transform_point(quad.x0 * invscale, quad.y0 * invscale);
-                let (p2, p3) = transform.transform_point(quad.x1 * invscale, quad.y0 * invscale);
-                let (p4, p5) = transform.transform_point(quad.x1 * invscale, quad.y1 * invscale);
-                let (p6, p7) = transform.transform_point(quad.x0 * invscale, quad.y1 * invscale);
-
-                verts.push(Vertex::new(p0, p1, quad.s0, quad.t0));
-                verts.push(Vertex::new(p4, p5, quad.s1, quad.t1));
-                verts.push(Vertex::new(p2, p3, quad.s1, quad.t0));
-                verts.push(Vertex::new(p0, p1, quad.s0, quad.t0));
-                verts.push(Vertex::new(p6, p7, quad.s0, quad.t1));
-                verts.push(Vertex::new(p4, p5, quad.s1, quad.t1));
-            }
-            verts
-        };
 
         // Apply global alpha
         let mut paint_flavor = paint.flavor.clone();
         paint_flavor.mul_alpha(self.state().alpha);
+        self.convert_color_space(&mut paint_flavor);
 
         for cmd in draw_commands.alpha_glyphs {
-            let verts = create_vertices(&cmd.quads);
+            let verts = glyph_vertices(&transform, &cmd.quads, scale);
 
             self.render_triangles(&verts, &transform, &paint_flavor, GlyphTexture::AlphaMask(cmd.image_id));
         }
 
         for cmd in draw_commands.color_glyphs {
-            let verts = create_vertices(&cmd.quads);
+            let verts = glyph_vertices(&transform, &cmd.quads, scale);
 
             self.render_triangles(
                 &verts,
@@ -1349,6 +2896,10 @@ where
         paint: &Paint,
         render_mode: RenderMode,
     ) -> Result<TextMetrics, ErrorKind> {
+        if let Some(shadow) = paint.text.shadow {
+            self.draw_text_shadow(x, y, text, paint, render_mode, shadow)?;
+        }
+
         let scale = self.font_scale() * self.device_px_ratio;
         let invscale = 1.0 / scale;
 
@@ -1358,6 +2909,8 @@ where
         let mut text_settings = paint.text.clone();
         text_settings.font_size *= scale;
         text_settings.letter_spacing *= scale;
+        text_settings.word_spacing *= scale;
+        text_settings.tab_width *= scale;
 
         let mut layout = text::shape(
             x * scale,
@@ -1371,35 +2924,226 @@ where
 
         // TODO: Early out if text is outside the canvas bounds, or maybe even check for each character in layout.
 
+        self.draw_glyph_layout(&layout, paint, &stroke, text_settings.font_size, scale, render_mode)?;
+
+        layout.scale(invscale);
+
+        if text_settings.underline || text_settings.strikethrough {
+            self.draw_text_decorations(&layout, paint, render_mode);
+        }
+
+        Ok(layout)
+    }
+
+    // Draws glyphs laid out in device pixels, picking the glyph atlas or direct rendering
+    fn draw_glyph_layout(
+        &mut self,
+        layout: &TextMetrics,
+        paint: &Paint,
+        stroke: &StrokeSettings,
+        font_size: f32,
+        scale: f32,
+        render_mode: RenderMode,
+    ) -> Result<(), ErrorKind> {
         let bitmap_glyphs = layout.has_bitmap_glyphs();
-        let need_direct_rendering = text_settings.font_size > 92.0;
+        let need_direct_rendering = font_size > 92.0;
+
+        // Glyphs that don't fit into an atlas page, which can happen with a small maximum texture size,
+        // are rendered as paths instead
+        let fits_atlas = |result: Result<(), ErrorKind>| match result {
+            Ok(()) => Ok(true),
+            Err(ErrorKind::FontSizeTooLargeForAtlas) if !bitmap_glyphs => Ok(false),
+            Err(error) => Err(error),
+        };
+
+        let rendered_with_atlas =
+            if self.text_rendering == TextRendering::Sdf && render_mode == RenderMode::Fill && !bitmap_glyphs {
+                let atlas = self.sdf_atlas.clone();
+                fits_atlas(
+                    atlas
+                        .render_atlas(self, layout, font_size)
+                        .map(|draw_commands| self.draw_sdf_glyph_commands(draw_commands, paint, scale, font_size)),
+                )?
+            } else if need_direct_rendering && !bitmap_glyphs {
+                false
+            } else {
+                let atlas = if bitmap_glyphs && need_direct_rendering {
+                    self.ephemeral_glyph_atlas.get_or_insert_with(Default::default).clone()
+                } else {
+                    self.glyph_atlas.clone()
+                };
+
+                fits_atlas(
+                    atlas
+                        .render_atlas(
+                            self,
+                            layout,
+                            font_size,
+                            stroke.line_width,
+                            render_mode,
+                            paint.text.subpixel_positioning,
+                        )
+                        .map(|draw_commands| self.draw_glyph_commands(draw_commands, paint, scale)),
+                )?
+            };
 
-        if need_direct_rendering && !bitmap_glyphs {
+        if !rendered_with_atlas {
             text::render_direct(
                 self,
-                &layout,
+                layout,
                 &paint.flavor,
                 paint.shape_anti_alias,
-                &stroke,
-                text_settings.font_size,
+                stroke,
+                font_size,
                 render_mode,
-                invscale,
+                1.0 / scale,
             )?;
-        } else {
-            let atlas = if bitmap_glyphs && need_direct_rendering {
-                self.ephemeral_glyph_atlas.get_or_insert_with(Default::default).clone()
-            } else {
-                self.glyph_atlas.clone()
-            };
+        }
+
+        Ok(())
+    }
+
+    fn draw_sdf_glyph_commands(&mut self, draw_commands: Vec<DrawCommand>, paint: &Paint, scale: f32, font_size: f32) {
+        let transform = self.state().transform;
+
+        // Converts the distance stored in the field to coverage of a screen pixel
+        let field_scale = font_size / text::SDF_GLYPH_SIZE / scale * transform.average_scale();
+        let sdf_scale = 2.0 * text::SDF_SPREAD * field_scale;
+
+        let mut paint_flavor = paint.flavor.clone();
+        paint_flavor.mul_alpha(self.state().alpha);
+        self.convert_color_space(&mut paint_flavor);
+
+        for cmd in draw_commands {
+            let verts = glyph_vertices(&transform, &cmd.quads, scale);
 
-            let draw_commands =
-                atlas.render_atlas(self, &layout, text_settings.font_size, stroke.line_width, render_mode)?;
-            self.draw_glyph_commands(draw_commands, paint, scale);
+            self.render_triangles(
+                &verts,
+                &transform,
+                &paint_flavor,
+                GlyphTexture::Sdf(cmd.image_id, sdf_scale),
+            );
         }
+    }
 
-        layout.scale(invscale);
+    fn draw_text_shadow(
+        &mut self,
+        x: f32,
+        y: f32,
+        text: &str,
+        paint: &Paint,
+        render_mode: RenderMode,
+        shadow: TextShadow,
+    ) -> Result<(), ErrorKind> {
+        let mut shadow_paint = paint.clone();
+        shadow_paint.text.shadow = None;
+        shadow_paint.set_color(shadow.color);
 
-        Ok(layout)
+        let x = x + shadow.offset_x;
+        let y = y + shadow.offset_y;
+
+        if shadow.blur <= 0.0 {
+            return self.draw_text(x, y, text, &shadow_paint, render_mode).map(|_| ());
+        }
+
+        let sigma = (shadow.blur / 2.0).min(8.0);
+
+        // Bounds of the shadow on the render target, with room for the blur to spread out
+        let metrics = self.measure_text(x, y, text, &shadow_paint)?;
+        let transform = self.state().transform;
+        let corners = [
+            (metrics.x, metrics.y),
+            (metrics.x + metrics.width(), metrics.y),
+            (metrics.x + metrics.width(), metrics.y + metrics.height()),
+            (metrics.x, metrics.y + metrics.height()),
+        ]
+        .map(|(x, y)| transform.transform_point(x, y));
+
+        let padding = (3.0 * sigma + paint.stroke.device_width(&transform)).ceil() + 1.0;
+        let minx = corners.iter().fold(f32::MAX, |min, p| min.min(p.0)) - padding;
+        let miny = corners.iter().fold(f32::MAX, |min, p| min.min(p.1)) - padding;
+        let maxx = corners.iter().fold(f32::MIN, |max, p| max.max(p.0)) + padding;
+        let maxy = corners.iter().fold(f32::MIN, |max, p| max.max(p.1)) + padding;
+
+        let minx = minx.floor().max(0.0);
+        let miny = miny.floor().max(0.0);
+        let maxx = maxx.ceil().min(self.width() as f32);
+        let maxy = maxy.ceil().min(self.height() as f32);
+
+        if minx >= maxx || miny >= maxy {
+            return Ok(());
+        }
+
+        let (width, height) = ((maxx - minx) as usize, (maxy - miny) as usize);
+        let flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+        let text_image = self.create_image_empty(width, height, PixelFormat::Rgba8, flags)?;
+        self.temporary_images.push(text_image);
+
+        let previous_target = self.current_render_target;
+        self.set_render_target(RenderTarget::Image(text_image));
+        self.clear_rect(0, 0, width as u32, height as u32, Color::rgba(0, 0, 0, 0));
+
+        // Global alpha, the scissor and the composite operation apply when drawing the blurred layer
+        self.save();
+        self.reset();
+        let mut layer_transform = transform;
+        layer_transform.multiply(&Transform2D::new_translation(-minx, -miny));
+        self.state_mut().transform = layer_transform;
+        let drawn = self.draw_text(x, y, text, &shadow_paint, render_mode);
+        self.restore();
+
+        self.set_render_target(previous_target);
+        drawn?;
+
+        let blurred_image = self.blur_image(text_image, shadow.blur)?;
+        self.temporary_images.push(blurred_image);
+
+        let (width, height) = (width as f32, height as f32);
+        let mut path = Path::new();
+        path.rect(minx, miny, width, height);
+
+        self.save();
+        self.reset_transform();
+        self.fill_path(&path, &Paint::image(blurred_image, minx, miny, width, height, 0.0, 1.0));
+        self.restore();
+
+        Ok(())
+    }
+
+    // Draws the underline and strikethrough lines across the whole laid out text
+    fn draw_text_decorations(&mut self, layout: &TextMetrics, paint: &Paint, render_mode: RenderMode) {
+        let metrics = match self
+            .text_context
+            .borrow_mut()
+            .measure_font(paint.text.font_size, &paint.text.font_ids)
+        {
+            Ok(metrics) => metrics,
+            Err(_) => return,
+        };
+
+        let mut path = Path::new();
+
+        if paint.text.underline {
+            let y = layout.baseline - metrics.underline_position();
+            path.rect(layout.x, y, layout.width(), metrics.underline_thickness());
+        }
+
+        if paint.text.strikethrough {
+            let y = layout.baseline - metrics.strikeout_position();
+            path.rect(layout.x, y, layout.width(), metrics.strikeout_thickness());
+        }
+
+        match render_mode {
+            RenderMode::Fill => self.fill_path(&path, paint),
+            RenderMode::Stroke => self.stroke_path(&path, paint),
+        }
+    }
+
+    // Colors are given in sRGB, so they need to be converted when rendering in linear space
+    fn convert_color_space(&self, paint_flavor: &mut PaintFlavor) {
+        if self.color_space == ColorSpace::Linear {
+            paint_flavor.convert_to_linear();
+        }
     }
 
     fn render_triangles(
@@ -1479,6 +3223,237 @@ where
     }
 }
 
+// Returns the bounds of the transformed corners of the bounds
+fn transform_bounds(bounds: &Bounds, transform: &Transform2D) -> Bounds {
+    let mut result = Bounds::default();
+
+    for (x, y) in [
+        (bounds.minx, bounds.miny),
+        (bounds.maxx, bounds.miny),
+        (bounds.maxx, bounds.maxy),
+        (bounds.minx, bounds.maxy),
+    ] {
+        let (x, y) = transform.transform_point(x, y);
+        result.minx = result.minx.min(x);
+        result.miny = result.miny.min(y);
+        result.maxx = result.maxx.max(x);
+        result.maxy = result.maxy.max(y);
+    }
+
+    result
+}
+
+fn fan_to_triangles(fan: &[Vertex], triangles: &mut Vec<Vertex>) {
+    if let Some((first, rest)) = fan.split_first() {
+        for pair in rest.windows(2) {
+            triangles.extend_from_slice(&[*first, pair[0], pair[1]]);
+        }
+    }
+}
+
+// Triangulates the area the closed rings cover under the fill rule, as non-overlapping trapezoids
+// between the horizontal lines through every vertex and every crossing of two edges. The crossings
+// are found while sweeping down, by only comparing edges that are next to each other in a band.
+fn fill_rule_to_triangles<'a>(
+    rings: impl Iterator<Item = &'a [Vertex]>,
+    fill_rule: FillRule,
+    triangles: &mut Vec<Vertex>,
+) {
+    struct Edge {
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        winding: i32,
+    }
+
+    impl Edge {
+        fn x_at(&self, y: f32) -> f32 {
+            self.x0 + (self.x1 - self.x0) * (y - self.y0) / (self.y1 - self.y0)
+        }
+    }
+
+    let mut edges = Vec::new();
+
+    for ring in rings {
+        for (i, a) in ring.iter().enumerate() {
+            let b = &ring[(i + 1) % ring.len()];
+
+            if a.y < b.y {
+                edges.push(Edge {
+                    x0: a.x,
+                    y0: a.y,
+                    x1: b.x,
+                    y1: b.y,
+                    winding: 1,
+                });
+            } else if a.y > b.y {
+                edges.push(Edge {
+                    x0: b.x,
+                    y0: b.y,
+                    x1: a.x,
+                    y1: a.y,
+                    winding: -1,
+                });
+            }
+        }
+    }
+
+    edges.sort_by(|a, b| a.y0.partial_cmp(&b.y0).unwrap_or(Ordering::Equal));
+
+    let mut ys: Vec<f32> = edges.iter().flat_map(|edge| [edge.y0, edge.y1]).collect();
+    ys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    ys.dedup();
+
+    // Sweeps down through the bands between the vertices, keeping the edges that span the current band
+    let mut active: Vec<&Edge> = Vec::new();
+    let mut next_edge = 0;
+    let mut next_y = 1;
+    let mut top = ys.first().copied().unwrap_or_default();
+
+    while next_y < ys.len() {
+        active.retain(|edge| edge.y1 > top);
+
+        while next_edge < edges.len() && edges[next_edge].y0 <= top {
+            if edges[next_edge].y1 > top {
+                active.push(&edges[next_edge]);
+            }
+            next_edge += 1;
+        }
+
+        // Ends the band early at the first crossing of two edges, which are neighbours right before it.
+        // Edges crossing within rounding distance of the top are swapped first, so their new neighbours
+        // get checked too.
+        let mut bottom = ys[next_y];
+
+        active.sort_by(|a, b| {
+            (a.x_at(top), a.x_at(bottom))
+                .partial_cmp(&(b.x_at(top), b.x_at(bottom)))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let mut swapped = true;
+
+        while swapped {
+            swapped = false;
+
+            for i in 1..active.len() {
+                let dtop = active[i - 1].x_at(top) - active[i].x_at(top);
+                let dbottom = active[i - 1].x_at(bottom) - active[i].x_at(bottom);
+
+                if dbottom > 0.0 {
+                    let y = top + (bottom - top) * dtop / (dtop - dbottom);
+
+                    if y <= top {
+                        active.swap(i - 1, i);
+                        swapped = true;
+                    } else if y < bottom {
+                        bottom = y;
+                    }
+                }
+            }
+        }
+
+        let middle = (top + bottom) * 0.5;
+        active.sort_by(|a, b| a.x_at(middle).partial_cmp(&b.x_at(middle)).unwrap_or(Ordering::Equal));
+
+        let mut winding = 0;
+        let mut left = None;
+
+        for edge in &active {
+            winding += edge.winding;
+
+            let inside = match fill_rule {
+                FillRule::NonZero => winding != 0,
+                FillRule::EvenOdd => winding % 2 != 0,
+            };
+
+            match (left, inside) {
+                (None, true) => left = Some(edge),
+                (Some(l), false) => {
+                    let tl = Vertex::new(l.x_at(top), top, 0.5, 1.0);
+                    let bl = Vertex::new(l.x_at(bottom), bottom, 0.5, 1.0);
+                    let tr = Vertex::new(edge.x_at(top), top, 0.5, 1.0);
+                    let br = Vertex::new(edge.x_at(bottom), bottom, 0.5, 1.0);
+                    triangles.extend_from_slice(&[tl, tr, br, tl, br, bl]);
+                    left = None;
+                }
+                _ => {}
+            }
+        }
+
+        if bottom >= ys[next_y] {
+            next_y += 1;
+        }
+
+        top = bottom;
+    }
+}
+
+fn strip_to_triangles(strip: &[Vertex], triangles: &mut Vec<Vertex>) {
+    for (i, tri) in strip.windows(3).enumerate() {
+        // Keep the winding of every other triangle consistent with the first one
+        if i % 2 == 0 {
+            triangles.extend_from_slice(tri);
+        } else {
+            triangles.extend_from_slice(&[tri[1], tri[0], tri[2]]);
+        }
+    }
+}
+
+// Appends two triangles covering a one pixel wide line from `a` to `b`
+fn push_line_quad(verts: &mut Vec<Vertex>, a: Vertex, b: Vertex) {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len = (dx * dx + dy * dy).sqrt();
+
+    if len < 1e-6 {
+        return;
+    }
+
+    let (nx, ny) = (-dy / len * 0.5, dx / len * 0.5);
+
+    let p0 = Vertex::new(a.x + nx, a.y + ny, 0.5, 1.0);
+    let p1 = Vertex::new(a.x - nx, a.y - ny, 0.5, 1.0);
+    let p2 = Vertex::new(b.x + nx, b.y + ny, 0.5, 1.0);
+    let p3 = Vertex::new(b.x - nx, b.y - ny, 0.5, 1.0);
+
+    verts.extend_from_slice(&[p0, p1, p2, p2, p1, p3]);
+}
+
+// Transforms glyph quads given in text layout space, which is scaled by `scale`, to two triangles each
+fn glyph_vertices(transform: &Transform2D, quads: &[text::Quad], scale: f32) -> Vec<Vertex> {
+    let invscale = 1.0 / scale;
+    let mut verts = Vec::with_capacity(quads.len() * 6);
+
+    for quad in quads {
+        let (p0, p1) = transform.transform_point(quad.x0 * invscale, quad.y0 * invscale);
+        let (p2, p3) = transform.transform_point(quad.x1 * invscale, quad.y0 * invscale);
+        let (p4, p5) = transform.transform_point(quad.x1 * invscale, quad.y1 * invscale);
+        let (p6, p7) = transform.transform_point(quad.x0 * invscale, quad.y1 * invscale);
+
+        verts.push(Vertex::new(p0, p1, quad.s0, quad.t0));
+        verts.push(Vertex::new(p4, p5, quad.s1, quad.t1));
+        verts.push(Vertex::new(p2, p3, quad.s1, quad.t0));
+        verts.push(Vertex::new(p0, p1, quad.s0, quad.t0));
+        verts.push(Vertex::new(p6, p7, quad.s0, quad.t1));
+        verts.push(Vertex::new(p4, p5, quad.s1, quad.t1));
+    }
+
+    verts
+}
+
+// Pops the state stack back to the given depth when dropped, also while unwinding
+struct RestoreGuard<'a, T: Renderer> {
+    canvas: &'a mut Canvas<T>,
+    depth: usize,
+}
+
+impl<T: Renderer> Drop for RestoreGuard<'_, T> {
+    fn drop(&mut self) {
+        self.canvas.state_stack.truncate(self.depth.max(1));
+    }
+}
+
 impl<T: Renderer> Drop for Canvas<T> {
     fn drop(&mut self) {
         self.images.clear(&mut self.renderer);
@@ -1597,3 +3572,140 @@ fn test_image_blit_fast_path() {
         })
     ));
 }
+
+#[test]
+fn test_self_intersecting_star_contains_point() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(100, 100, 1.);
+
+    let mut path = Path::new();
+    path.move_to(50.0, 0.0);
+    path.line_to(21.0, 90.0);
+    path.line_to(98.0, 35.0);
+    path.line_to(2.0, 35.0);
+    path.line_to(79.0, 90.0);
+    path.close();
+
+    // The pentagon in the middle is crossed twice, so it's only inside under the non-zero rule
+    assert!(canvas.contains_point(&path, 50.0, 50.0, FillRule::NonZero));
+    assert!(!canvas.contains_point(&path, 50.0, 50.0, FillRule::EvenOdd));
+
+    // The tips are inside either way
+    assert!(canvas.contains_point(&path, 50.0, 10.0, FillRule::NonZero));
+    assert!(canvas.contains_point(&path, 50.0, 10.0, FillRule::EvenOdd));
+
+    // A separate contour inside the first one cancels it out under even-odd
+    let mut path = Path::new();
+    path.rect(10.0, 10.0, 80.0, 80.0);
+    path.rect(30.0, 30.0, 40.0, 40.0);
+
+    assert!(canvas.contains_point(&path, 50.0, 50.0, FillRule::NonZero));
+    assert!(!canvas.contains_point(&path, 50.0, 50.0, FillRule::EvenOdd));
+    assert!(canvas.contains_point(&path, 20.0, 50.0, FillRule::EvenOdd));
+}
+
+#[test]
+fn test_self_intersecting_fill_uses_stencil_with_fill_rule() {
+    use renderer::{Command, CommandType};
+
+    let renderer = RecordingRenderer::default();
+    let recorded_commands = renderer.last_commands.clone();
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(100, 100, 1.);
+
+    // Pentagram: every turn is a left turn, so only the axis sign flips reveal that it isn't convex
+    let mut path = Path::new();
+    path.move_to(50.0, 0.0);
+    path.line_to(21.0, 90.0);
+    path.line_to(98.0, 35.0);
+    path.line_to(2.0, 35.0);
+    path.line_to(79.0, 90.0);
+    path.close();
+
+    for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+        let paint = Paint::color(Color::black()).with_fill_rule(fill_rule);
+        canvas.fill_path(&path, &paint);
+        canvas.flush();
+
+        let commands = recorded_commands.borrow();
+        let fill = commands
+            .iter()
+            .find(|cmd| !matches!(cmd.cmd_type, CommandType::SetRenderTarget(..)))
+            .unwrap();
+
+        // The interior parity can only be resolved through the stencil buffer, a convex fill would
+        // cover the pentagon in the middle regardless of the fill rule.
+        assert!(matches!(
+            fill,
+            Command {
+                cmd_type: CommandType::ConcaveFill { .. },
+                ..
+            }
+        ));
+        assert_eq!(fill.fill_rule, fill_rule);
+    }
+}
+
+#[test]
+fn test_dashed_stroke_reuses_cached_dashes() {
+    let mut canvas = Canvas::new(RecordingRenderer::default()).unwrap();
+    canvas.set_size(100, 100, 1.);
+
+    let mut path = Path::new();
+    path.move_to(0.0, 50.0);
+    path.line_to(100.0, 50.0);
+
+    let mut paint = Paint::color(Color::black()).with_line_dash(&[10.0, 5.0]);
+    let dashes = |path: &Path| &*path.dashed_cached(&[10.0, 5.0], 0.0, 0.25) as *const Path;
+
+    canvas.stroke_path(&path, &paint);
+    let first = dashes(&path);
+    canvas.stroke_path(&path, &paint);
+    assert_eq!(dashes(&path), first);
+
+    // A new offset cuts new dashes from the same flattened path
+    paint.set_line_dash_offset(3.0);
+    canvas.stroke_path(&path, &paint);
+    let shifted = format!(
+        "{:?}",
+        path.dashed_cached(&[10.0, 5.0], 3.0, 0.25).verbs().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        shifted,
+        format!("{:?}", path.dashed(&[10.0, 5.0], 3.0, 0.25).verbs().collect::<Vec<_>>())
+    );
+
+    // Once the offset went through the pattern, animating it further reuses the dashes of the first round
+    let dashes_at = |path: &Path, offset: f32| &*path.dashed_cached(&[10.0, 5.0], offset, 0.25) as *const Path;
+
+    for frame in 0..30 {
+        paint.set_line_dash_offset(frame as f32 * 0.5);
+        canvas.stroke_path(&path, &paint);
+    }
+
+    let round = dashes_at(&path, 3.0);
+    paint.set_line_dash_offset(18.0);
+    canvas.stroke_path(&path, &paint);
+    assert_eq!(dashes_at(&path, 18.0), round);
+    assert_eq!(dashes_at(&path, -12.0), round);
+
+    // Offsets aren't rounded, while a slightly different tolerance keeps the flattened path
+    assert_eq!(
+        format!(
+            "{:?}",
+            path.dashed_cached(&[10.0, 5.0], 0.7, 0.3).verbs().collect::<Vec<_>>()
+        ),
+        format!("{:?}", path.dashed(&[10.0, 5.0], 0.7, 0.25).verbs().collect::<Vec<_>>())
+    );
+    let coarser = {
+        let dashes = path.dashed_cached(&[10.0, 5.0], 0.7, 0.3);
+        &*dashes as *const Path
+    };
+    assert_eq!(dashes_at(&path, 0.7), coarser);
+
+    // Changing the path drops the dashes cut from its old shape
+    path.line_to(100.0, 80.0);
+    let verbs = path.dashed_cached(&[10.0, 5.0], 0.0, 0.25).verbs().count();
+    assert_eq!(verbs, path.dashed(&[10.0, 5.0], 0.0, 0.25).verbs().count());
+    assert!(verbs > 14);
+}
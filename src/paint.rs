@@ -1,9 +1,11 @@
 // TODO: prefix paint creation functions with make_ or new_
 // so that they are easier to find when autocompleting
 
-use std::rc::Rc;
+use std::{cell::Ref, sync::Arc};
 
-use crate::{geometry::Position, Align, Baseline, Color, FillRule, FontId, ImageId, LineCap, LineJoin};
+use crate::{
+    geometry::Position, Align, Baseline, Color, FillRule, FontId, ImageId, LineCap, LineJoin, Path, Transform2D,
+};
 
 #[derive(Copy, Clone, Debug, PartialEq, Default)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -33,7 +35,7 @@ impl PartialOrd for GradientStop {
 #[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub(crate) struct MultiStopGradient {
-    shared_stops: Rc<[GradientStop]>,
+    shared_stops: Arc<[GradientStop]>,
     tint: f32,
 }
 
@@ -49,6 +51,14 @@ impl MultiStopGradient {
         stop
     }
 
+    pub(crate) fn last(&self) -> Option<GradientStop> {
+        self.shared_stops.last().map(|stop| {
+            let mut stop = *stop;
+            stop.1.a *= self.tint;
+            stop
+        })
+    }
+
     pub(crate) fn pairs(&self) -> impl Iterator<Item = [GradientStop; 2]> + '_ {
         self.shared_stops.as_ref().windows(2).map(move |pair| {
             let mut stops = [pair[0], pair[1]];
@@ -98,10 +108,70 @@ impl GradientColors {
             }
         }
     }
+    fn convert_to_linear(&mut self) {
+        match self {
+            GradientColors::TwoStop { start_color, end_color } => {
+                *start_color = start_color.to_linear();
+                *end_color = end_color.to_linear();
+            }
+            GradientColors::MultiStop { stops } => {
+                stops.shared_stops = stops
+                    .shared_stops
+                    .iter()
+                    .map(|stop| GradientStop(stop.0, stop.1.to_linear()))
+                    .collect();
+            }
+        }
+    }
+    // Turns two stop colors into a multi stop gradient, for paints that always sample the gradient texture
+    fn into_multi_stop(self) -> Self {
+        match self {
+            GradientColors::TwoStop { start_color, end_color } => GradientColors::MultiStop {
+                stops: MultiStopGradient {
+                    shared_stops: vec![GradientStop(0.0, start_color), GradientStop(1.0, end_color)].into(),
+                    tint: 1.0,
+                },
+            },
+            multi_stop => multi_stop,
+        }
+    }
+
+    // Adds the color of the first stop at the end if the stops don't reach it, so that a gradient going
+    // around blends back to where it started
+    fn wrap_around(self) -> Self {
+        match self {
+            GradientColors::MultiStop { mut stops } => {
+                let first = stops.shared_stops.first().copied();
+                let last = stops.shared_stops.last().copied();
+
+                if let (Some(GradientStop(_, color)), Some(GradientStop(offset, _))) = (first, last) {
+                    if offset < 1.0 {
+                        stops.shared_stops = stops
+                            .shared_stops
+                            .iter()
+                            .copied()
+                            .chain([GradientStop(1.0, color)])
+                            .collect();
+                    }
+                }
+
+                GradientColors::MultiStop { stops }
+            }
+            two_stop => two_stop,
+        }
+    }
+
     fn from_stops<Stops>(stops: Stops) -> GradientColors
     where
         Stops: IntoIterator<Item = (f32, Color)>,
     {
+        // Offsets are clamped and sorted, stops with the same offset keep their order to form a hard edge
+        let mut stops: Vec<_> = stops
+            .into_iter()
+            .map(|(offset, color)| (if offset.is_nan() { 0.0 } else { offset.clamp(0.0, 1.0) }, color))
+            .collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
         let mut stops = stops.into_iter();
         let first_stop = match stops.next() {
             Some(stop) => stop,
@@ -165,11 +235,13 @@ pub(crate) enum PaintFlavor {
         height: f32,
         angle: f32,
         tint: Color,
+        transform: Transform2D,
     },
     LinearGradient {
         start: Position,
         end: Position,
         colors: GradientColors,
+        transform: Transform2D,
     },
     BoxGradient {
         pos: Position,
@@ -178,12 +250,20 @@ pub(crate) enum PaintFlavor {
         radius: f32,
         feather: f32,
         colors: GradientColors,
+        transform: Transform2D,
     },
     RadialGradient {
         center: Position,
         in_radius: f32,
         out_radius: f32,
         colors: GradientColors,
+        transform: Transform2D,
+    },
+    ConicGradient {
+        center: Position,
+        angle: f32,
+        colors: GradientColors,
+        transform: Transform2D,
     },
 }
 
@@ -206,6 +286,42 @@ impl PaintFlavor {
             PaintFlavor::RadialGradient { colors, .. } => {
                 colors.mul_alpha(a);
             }
+            PaintFlavor::ConicGradient { colors, .. } => {
+                colors.mul_alpha(a);
+            }
+        }
+    }
+
+    pub(crate) fn convert_to_linear(&mut self) {
+        match self {
+            PaintFlavor::Color(color) => *color = color.to_linear(),
+            PaintFlavor::Image { tint, .. } => *tint = tint.to_linear(),
+            PaintFlavor::LinearGradient { colors, .. }
+            | PaintFlavor::BoxGradient { colors, .. }
+            | PaintFlavor::RadialGradient { colors, .. }
+            | PaintFlavor::ConicGradient { colors, .. } => colors.convert_to_linear(),
+        }
+    }
+
+    pub(crate) fn transform(&self) -> Transform2D {
+        match self {
+            PaintFlavor::Color(_) => Transform2D::identity(),
+            PaintFlavor::Image { transform, .. }
+            | PaintFlavor::LinearGradient { transform, .. }
+            | PaintFlavor::BoxGradient { transform, .. }
+            | PaintFlavor::RadialGradient { transform, .. }
+            | PaintFlavor::ConicGradient { transform, .. } => *transform,
+        }
+    }
+
+    pub(crate) fn set_transform(&mut self, new_transform: Transform2D) {
+        match self {
+            PaintFlavor::Color(_) => {}
+            PaintFlavor::Image { transform, .. }
+            | PaintFlavor::LinearGradient { transform, .. }
+            | PaintFlavor::BoxGradient { transform, .. }
+            | PaintFlavor::RadialGradient { transform, .. }
+            | PaintFlavor::ConicGradient { transform, .. } => *transform = new_transform,
         }
     }
 
@@ -214,13 +330,19 @@ impl PaintFlavor {
             PaintFlavor::LinearGradient { colors, .. } => Some(colors),
             PaintFlavor::BoxGradient { colors, .. } => Some(colors),
             PaintFlavor::RadialGradient { colors, .. } => Some(colors),
+            PaintFlavor::ConicGradient { colors, .. } => Some(colors),
             _ => None,
         }
     }
 
     /// Returns true if this paint is an untransformed image paint without anti-aliasing at the edges in case of a fill
     pub(crate) fn is_straight_tinted_image(&self, shape_anti_alias: bool) -> bool {
-        matches!(self, &PaintFlavor::Image { angle, .. } if angle == 0.0 && !shape_anti_alias)
+        match self {
+            &PaintFlavor::Image { angle, transform, .. } => {
+                angle == 0.0 && transform[1] == 0.0 && transform[2] == 0.0 && !shape_anti_alias
+            }
+            _ => false,
+        }
     }
 }
 
@@ -229,6 +351,8 @@ pub(crate) enum GlyphTexture {
     None,
     AlphaMask(ImageId),
     ColorTexture(ImageId),
+    // Signed distance field, with the factor converting field values to coverage of a screen pixel
+    Sdf(ImageId, f32),
 }
 
 impl Default for GlyphTexture {
@@ -246,6 +370,9 @@ pub(crate) struct StrokeSettings {
     pub(crate) line_cap_start: LineCap,
     pub(crate) line_cap_end: LineCap,
     pub(crate) line_join: LineJoin,
+    pub(crate) line_dash: Vec<f32>,
+    pub(crate) line_dash_offset: f32,
+    pub(crate) non_scaling: bool,
 }
 
 impl Default for StrokeSettings {
@@ -257,10 +384,56 @@ impl Default for StrokeSettings {
             line_cap_start: Default::default(),
             line_cap_end: Default::default(),
             line_join: Default::default(),
+            line_dash: Vec::new(),
+            line_dash_offset: 0.0,
+            non_scaling: false,
         }
     }
 }
 
+impl StrokeSettings {
+    // Returns the line width in device pixels
+    pub(crate) fn device_width(&self, transform: &Transform2D) -> f32 {
+        if self.non_scaling {
+            self.line_width.max(0.0)
+        } else {
+            (self.line_width * transform.average_scale()).max(0.0)
+        }
+    }
+
+    // Returns the path cut into dashes if the stroke is dashed. Dashes are cut in user space, so that
+    // they scale along with the line width; non-scaling strokes give their dash lengths in device pixels.
+    pub(crate) fn dashed_path<'a>(
+        &self,
+        path: &'a Path,
+        transform: &Transform2D,
+        tess_tol: f32,
+    ) -> Option<Ref<'a, Path>> {
+        if self.line_dash.is_empty() {
+            return None;
+        }
+
+        let scale = transform.average_scale().max(f32::EPSILON);
+        let tess_tol = tess_tol / scale;
+
+        Some(if self.non_scaling {
+            let pattern: Vec<f32> = self.line_dash.iter().map(|len| len / scale).collect();
+            path.dashed_cached(&pattern, self.line_dash_offset / scale, tess_tol)
+        } else {
+            path.dashed_cached(&self.line_dash, self.line_dash_offset, tess_tol)
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct TextShadow {
+    pub(crate) offset_x: f32,
+    pub(crate) offset_y: f32,
+    pub(crate) blur: f32,
+    pub(crate) color: Color,
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub(crate) struct TextSettings {
@@ -268,6 +441,14 @@ pub(crate) struct TextSettings {
     pub(crate) font_ids: [Option<FontId>; 8],
     pub(crate) font_size: f32,
     pub(crate) letter_spacing: f32,
+    pub(crate) word_spacing: f32,
+    pub(crate) tab_width: f32,
+    pub(crate) kerning: bool,
+    pub(crate) font_features: Vec<(String, u32)>,
+    pub(crate) subpixel_positioning: bool,
+    pub(crate) underline: bool,
+    pub(crate) strikethrough: bool,
+    pub(crate) shadow: Option<TextShadow>,
     pub(crate) text_baseline: Baseline,
     pub(crate) text_align: Align,
 }
@@ -278,6 +459,14 @@ impl Default for TextSettings {
             font_ids: Default::default(),
             font_size: 16.0,
             letter_spacing: 0.0,
+            word_spacing: 0.0,
+            tab_width: 0.0,
+            kerning: true,
+            font_features: Vec::new(),
+            subpixel_positioning: false,
+            underline: false,
+            strikethrough: false,
+            shadow: None,
             text_baseline: Default::default(),
             text_align: Default::default(),
         }
@@ -337,6 +526,16 @@ impl Paint {
         Paint::with_flavor(PaintFlavor::Color(color))
     }
 
+    /// Creates a solid color paint meant for filling, like [`Paint::color`].
+    pub fn fill(color: Color) -> Self {
+        Self::color(color)
+    }
+
+    /// Creates a solid color paint meant for stroking, with the line width already set.
+    pub fn stroke(color: Color, line_width: f32) -> Self {
+        Self::color(color).with_line_width(line_width)
+    }
+
     fn with_flavor(flavor: PaintFlavor) -> Self {
         Paint {
             flavor,
@@ -373,6 +572,7 @@ impl Paint {
             height,
             angle,
             tint: Color::rgbaf(1.0, 1.0, 1.0, alpha),
+            transform: Transform2D::identity(),
         })
     }
 
@@ -386,6 +586,7 @@ impl Paint {
             height,
             angle,
             tint,
+            transform: Transform2D::identity(),
         })
     }
 
@@ -416,10 +617,15 @@ impl Paint {
             start: Position { x: start_x, y: start_y },
             end: Position { x: end_x, y: end_y },
             colors: GradientColors::TwoStop { start_color, end_color },
+            transform: Transform2D::identity(),
         })
     }
     /// Creates and returns a linear gradient paint with two or more stops.
     ///
+    /// The stops are given as offsets in [0..1] with their colors. They may be in any order; stops with
+    /// the same offset keep their relative order and form a hard edge. The colors of the first and
+    /// last stop extend to the start and end of the gradient.
+    ///
     /// The gradient is transformed by the current transform when it is passed to fill_path() or stroke_path().
     ///
     /// # Example
@@ -451,6 +657,7 @@ impl Paint {
             start: Position { x: start_x, y: start_y },
             end: Position { x: end_x, y: end_y },
             colors: GradientColors::from_stops(stops),
+            transform: Transform2D::identity(),
         })
     }
 
@@ -504,6 +711,36 @@ impl Paint {
                 start_color: inner_color,
                 end_color: outer_color,
             },
+            transform: Transform2D::identity(),
+        })
+    }
+
+    // Box gradient that fades out like a gaussian blurred edge, see Canvas::draw_box_shadow. The feather
+    // spans three standard deviations on both sides of the edge, with the alpha of each stop following
+    // the coverage of the blurred edge at that distance.
+    pub(crate) fn box_shadow(x: f32, y: f32, width: f32, height: f32, radius: f32, blur: f32, color: Color) -> Self {
+        const STOPS: usize = 16;
+
+        let sigma = blur / 2.0;
+        let feather = 6.0 * sigma;
+
+        let stops = (0..STOPS).map(|i| {
+            let offset = i as f32 / (STOPS - 1) as f32;
+            let distance = (offset - 0.5) * feather;
+
+            let mut stop_color = color;
+            stop_color.a *= 0.5 * erfc(distance / (sigma * std::f32::consts::SQRT_2));
+            (offset, stop_color)
+        });
+
+        Paint::with_flavor(PaintFlavor::BoxGradient {
+            pos: Position { x, y },
+            width,
+            height,
+            radius,
+            feather,
+            colors: GradientColors::from_stops(stops),
+            transform: Transform2D::identity(),
         })
     }
 
@@ -548,13 +785,15 @@ impl Paint {
                 start_color: inner_color,
                 end_color: outer_color,
             },
+            transform: Transform2D::identity(),
         })
     }
 
     /// Creates and returns a multi-stop radial gradient.
     ///
     /// Parameters (cx,cy) specify the center, in_radius and out_radius specify the inner and outer radius of the gradient,
-    /// colors specifies a list of color stops with offsets. The first offset should be 0.0 and the last offset should be 1.0.
+    /// colors specifies a list of color stops with offsets in [0..1], see [`Paint::linear_gradient_stops`] for how
+    /// they are ordered and extended.
     ///
     /// The gradient is transformed by the current transform when it is passed to fill_paint() or stroke_paint().
     ///
@@ -592,6 +831,47 @@ impl Paint {
             in_radius,
             out_radius,
             colors: GradientColors::from_stops(stops),
+            transform: Transform2D::identity(),
+        })
+    }
+
+    /// Creates and returns a conic (sweep) gradient paint, as used for color wheels.
+    ///
+    /// The colors change with the angle around the center (cx,cy), starting at `start_angle` and going
+    /// clockwise (with the y axis pointing down) for a full turn. The stops are interpreted like for
+    /// [`Paint::linear_gradient_stops`], with the offset being the fraction of the turn. After the last
+    /// stop the colors blend back to the color of the first stop, so that the gradient wraps around
+    /// seamlessly. A stop at 1.0 makes a hard edge at `start_angle` instead.
+    ///
+    /// The gradient is transformed by the current transform when it is passed to fill_path() or stroke_path().
+    ///
+    /// # Example
+    /// ```
+    /// use femtovg::{Paint, Path, Color, Canvas, renderer::Void};
+    ///
+    /// let mut canvas = Canvas::new(Void).expect("Cannot create canvas");
+    ///
+    /// let wheel = Paint::conic_gradient(
+    ///     50.0,
+    ///     50.0,
+    ///     0.0,
+    ///     [
+    ///         (0.0, Color::rgb(255, 0, 0)),
+    ///         (1.0 / 3.0, Color::rgb(0, 255, 0)),
+    ///         (2.0 / 3.0, Color::rgb(0, 0, 255)),
+    ///     ],
+    /// );
+    ///
+    /// let mut path = Path::new();
+    /// path.circle(50.0, 50.0, 40.0);
+    /// canvas.fill_path(&path, &wheel);
+    /// ```
+    pub fn conic_gradient(cx: f32, cy: f32, start_angle: f32, stops: impl IntoIterator<Item = (f32, Color)>) -> Self {
+        Paint::with_flavor(PaintFlavor::ConicGradient {
+            center: Position { x: cx, y: cy },
+            angle: start_angle,
+            colors: GradientColors::from_stops(stops).into_multi_stop().wrap_around(),
+            transform: Transform2D::identity(),
         })
     }
 
@@ -606,6 +886,43 @@ impl Paint {
         self
     }
 
+    /// Multiplies the alpha of the paint's color, image tint or gradient stops by `alpha`, e.g. to fade it
+    /// out. The text shadow color is faded along with it.
+    pub fn mul_alpha(&mut self, alpha: f32) {
+        self.flavor.mul_alpha(alpha);
+
+        if let Some(shadow) = &mut self.text.shadow {
+            shadow.color.a *= alpha;
+        }
+    }
+
+    /// Returns the paint with its alpha multiplied by `alpha`, see [`Paint::mul_alpha`].
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.mul_alpha(alpha);
+        self
+    }
+
+    /// Returns the transform from the space of the image or gradient of this paint to user space.
+    pub fn transform(&self) -> Transform2D {
+        self.flavor.transform()
+    }
+
+    /// Sets the transform from the space of the image or gradient of this paint to user space, like the
+    /// `gradientTransform` and `patternTransform` attributes of SVG.
+    ///
+    /// This positions, rotates or skews the image or gradient independently of the geometry it fills. The
+    /// coordinates the paint was created with are in paint space. The current transform of the canvas is
+    /// applied after this one. Has no effect on solid color paints.
+    pub fn set_transform(&mut self, transform: Transform2D) {
+        self.flavor.set_transform(transform);
+    }
+
+    /// Returns the paint with the paint space transform set to the specified value.
+    pub fn with_transform(mut self, transform: Transform2D) -> Self {
+        self.set_transform(transform);
+        self
+    }
+
     /// Returns boolean if the shapes drawn with this paint will be antialiased.
     pub fn anti_alias(&self) -> bool {
         self.shape_anti_alias
@@ -654,6 +971,25 @@ impl Paint {
         self
     }
 
+    /// Returns whether the line width is in device pixels instead of user space units.
+    pub fn non_scaling_stroke(&self) -> bool {
+        self.stroke.non_scaling
+    }
+
+    /// Sets whether the line width is in device pixels, ignoring the scale of the current transform.
+    ///
+    /// This matches SVG's `vector-effect: non-scaling-stroke`: outlines keep their width when zooming in
+    /// or out, as is expected in CAD-like views. The dash pattern and offset are in device pixels as well.
+    pub fn set_non_scaling_stroke(&mut self, value: bool) {
+        self.stroke.non_scaling = value;
+    }
+
+    /// Returns the paint with non-scaling stroke set to the specified value.
+    pub fn with_non_scaling_stroke(mut self, value: bool) -> Self {
+        self.set_non_scaling_stroke(value);
+        self
+    }
+
     /// Getter for the miter limit
     pub fn miter_limit(&self) -> f32 {
         self.stroke.miter_limit
@@ -740,6 +1076,106 @@ impl Paint {
         self
     }
 
+    /// Returns the dash pattern of strokes. An empty pattern means solid strokes.
+    pub fn line_dash(&self) -> &[f32] {
+        &self.stroke.line_dash
+    }
+
+    /// Sets the dash pattern used for strokes, as alternating lengths of dashes and gaps.
+    ///
+    /// Like on the HTML canvas, a pattern with an odd number of entries is repeated to make it even,
+    /// so `[5.0]` draws 5 unit long dashes with 5 unit long gaps. The lengths are in the same units
+    /// as the line width. An empty pattern, or one with negative or only zero lengths, strokes solid lines.
+    ///
+    /// Every sub-path starts at the beginning of the pattern, shifted by the [dash offset](Self::set_line_dash_offset).
+    pub fn set_line_dash(&mut self, pattern: &[f32]) {
+        let valid = pattern.iter().all(|len| len.is_finite() && *len >= 0.0) && pattern.iter().sum::<f32>() > 0.0;
+
+        self.stroke.line_dash.clear();
+
+        if valid {
+            self.stroke.line_dash.extend_from_slice(pattern);
+
+            if pattern.len() % 2 == 1 {
+                self.stroke.line_dash.extend_from_slice(pattern);
+            }
+        }
+    }
+
+    /// Returns the paint with the dash pattern set to the specified value.
+    pub fn with_line_dash(mut self, pattern: &[f32]) -> Self {
+        self.set_line_dash(pattern);
+        self
+    }
+
+    /// Makes strokes draw round dots of the given radius instead of a continuous line, with `spacing`
+    /// being the distance between the centers of neighbouring dots.
+    ///
+    /// This is a shortcut that sets the line width, round caps and joins and a matching dash pattern,
+    /// so changing any of those afterwards alters the dots. A dot that falls on a sharp corner is bent
+    /// around it, which due to the round joins still looks like a dot.
+    pub fn set_dotted(&mut self, radius: f32, spacing: f32) {
+        // Zero-length dashes are dropped as degenerate sub-paths, so every dot is a very short dash
+        let dot = radius * 0.1;
+
+        self.set_line_width(radius * 2.0);
+        self.set_line_cap(LineCap::Round);
+        self.set_line_join(LineJoin::Round);
+        self.set_line_dash(&[dot, (spacing - dot).max(dot)]);
+    }
+
+    /// Returns the paint set up to stroke round dots, see [`set_dotted`](Self::set_dotted).
+    pub fn dotted(mut self, radius: f32, spacing: f32) -> Self {
+        self.set_dotted(radius, spacing);
+        self
+    }
+
+    /// Returns the distance into the dash pattern at which strokes start.
+    pub fn line_dash_offset(&self) -> f32 {
+        self.stroke.line_dash_offset
+    }
+
+    /// Sets the distance into the dash pattern at which strokes start.
+    ///
+    /// Increasing the offset moves the dashes backwards along the path. Dashes are cut on the CPU at
+    /// the exact offset and kept with the path for every offset into the pattern it was stroked with.
+    /// Animating the offset, e.g. for "marching ants" selection outlines, cuts the dashes again from
+    /// the cached flattened path until the offsets repeat, like `frame as f32 * 0.5` does once it went
+    /// through the whole pattern.
+    ///
+    /// # Example
+    /// ```
+    /// use femtovg::{Paint, Path, Color, Canvas, renderer::Void};
+    ///
+    /// let mut canvas = Canvas::new(Void).expect("Cannot create canvas");
+    ///
+    /// let mut path = Path::new();
+    /// path.rect(10.0, 10.0, 100.0, 50.0);
+    ///
+    /// let mut paint = Paint::color(Color::black()).with_line_dash(&[4.0, 4.0]);
+    ///
+    /// for frame in 0..3 {
+    ///     paint.set_line_dash_offset(frame as f32 * 0.5);
+    ///     canvas.stroke_path(&path, &paint);
+    /// }
+    /// ```
+    pub fn set_line_dash_offset(&mut self, offset: f32) {
+        self.stroke.line_dash_offset = offset;
+    }
+
+    /// Returns the paint with the dash offset set to the specified value.
+    pub fn with_line_dash_offset(mut self, offset: f32) -> Self {
+        self.set_line_dash_offset(offset);
+        self
+    }
+
+    /// Sets the fonts used for text, in order of preference. At most 8 fonts are used.
+    ///
+    /// Text is shaped with the first font that has glyphs for all characters of a word. If there is
+    /// none, each character falls back to the first of these fonts that has a glyph for it. Other
+    /// registered fonts are not used.
+    ///
+    /// Only has effect on canvas text operations
     pub fn set_font(&mut self, font_ids: &[FontId]) {
         self.text.font_ids = Default::default();
 
@@ -748,7 +1184,7 @@ impl Paint {
         }
     }
 
-    /// Returns the paint with the font set to the specified value.
+    /// Returns the paint with the fonts set to the specified values.
     pub fn with_font(mut self, font_ids: &[FontId]) -> Self {
         self.set_font(font_ids);
         self
@@ -779,7 +1215,8 @@ impl Paint {
         self.text.letter_spacing
     }
 
-    /// Sets the letter spacing for this paint
+    /// Sets the letter spacing for this paint. It is added to the advance of every glyph,
+    /// negative values tighten the text.
     ///
     /// Only has effect on canvas text operations
     pub fn set_letter_spacing(&mut self, spacing: f32) {
@@ -792,6 +1229,185 @@ impl Paint {
         self
     }
 
+    /// Returns the current word spacing
+    pub fn word_spacing(&self) -> f32 {
+        self.text.word_spacing
+    }
+
+    /// Sets the word spacing for this paint. It is added to the advance of every space character,
+    /// on top of the letter spacing. Negative values tighten the text.
+    ///
+    /// Only has effect on canvas text operations
+    pub fn set_word_spacing(&mut self, spacing: f32) {
+        self.text.word_spacing = spacing;
+    }
+
+    /// Returns the paint with the word spacing set to the specified value.
+    pub fn with_word_spacing(mut self, spacing: f32) -> Self {
+        self.set_word_spacing(spacing);
+        self
+    }
+
+    /// Returns the distance between tab stops.
+    pub fn tab_width(&self) -> f32 {
+        self.text.tab_width
+    }
+
+    /// Sets the distance between tab stops, in the same units as the font size.
+    ///
+    /// Tab characters advance to the next multiple of the tab width, measured from the start of the
+    /// text, so that columns of pre-formatted text line up. With the default of 0, tabs are shaped
+    /// like any other character and get the advance of the font's tab glyph.
+    ///
+    /// Only has effect on canvas text operations
+    pub fn set_tab_width(&mut self, width: f32) {
+        self.text.tab_width = width.max(0.0);
+    }
+
+    /// Returns the paint with the tab width set to the specified value.
+    pub fn with_tab_width(mut self, width: f32) -> Self {
+        self.set_tab_width(width);
+        self
+    }
+
+    /// Returns whether the kerning of the font is applied to text.
+    pub fn kerning(&self) -> bool {
+        self.text.kerning
+    }
+
+    /// Sets whether the kerning of the font is applied to text. Enabled by default.
+    ///
+    /// Disabling kerning gives each glyph its nominal advance, like the CSS `font-kerning: none`,
+    /// which keeps the digits of numeric columns aligned.
+    ///
+    /// Only has effect on canvas text operations
+    pub fn set_kerning(&mut self, kerning: bool) {
+        self.text.kerning = kerning;
+    }
+
+    /// Returns the paint with kerning enabled or disabled.
+    pub fn with_kerning(mut self, kerning: bool) -> Self {
+        self.set_kerning(kerning);
+        self
+    }
+
+    /// Returns the OpenType features set with [`Paint::set_font_features`].
+    pub fn font_features(&self) -> &[(String, u32)] {
+        &self.text.font_features
+    }
+
+    /// Sets OpenType features to enable or disable when shaping text, as pairs of a feature tag
+    /// and a value.
+    ///
+    /// A value of 0 turns a feature off and 1 turns it on, for example `("liga", 0)` disables the
+    /// standard ligatures and `("smcp", 1)` enables small capitals. Features that select one of
+    /// several alternates take the index of the alternate. Features the font doesn't have are
+    /// ignored, as are tags that aren't made of up to four ASCII characters. Replaces the features
+    /// set before.
+    ///
+    /// Only has effect on canvas text operations
+    pub fn set_font_features(&mut self, features: &[(&str, u32)]) {
+        self.text.font_features = features
+            .iter()
+            .filter(|(tag, _)| (1..=4).contains(&tag.len()) && tag.bytes().all(|b| b.is_ascii_graphic()))
+            .map(|&(tag, value)| (tag.to_owned(), value))
+            .collect();
+    }
+
+    /// Returns the paint with the OpenType features set to the specified values.
+    pub fn with_font_features(mut self, features: &[(&str, u32)]) -> Self {
+        self.set_font_features(features);
+        self
+    }
+
+    /// Returns whether glyphs are placed at fractional pixel positions
+    pub fn subpixel_positioning(&self) -> bool {
+        self.text.subpixel_positioning
+    }
+
+    /// Sets whether glyphs are placed at fractional pixel positions.
+    ///
+    /// By default the origin of every glyph is rounded to whole pixels, which renders static text
+    /// crisply and lets a glyph be rendered once per size. When enabled, glyph origins are kept at
+    /// fractional positions in steps of a tenth of a pixel, which keeps moving text from jittering, at
+    /// the cost of rendering each glyph once for every fractional offset it is drawn at.
+    ///
+    /// Only has effect on canvas text operations
+    pub fn set_subpixel_positioning(&mut self, enabled: bool) {
+        self.text.subpixel_positioning = enabled;
+    }
+
+    /// Returns the paint with subpixel positioning of glyphs enabled or disabled.
+    pub fn with_subpixel_positioning(mut self, enabled: bool) -> Self {
+        self.set_subpixel_positioning(enabled);
+        self
+    }
+
+    /// Returns whether text is underlined
+    pub fn underline(&self) -> bool {
+        self.text.underline
+    }
+
+    /// Sets whether text is underlined. The line spans the whole text and uses the position and
+    /// thickness suggested by the font.
+    ///
+    /// Only has effect on canvas text operations
+    pub fn set_underline(&mut self, underline: bool) {
+        self.text.underline = underline;
+    }
+
+    /// Returns the paint with underlining enabled or disabled.
+    pub fn with_underline(mut self, underline: bool) -> Self {
+        self.set_underline(underline);
+        self
+    }
+
+    /// Returns whether text is struck through
+    pub fn strikethrough(&self) -> bool {
+        self.text.strikethrough
+    }
+
+    /// Sets whether text is struck through. The line spans the whole text and uses the position and
+    /// thickness suggested by the font.
+    ///
+    /// Only has effect on canvas text operations
+    pub fn set_strikethrough(&mut self, strikethrough: bool) {
+        self.text.strikethrough = strikethrough;
+    }
+
+    /// Returns the paint with strikethrough enabled or disabled.
+    pub fn with_strikethrough(mut self, strikethrough: bool) -> Self {
+        self.set_strikethrough(strikethrough);
+        self
+    }
+
+    /// Sets a shadow that is drawn beneath text, offset by `offset_x` and `offset_y`.
+    ///
+    /// A `blur` larger than zero blurs the shadow with a gaussian filter whose standard deviation is
+    /// half the blur radius, capped at 8 pixels. Blurred shadows are rendered into temporary images,
+    /// so they are considerably more expensive than plain ones.
+    ///
+    /// Only has effect on canvas text operations
+    pub fn set_text_shadow(&mut self, offset_x: f32, offset_y: f32, blur: f32, color: Color) {
+        self.text.shadow = Some(TextShadow {
+            offset_x,
+            offset_y,
+            blur,
+            color,
+        });
+    }
+
+    /// Returns the paint with a text shadow, see [`Paint::set_text_shadow`].
+    pub fn with_text_shadow(mut self, offset_x: f32, offset_y: f32, blur: f32, color: Color) -> Self {
+        self.set_text_shadow(offset_x, offset_y, blur, color);
+        self
+    }
+
+    /// Removes the text shadow.
+    pub fn clear_text_shadow(&mut self) {
+        self.text.shadow = None;
+    }
+
     /// Returns the current vertical align
     pub fn text_baseline(&self) -> Baseline {
         self.text.text_baseline
@@ -846,3 +1462,14 @@ impl Paint {
         self
     }
 }
+
+// Complementary error function, using approximation 7.1.26 from Abramowitz and Stegun
+fn erfc(x: f32) -> f32 {
+    let x = f64::from(x);
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly =
+        t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erfc = poly * (-x * x).exp();
+
+    (if x >= 0.0 { erfc } else { 2.0 - erfc }) as f32
+}
@@ -1,15 +1,28 @@
 use std::{
-    cell::{RefCell, RefMut},
+    cell::{Ref, RefCell, RefMut},
     f32::consts::PI,
     slice,
 };
 
-use crate::geometry::{Position, Transform2D, Vector};
+use fnv::FnvHashMap;
+
+use crate::{
+    geometry::{Position, Transform2D, Vector},
+    ErrorKind, Paint,
+};
+#[cfg(feature = "text")]
 use rustybuzz::ttf_parser;
 
 mod cache;
 pub use cache::{Convexity, PathCache};
 
+// Header of the binary encoding written by `Path::to_bytes`, followed by the format version
+const BYTES_MAGIC: &[u8; 4] = b"FVPT";
+const BYTES_VERSION: u8 = 1;
+
+// Number of dash offsets kept per pattern by `Path::dashed_cached` before they are dropped
+const MAX_DASH_PHASES: usize = 256;
+
 // Length proportional to radius of a cubic bezier handle for 90deg arcs.
 const KAPPA90: f32 = 0.552_284_8; // 0.552_284_749_3;
 
@@ -38,6 +51,38 @@ pub enum PackedVerb {
     Close,
 }
 
+// Reads the little endian values of the binary path encoding
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ErrorKind> {
+        if self.0.len() < len {
+            return Err(ErrorKind::InvalidPathData);
+        }
+
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32, ErrorKind> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, ErrorKind> {
+        self.u32().map(f32::from_bits)
+    }
+
+    fn position(&mut self) -> Result<Position, ErrorKind> {
+        Ok(Position {
+            x: self.f32()?,
+            y: self.f32()?,
+        })
+    }
+}
+
 /// A verb describes how to interpret one or more points to continue the countour
 /// of a [`Path`].
 #[derive(Copy, Clone, Debug)]
@@ -59,6 +104,28 @@ pub enum Verb {
     Close,
 }
 
+impl PackedVerb {
+    fn from_byte(byte: u8) -> Option<Self> {
+        Some(match byte {
+            0 => Self::MoveTo,
+            1 => Self::LineTo,
+            2 => Self::BezierTo,
+            3 => Self::Solid,
+            4 => Self::Hole,
+            5 => Self::Close,
+            _ => return None,
+        })
+    }
+
+    fn num_coordinates(self) -> usize {
+        match self {
+            Self::MoveTo | Self::LineTo => 1,
+            Self::BezierTo => 3,
+            Self::Solid | Self::Hole | Self::Close => 0,
+        }
+    }
+}
+
 impl Verb {
     fn num_coordinates(&self) -> usize {
         match *self {
@@ -101,6 +168,18 @@ pub struct Path {
     dist_tol: f32,
     #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) cache: RefCell<Option<(u64, PathCache)>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dash_cache: RefCell<Option<Box<DashCache>>>,
+}
+
+// The flattened sub-paths of a path and the dashes cut from them with the last pattern, by the bits of
+// the offset into the pattern
+#[derive(Clone, Debug)]
+struct DashCache {
+    tolerance_level: i32,
+    polylines: Vec<Vec<Position>>,
+    pattern: Vec<f32>,
+    dashed: FnvHashMap<u32, Path>,
 }
 
 impl Path {
@@ -111,15 +190,100 @@ impl Path {
         }
     }
 
+    /// Creates an empty path with room for `verbs` verbs, and as many points, before it reallocates.
+    ///
+    /// Lines and moves take one point each, bezier curves take three.
+    pub fn with_capacity(verbs: usize) -> Self {
+        Self {
+            verbs: Vec::with_capacity(verbs),
+            coords: Vec::with_capacity(verbs),
+            ..Self::new()
+        }
+    }
+
     /// Memory usage in bytes
     pub fn size(&self) -> usize {
         std::mem::size_of::<PackedVerb>() * self.verbs.len() + std::mem::size_of::<f32>() * self.coords.len()
     }
 
+    /// Encodes the path into a compact binary representation that can be loaded with [`Path::from_bytes`].
+    ///
+    /// The encoding is versioned and stores the verbs and points exactly, so decoding it gives back
+    /// an identical path.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + 1 + 4 + self.verbs.len() + 8 * (self.coords.len() + 1) + 4);
+
+        bytes.extend_from_slice(BYTES_MAGIC);
+        bytes.push(BYTES_VERSION);
+        bytes.extend_from_slice(&(self.verbs.len() as u32).to_le_bytes());
+        bytes.extend(self.verbs.iter().map(|verb| *verb as u8));
+
+        for pos in self.coords.iter().chain(std::iter::once(&self.last_pos)) {
+            bytes.extend_from_slice(&pos.x.to_le_bytes());
+            bytes.extend_from_slice(&pos.y.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&self.dist_tol.to_le_bytes());
+
+        bytes
+    }
+
+    /// Decodes a path encoded with [`Path::to_bytes`].
+    ///
+    /// Returns [`ErrorKind::InvalidPathData`] if the data is truncated, was written by an unsupported
+    /// version or doesn't describe a valid sequence of verbs.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ErrorKind> {
+        let mut reader = ByteReader(bytes);
+
+        if reader.take(4)? != BYTES_MAGIC || reader.take(1)?[0] != BYTES_VERSION {
+            return Err(ErrorKind::InvalidPathData);
+        }
+
+        let verb_count = reader.u32()? as usize;
+        let verbs = reader
+            .take(verb_count)?
+            .iter()
+            .map(|byte| PackedVerb::from_byte(*byte))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(ErrorKind::InvalidPathData)?;
+
+        let coord_count = verbs.iter().map(|verb| verb.num_coordinates()).sum::<usize>();
+        let mut coords = Vec::with_capacity(coord_count);
+
+        for _ in 0..coord_count {
+            coords.push(reader.position()?);
+        }
+
+        let last_pos = reader.position()?;
+        let dist_tol = reader.f32()?;
+
+        if !reader.0.is_empty() {
+            return Err(ErrorKind::InvalidPathData);
+        }
+
+        Ok(Self {
+            verbs,
+            coords,
+            last_pos,
+            dist_tol,
+            ..Default::default()
+        })
+    }
+
     pub fn is_empty(&self) -> bool {
         self.verbs.is_empty()
     }
 
+    /// Removes all sub-paths while keeping the allocated memory, so the path can be reused for
+    /// building the next shape without allocating.
+    pub fn clear(&mut self) {
+        self.verbs.clear();
+        self.coords.clear();
+        self.last_pos = Position::default();
+        *self.cache.get_mut() = None;
+        *self.dash_cache.get_mut() = None;
+    }
+
     pub fn set_distance_tolerance(&mut self, value: f32) {
         self.dist_tol = value;
     }
@@ -158,17 +322,17 @@ impl Path {
 
     /// Starts new sub-path with specified point as first point.
     pub fn move_to(&mut self, x: f32, y: f32) {
-        self.append(&[PackedVerb::MoveTo], &[Position { x, y }]);
+        self.append_packed(&[PackedVerb::MoveTo], &[Position { x, y }]);
     }
 
     /// Adds line segment from the last point in the path to the specified point.
     pub fn line_to(&mut self, x: f32, y: f32) {
-        self.append(&[PackedVerb::LineTo], &[Position { x, y }]);
+        self.append_packed(&[PackedVerb::LineTo], &[Position { x, y }]);
     }
 
     /// Adds cubic bezier segment from last point in the path via two control points to the specified point.
     pub fn bezier_to(&mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) {
-        self.append(
+        self.append_packed(
             &[PackedVerb::BezierTo],
             &[
                 Position { x: c1x, y: c1y },
@@ -186,25 +350,30 @@ impl Path {
         let pos1 = pos0 + (cpos - pos0) * (2.0 / 3.0);
         let pos2 = pos + (cpos - pos) * (2.0 / 3.0);
 
-        self.append(&[PackedVerb::BezierTo], &[pos1, pos2, pos]);
+        self.append_packed(&[PackedVerb::BezierTo], &[pos1, pos2, pos]);
     }
 
     /// Closes current sub-path with a line segment.
     pub fn close(&mut self) {
-        self.append(&[PackedVerb::Close], &[]);
+        self.append_packed(&[PackedVerb::Close], &[]);
     }
 
     /// Sets the current sub-path winding, see [`Solidity`].
     pub fn solidity(&mut self, solidity: Solidity) {
         match solidity {
-            Solidity::Solid => self.append(&[PackedVerb::Solid], &[]),
-            Solidity::Hole => self.append(&[PackedVerb::Hole], &[]),
+            Solidity::Solid => self.append_packed(&[PackedVerb::Solid], &[]),
+            Solidity::Hole => self.append_packed(&[PackedVerb::Hole], &[]),
         }
     }
 
     /// Creates new circle arc shaped sub-path. The arc center is at cx,cy, the arc radius is r,
     /// and the arc is drawn from angle a0 to a1, and swept in direction dir (Winding)
     /// Angles are specified in radians.
+    ///
+    /// With the y axis pointing down, [`Solidity::Hole`] sweeps clockwise (increasing angles) and
+    /// [`Solidity::Solid`] sweeps counter-clockwise (decreasing angles). If the path already has
+    /// a current point, a straight line connects it to the start of the arc; otherwise the arc
+    /// starts a new sub-path. For arcs between two tangent lines see [`Path::arc_to`].
     pub fn arc(&mut self, cx: f32, cy: f32, r: f32, a0: f32, a1: f32, dir: Solidity) {
         let cpos = Position { x: cx, y: cy };
 
@@ -266,7 +435,7 @@ impl Path {
             ptanpos = tanpos;
         }
 
-        self.append(&commands, &coords);
+        self.append_packed(&commands, &coords);
     }
 
     /// Adds an arc segment at the corner defined by the last path point, and two specified points.
@@ -320,7 +489,7 @@ impl Path {
 
     /// Creates new rectangle shaped sub-path.
     pub fn rect(&mut self, x: f32, y: f32, w: f32, h: f32) {
-        self.append(
+        self.append_packed(
             &[
                 PackedVerb::MoveTo,
                 PackedVerb::LineTo,
@@ -379,7 +548,7 @@ impl Path {
             let rx_tl = rad_top_left.min(halfw) * w.signum();
             let ry_tl = rad_top_left.min(halfh) * h.signum();
 
-            self.append(
+            self.append_packed(
                 &[
                     PackedVerb::MoveTo,
                     PackedVerb::LineTo,
@@ -454,7 +623,7 @@ impl Path {
 
     /// Creates new ellipse shaped sub-path.
     pub fn ellipse(&mut self, cx: f32, cy: f32, rx: f32, ry: f32) {
-        self.append(
+        self.append_packed(
             &[
                 PackedVerb::MoveTo,
                 PackedVerb::BezierTo,
@@ -491,8 +660,548 @@ impl Path {
         self.ellipse(cx, cy, r, r);
     }
 
+    /// Creates new pie slice shaped sub-path. The slice is bounded by the center cx,cy and the arc
+    /// of radius r that starts at `start_angle` and spans `sweep` radians. A positive sweep goes
+    /// clockwise (with the y axis pointing down), a negative one counter-clockwise.
+    pub fn sector(&mut self, cx: f32, cy: f32, r: f32, start_angle: f32, sweep: f32) {
+        let dir = if sweep >= 0.0 { Solidity::Hole } else { Solidity::Solid };
+
+        self.move_to(cx, cy);
+        self.arc(cx, cy, r, start_angle, start_angle + sweep, dir);
+        self.close();
+    }
+
+    /// Creates new ring segment shaped sub-path, as used by donut charts. The segment lies between the
+    /// radii `inner_r` and `outer_r` around cx,cy and spans `sweep` radians from `start_angle`. A
+    /// positive sweep goes clockwise (with the y axis pointing down), a negative one counter-clockwise.
+    pub fn annular_sector(&mut self, cx: f32, cy: f32, inner_r: f32, outer_r: f32, start_angle: f32, sweep: f32) {
+        let (dir, reverse_dir) = if sweep >= 0.0 {
+            (Solidity::Hole, Solidity::Solid)
+        } else {
+            (Solidity::Solid, Solidity::Hole)
+        };
+        let end_angle = start_angle + sweep;
+
+        // Start a new sub-path, so that the outer arc isn't connected to the previous one
+        self.move_to(cx + start_angle.cos() * outer_r, cy + start_angle.sin() * outer_r);
+        self.arc(cx, cy, outer_r, start_angle, end_angle, dir);
+        self.arc(cx, cy, inner_r, end_angle, start_angle, reverse_dir);
+        self.close();
+    }
+
+    /// Creates new triangular arrow head shaped sub-path with its tip at x,y, pointing in the
+    /// direction of `angle` in radians. The head is `size` long and as wide at its base.
+    ///
+    /// To put a head on the end of a line, pass its end point and direction, for example as returned
+    /// by [`Path::point_at_length`] at the [`Path::total_length`].
+    pub fn arrow_head(&mut self, x: f32, y: f32, angle: f32, size: f32) {
+        let (sin, cos) = angle.sin_cos();
+        let (dx, dy) = (cos * size, sin * size);
+
+        self.move_to(x, y);
+        self.line_to(x - dx - dy * 0.5, y - dy + dx * 0.5);
+        self.line_to(x - dx + dy * 0.5, y - dy - dx * 0.5);
+        self.close();
+    }
+
+    /// Creates new sub-path connecting all of the given points with straight lines, closing it if
+    /// `closed` is true. See [`Path::catmull_rom`] for a smooth curve through the points.
+    pub fn polyline(&mut self, points: &[(f32, f32)], closed: bool) {
+        let (x, y) = match points.first() {
+            Some(&point) => point,
+            None => return,
+        };
+
+        self.move_to(x, y);
+
+        for &(x, y) in &points[1..] {
+            self.line_to(x, y);
+        }
+
+        if closed {
+            self.close();
+        }
+    }
+
+    /// Creates new smooth sub-path through all of the given points, e.g. to plot data.
+    ///
+    /// The curve is a Catmull-Rom spline made of one bezier segment between each pair of consecutive
+    /// points. `tension` scales the tangents: `1.0` gives the classic Catmull-Rom curve, smaller
+    /// values make the curve tighter and `0.0` results in straight lines. With fewer than three
+    /// points the points are connected with lines.
+    pub fn catmull_rom(&mut self, points: &[(f32, f32)], tension: f32) {
+        let (x, y) = match points.first() {
+            Some(&point) => point,
+            None => return,
+        };
+
+        self.move_to(x, y);
+
+        if points.len() < 3 {
+            for &(x, y) in &points[1..] {
+                self.line_to(x, y);
+            }
+
+            return;
+        }
+
+        let point = |index: usize| {
+            let (x, y) = points[index.min(points.len() - 1)];
+            Position { x, y }
+        };
+
+        let scale = tension / 6.0;
+
+        for i in 0..points.len() - 1 {
+            // The end points are their own neighbours, so the curve starts and ends straight
+            let p0 = point(i.saturating_sub(1));
+            let (p1, p2, p3) = (point(i), point(i + 1), point(i + 2));
+
+            let c1 = p1 + (p2 - p0) * scale;
+            let c2 = p2 - (p3 - p1) * scale;
+
+            self.bezier_to(c1.x, c1.y, c2.x, c2.y, p2.x, p2.y);
+        }
+    }
+
+    /// Returns a simplified copy of this path that stays within `tolerance` of the original.
+    ///
+    /// Runs of line segments are reduced with the Douglas-Peucker algorithm, which removes collinear
+    /// and nearly collinear points. Bezier segments that are flat within the tolerance are treated as
+    /// lines, all other curves are kept as they are.
+    pub fn simplify(&self, tolerance: f32) -> Self {
+        let mut simplified = Self::new();
+        simplified.dist_tol = self.dist_tol;
+
+        // Points of the current run of lines. The first point is already part of the simplified path.
+        let mut run: Vec<Position> = Vec::new();
+
+        for verb in self.verbs() {
+            match verb {
+                Verb::MoveTo(x, y) => {
+                    simplified.append_line_run(&run, tolerance);
+                    simplified.move_to(x, y);
+                    run = vec![Position { x, y }];
+                }
+                Verb::LineTo(x, y) => {
+                    if run.is_empty() {
+                        simplified.line_to(x, y);
+                    }
+
+                    run.push(Position { x, y });
+                }
+                Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                    let start = run.last().copied().unwrap_or(simplified.last_pos);
+                    let end = Position { x, y };
+                    let tol2 = tolerance * tolerance;
+
+                    let is_flat = Position::segment_distance(Position { x: c1x, y: c1y }, start, end) <= tol2
+                        && Position::segment_distance(Position { x: c2x, y: c2y }, start, end) <= tol2;
+
+                    if is_flat && !run.is_empty() {
+                        run.push(end);
+                    } else {
+                        simplified.append_line_run(&run, tolerance);
+                        simplified.bezier_to(c1x, c1y, c2x, c2y, x, y);
+                        run = vec![end];
+                    }
+                }
+                Verb::Solid | Verb::Hole | Verb::Close => {
+                    simplified.append_line_run(&run, tolerance);
+                    run = run.last().copied().into_iter().collect();
+
+                    match verb {
+                        Verb::Solid => simplified.solidity(Solidity::Solid),
+                        Verb::Hole => simplified.solidity(Solidity::Hole),
+                        _ => simplified.close(),
+                    }
+                }
+            }
+        }
+
+        simplified.append_line_run(&run, tolerance);
+
+        simplified
+    }
+
+    // Appends the Douglas-Peucker reduced run of line points, skipping the first point which is
+    // already the current point.
+    fn append_line_run(&mut self, run: &[Position], tolerance: f32) {
+        if run.len() < 2 {
+            return;
+        }
+
+        let tol2 = tolerance * tolerance;
+        let mut keep = vec![false; run.len()];
+        keep[run.len() - 1] = true;
+
+        let mut stack = vec![(0, run.len() - 1)];
+
+        while let Some((first, last)) = stack.pop() {
+            let mut max_dist = 0.0;
+            let mut index = first;
+
+            for i in (first + 1)..last {
+                let dist = Position::segment_distance(run[i], run[first], run[last]);
+
+                if dist > max_dist {
+                    max_dist = dist;
+                    index = i;
+                }
+            }
+
+            if max_dist > tol2 {
+                keep[index] = true;
+                stack.push((first, index));
+                stack.push((index, last));
+            }
+        }
+
+        for (point, _) in run.iter().zip(keep).skip(1).filter(|(_, keep)| *keep) {
+            self.line_to(point.x, point.y);
+        }
+    }
+
+    /// Returns whether the sub-path with the given index winds clockwise on the screen, where the
+    /// y axis points down, or `None` if the path has fewer sub-paths.
+    ///
+    /// Sub-paths are numbered in the order they were started, each move starts a new one. Open
+    /// sub-paths are treated as if they were closed. Curves are flattened to lines that stay within
+    /// `tolerance` of the exact curve, see [`Path::stroke_outline`] for the tolerance used when drawing.
+    pub fn is_clockwise(&self, subpath: usize, tolerance: f32) -> Option<bool> {
+        // The first polyline holds the points before the first move
+        let polylines = self.polylines(tolerance);
+        let polyline = polylines.get(subpath + 1)?;
+
+        let mut area = 0.0;
+
+        for (i, p0) in polyline.iter().enumerate() {
+            let p1 = polyline[(i + 1) % polyline.len()];
+            area += p0.x * p1.y - p1.x * p0.y;
+        }
+
+        Some(area > 0.0)
+    }
+
+    /// Reverses the direction of all sub-paths, keeping their shape.
+    ///
+    /// Closing and the solidity of each sub-path are kept, so filling the path gives the same
+    /// result. The direction matters for strokes with different start and end caps, dashes and
+    /// text drawn along the path.
+    pub fn reverse(&mut self) {
+        let mut reversed = Self::with_capacity(self.verbs.len());
+        reversed.dist_tol = self.dist_tol;
+
+        // Points of the current sub-path, and for each segment to the next point its control points
+        let mut points: Vec<Position> = Vec::new();
+        let mut segments: Vec<Option<(Position, Position)>> = Vec::new();
+        let mut trailing: Vec<PackedVerb> = Vec::new();
+
+        for verb in self.verbs() {
+            match verb {
+                Verb::MoveTo(x, y) => {
+                    reversed.append_reversed(&mut points, &mut segments, &mut trailing);
+                    points.push(Position { x, y });
+                }
+                // Segments before the first move have no start point to reverse from
+                Verb::LineTo(x, y) if points.is_empty() => reversed.line_to(x, y),
+                Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) if points.is_empty() => {
+                    reversed.bezier_to(c1x, c1y, c2x, c2y, x, y)
+                }
+                Verb::LineTo(x, y) => {
+                    points.push(Position { x, y });
+                    segments.push(None);
+                }
+                Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                    points.push(Position { x, y });
+                    segments.push(Some((Position { x: c1x, y: c1y }, Position { x: c2x, y: c2y })));
+                }
+                Verb::Close => trailing.push(PackedVerb::Close),
+                Verb::Solid => trailing.push(PackedVerb::Solid),
+                Verb::Hole => trailing.push(PackedVerb::Hole),
+            }
+        }
+
+        reversed.append_reversed(&mut points, &mut segments, &mut trailing);
+
+        *self = reversed;
+    }
+
+    // Appends the sub-path made of the points and the segments between them in reverse, followed by
+    // the verbs that came after it, and clears them for the next sub-path
+    fn append_reversed(
+        &mut self,
+        points: &mut Vec<Position>,
+        segments: &mut Vec<Option<(Position, Position)>>,
+        trailing: &mut Vec<PackedVerb>,
+    ) {
+        if let Some(&last) = points.last() {
+            self.move_to(last.x, last.y);
+
+            for (i, segment) in segments.iter().enumerate().rev() {
+                let end = points[i];
+
+                match segment {
+                    Some((c1, c2)) => self.bezier_to(c2.x, c2.y, c1.x, c1.y, end.x, end.y),
+                    None => self.line_to(end.x, end.y),
+                }
+            }
+        }
+
+        self.append_packed(trailing, &[]);
+
+        points.clear();
+        segments.clear();
+        trailing.clear();
+    }
+
+    /// Returns the length of the path, with all sub-paths added up and curves measured along their
+    /// flattened approximation.
+    ///
+    /// The flattened lines stay within `tolerance` of the exact curves, see [`Path::stroke_outline`]
+    /// for the tolerance used when drawing. Smaller tolerances measure curves more accurately.
+    pub fn total_length(&self, tolerance: f32) -> f32 {
+        PathMeasure::new(self, tolerance).length()
+    }
+
+    /// Returns the point at the given distance along the path as `(x, y, angle)`, where the angle
+    /// in radians is the direction of the path at that point. Returns `None` for distances outside
+    /// of `0.0..=total_length(tolerance)`.
+    ///
+    /// Sub-paths follow each other in the order they were started, the jumps between them don't
+    /// count towards the distance. Curves are measured like in [`Path::total_length`].
+    pub fn point_at_length(&self, length: f32, tolerance: f32) -> Option<(f32, f32, f32)> {
+        PathMeasure::new(self, tolerance)
+            .sample(length)
+            .map(|(pos, angle)| (pos.x, pos.y, angle))
+    }
+
+    /// Returns the outline of the area covered when stroking this path with the given paint.
+    ///
+    /// The line width, caps, joins and miter limit of the paint are applied by the same tessellation
+    /// that is used for rendering strokes. The result is meant to be filled with
+    /// [`FillRule::NonZero`](crate::FillRule::NonZero), which makes it usable for hit testing thick
+    /// strokes or exporting outlines.
+    ///
+    /// Curves, round caps and round joins are flattened to lines that stay within `tolerance` of the
+    /// exact shape. [`Canvas`](crate::Canvas) flattens with a tolerance of `0.25 / device_pixel_ratio`
+    /// device pixels, so for a path that is drawn untransformed `0.25` gives the same outline.
+    pub fn stroke_outline(&self, paint: &Paint, tolerance: f32) -> Self {
+        let stroke = &paint.stroke;
+
+        let mut cache = PathCache::new(self.verbs(), &Transform2D::identity(), tolerance, self.dist_tol);
+        cache.expand_stroke(
+            stroke.line_width * 0.5,
+            0.0,
+            stroke.line_cap_start,
+            stroke.line_cap_end,
+            stroke.line_join,
+            stroke.miter_limit,
+            tolerance,
+        );
+
+        let mut outline = Self::new();
+
+        for (ring, solidity) in cache.stroke_outlines() {
+            outline.move_to(ring[0].x, ring[0].y);
+
+            for point in &ring[1..] {
+                outline.line_to(point.x, point.y);
+            }
+
+            outline.close();
+            outline.solidity(solidity);
+        }
+
+        outline
+    }
+
+    /// Returns a copy of this path split into dashes, following the given pattern of alternating dash
+    /// and gap lengths.
+    ///
+    /// The pattern starts over at every sub-path, shifted by `offset`. The pattern is expected to have
+    /// an even number of entries with a positive sum, see [`Paint::set_line_dash`](crate::Paint::set_line_dash).
+    /// Otherwise a copy of the path is returned.
+    ///
+    /// Curves are flattened to lines that stay within `tolerance` of the exact curve, see
+    /// [`Path::stroke_outline`] for the tolerance used when drawing.
+    pub fn dashed(&self, pattern: &[f32], offset: f32, tolerance: f32) -> Self {
+        self.dash_polylines(&self.polylines(tolerance), pattern, offset)
+    }
+
+    // Same as `dashed`, but keeps the results along with the flattened sub-paths they were cut from.
+    // The dashes are kept for every offset into the pattern, so an animated offset stops cutting new
+    // ones once it goes through the same offsets again. The tolerance is rounded down to a power of
+    // two, so that a zoom animation only flattens the path again when it crosses one.
+    pub(crate) fn dashed_cached(&self, pattern: &[f32], offset: f32, tolerance: f32) -> Ref<'_, Self> {
+        let total: f32 = pattern.iter().sum();
+        let phase = offset.rem_euclid(total);
+        let key = phase.to_bits();
+
+        let (tolerance_level, tolerance) = if tolerance > 0.0 && tolerance.is_finite() {
+            let level = tolerance.log2().floor() as i32;
+            (level, 2f32.powi(level))
+        } else {
+            (i32::MIN, tolerance)
+        };
+
+        {
+            let mut cache = self.dash_cache.borrow_mut();
+
+            if !matches!(&*cache, Some(cache) if cache.tolerance_level == tolerance_level) {
+                *cache = Some(Box::new(DashCache {
+                    tolerance_level,
+                    polylines: self.polylines(tolerance),
+                    pattern: Vec::new(),
+                    dashed: FnvHashMap::default(),
+                }));
+            }
+
+            let cache = &mut **cache.as_mut().unwrap();
+
+            let full = cache.dashed.len() >= MAX_DASH_PHASES && !cache.dashed.contains_key(&key);
+
+            if cache.pattern != pattern || full {
+                cache.pattern = pattern.to_vec();
+                cache.dashed.clear();
+            }
+
+            cache
+                .dashed
+                .entry(key)
+                .or_insert_with(|| self.dash_polylines(&cache.polylines, pattern, phase));
+        }
+
+        Ref::map(self.dash_cache.borrow(), |cache| &cache.as_ref().unwrap().dashed[&key])
+    }
+
+    fn dash_polylines(&self, polylines: &[Vec<Position>], pattern: &[f32], offset: f32) -> Self {
+        let total: f32 = pattern.iter().sum();
+
+        if pattern.len() % 2 == 1 || total <= 0.0 || !total.is_finite() {
+            return self.clone();
+        }
+
+        let mut dashed = Self::new();
+        dashed.dist_tol = self.dist_tol;
+
+        for polyline in polylines {
+            dashed.dash_polyline(polyline, pattern, offset.rem_euclid(total));
+        }
+
+        dashed
+    }
+
+    /// Returns the sub-paths as polylines, with curves flattened to lines and closed sub-paths ending
+    /// at their start point.
+    pub(crate) fn polylines(&self, tess_tol: f32) -> Vec<Vec<Position>> {
+        let mut polylines = vec![Vec::new()];
+
+        for verb in self.verbs() {
+            // There is always a polyline, the first one collects points before the first move
+            let polyline = polylines.last_mut().unwrap();
+
+            match verb {
+                Verb::MoveTo(x, y) => polylines.push(vec![Position { x, y }]),
+                Verb::LineTo(x, y) => polyline.push(Position { x, y }),
+                Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                    if let Some(&start) = polyline.last() {
+                        let c1 = Position { x: c1x, y: c1y };
+                        let c2 = Position { x: c2x, y: c2y };
+                        let end = Position { x, y };
+                        flatten_bezier(start, c1, c2, end, tess_tol, polyline);
+                    }
+                }
+                Verb::Close => {
+                    if let Some(&start) = polyline.first() {
+                        polyline.push(start);
+                    }
+                }
+                Verb::Solid | Verb::Hole => {}
+            }
+        }
+
+        polylines
+    }
+
+    // Walks along the polyline and adds a sub-path for every dash. `phase` is the distance into the
+    // pattern at the first point.
+    fn dash_polyline(&mut self, polyline: &[Position], pattern: &[f32], mut phase: f32) {
+        let first = match polyline.first() {
+            Some(first) => *first,
+            None => return,
+        };
+
+        let mut index = 0;
+
+        while phase >= pattern[index] {
+            phase -= pattern[index];
+            index = (index + 1) % pattern.len();
+        }
+
+        let mut remaining = pattern[index] - phase;
+
+        if index % 2 == 0 {
+            self.move_to(first.x, first.y);
+        }
+
+        for segment in polyline.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let len = (end - start).mag2().sqrt();
+            let mut pos = 0.0;
+
+            while len - pos > remaining {
+                pos += remaining;
+                let point = start + (end - start) * (pos / len);
+
+                if index % 2 == 0 {
+                    self.line_to(point.x, point.y);
+                } else {
+                    self.move_to(point.x, point.y);
+                }
+
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+            }
+
+            remaining -= len - pos;
+
+            if index % 2 == 0 {
+                self.line_to(end.x, end.y);
+            }
+        }
+    }
+
+    /// Appends all sub-paths of `other` to this path.
+    ///
+    /// The sub-paths keep their solidity, so the combined path can be filled with a single call. If
+    /// `other` doesn't start with a move, its first segment continues the current sub-path.
+    pub fn append(&mut self, other: &Self) {
+        self.append_packed(&other.verbs, &other.coords);
+    }
+
+    /// Appends all sub-paths of `other` to this path, with their points transformed by `transform`.
+    ///
+    /// This bakes the transform into the points, see [`Path::append`].
+    pub fn append_transformed(&mut self, other: &Self, transform: &Transform2D) {
+        let coords: Vec<Position> = other
+            .coords
+            .iter()
+            .map(|pos| {
+                let (x, y) = transform.transform_point(pos.x, pos.y);
+                Position { x, y }
+            })
+            .collect();
+
+        self.append_packed(&other.verbs, &coords);
+    }
+
     /// Appends a slice of verbs to the path
-    fn append(&mut self, verbs: &[PackedVerb], coords: &[Position]) {
+    fn append_packed(&mut self, verbs: &[PackedVerb], coords: &[Position]) {
+        // The flattened geometry no longer matches
+        *self.cache.get_mut() = None;
+        *self.dash_cache.get_mut() = None;
+
         if !coords.is_empty() {
             self.last_pos = coords[coords.len() - 1];
         }
@@ -522,6 +1231,7 @@ impl<'a> Iterator for PathIter<'a> {
     }
 }
 
+#[cfg(feature = "text")]
 impl ttf_parser::OutlineBuilder for Path {
     fn move_to(&mut self, x: f32, y: f32) {
         self.move_to(x, y);
@@ -543,3 +1253,85 @@ impl ttf_parser::OutlineBuilder for Path {
         self.close();
     }
 }
+
+// Appends points approximating the cubic bezier to `points`, leaving out the start point. The number of
+// segments is chosen so that the flattened curve stays within `tolerance` of the curve.
+fn flatten_bezier(
+    start: Position,
+    c1: Position,
+    c2: Position,
+    end: Position,
+    tolerance: f32,
+    points: &mut Vec<Position>,
+) {
+    let dd1 = (c1 - start) - (c2 - c1);
+    let dd2 = (c2 - c1) - (end - c2);
+    let dd = dd1.mag2().max(dd2.mag2()).sqrt();
+
+    let segments = ((0.75 * dd / tolerance).sqrt().ceil() as usize).clamp(1, 100);
+
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0 - t;
+
+        let a = mt * mt * mt;
+        let b = 3.0 * mt * mt * t;
+        let c = 3.0 * mt * t * t;
+        let d = t * t * t;
+
+        points.push(Position {
+            x: a * start.x + b * c1.x + c * c2.x + d * end.x,
+            y: a * start.y + b * c1.y + c * c2.y + d * end.y,
+        });
+    }
+}
+
+/// Arc length parameterization of a path, with all sub-paths joined one after the other.
+pub(crate) struct PathMeasure {
+    polylines: Vec<Vec<Position>>,
+}
+
+impl PathMeasure {
+    pub(crate) fn new(path: &Path, tess_tol: f32) -> Self {
+        Self {
+            polylines: path.polylines(tess_tol),
+        }
+    }
+
+    /// Returns the total length of all sub-paths.
+    pub(crate) fn length(&self) -> f32 {
+        self.polylines
+            .iter()
+            .flat_map(|polyline| polyline.windows(2))
+            .map(|segment| (segment[1] - segment[0]).mag2().sqrt())
+            .sum()
+    }
+
+    /// Returns the point at the given distance from the start of the path and the direction of the
+    /// path there as an angle in radians, or `None` if the distance is outside of the path.
+    pub(crate) fn sample(&self, distance: f32) -> Option<(Position, f32)> {
+        if distance < 0.0 {
+            return None;
+        }
+
+        let mut remaining = distance;
+
+        for segment in self.polylines.iter().flat_map(|polyline| polyline.windows(2)) {
+            let (start, end) = (segment[0], segment[1]);
+            let len = (end - start).mag2().sqrt();
+
+            if len <= 0.0 {
+                continue;
+            }
+
+            if remaining <= len {
+                let dir = end - start;
+                return Some((start + dir * (remaining / len), dir.angle()));
+            }
+
+            remaining -= len;
+        }
+
+        None
+    }
+}
@@ -65,6 +65,8 @@ impl Default for Convexity {
 #[derive(Clone, Debug)]
 pub struct Contour {
     point_range: Range<usize>,
+    // Index of the sub-path in the path, which stays the same when degenerate contours are removed
+    subpath: usize,
     closed: bool,
     bevel: usize,
     solidity: Solidity,
@@ -77,6 +79,7 @@ impl Default for Contour {
     fn default() -> Self {
         Self {
             point_range: 0..0,
+            subpath: 0,
             closed: Default::default(),
             bevel: Default::default(),
             solidity: Default::default(),
@@ -264,11 +267,60 @@ impl PathCache {
         cache
     }
 
+    /// Moves horizontal and vertical segments onto the pixel grid, so that strokes of the given width
+    /// cover whole pixels instead of blurring across two of them.
+    pub(crate) fn snap_to_pixels(&mut self, stroke_width: f32) {
+        const AXIS_TOLERANCE: f32 = 1e-3;
+
+        // Odd widths are centered on pixel centers, even widths on pixel edges
+        let offset = if stroke_width.round() as i32 % 2 == 1 { 0.5 } else { 0.0 };
+        let snap = |value: f32| (value - offset).round() + offset;
+
+        let mut bounds = Bounds::default();
+
+        for contour in &self.contours {
+            let points = &mut self.points[contour.point_range.clone()];
+            let count = points.len();
+            let segments = if contour.closed { count } else { count - 1 };
+
+            for i in 0..segments {
+                let next = (i + 1) % count;
+                let dpos = points[i].dpos;
+
+                if dpos.y.abs() < AXIS_TOLERANCE {
+                    let y = snap(points[i].pos.y);
+                    points[i].pos.y = y;
+                    points[next].pos.y = y;
+                } else if dpos.x.abs() < AXIS_TOLERANCE {
+                    let x = snap(points[i].pos.x);
+                    points[i].pos.x = x;
+                    points[next].pos.x = x;
+                }
+            }
+
+            for i in 0..count {
+                let next = points[(i + 1) % count].pos;
+                let point = &mut points[i];
+
+                point.dpos = next - point.pos;
+                point.len = point.dpos.normalize();
+
+                bounds.minx = bounds.minx.min(point.pos.x);
+                bounds.miny = bounds.miny.min(point.pos.y);
+                bounds.maxx = bounds.maxx.max(point.pos.x);
+                bounds.maxy = bounds.maxy.max(point.pos.y);
+            }
+        }
+
+        self.bounds = bounds;
+    }
+
     fn add_contour(&mut self) {
         let mut contour = Contour::default();
 
         contour.point_range.start = self.points.len();
         contour.point_range.end = self.points.len();
+        contour.subpath = self.contours.len();
 
         self.contours.push(contour);
     }
@@ -480,10 +532,12 @@ impl PathCache {
             return false;
         }
 
+        // Crossings are accumulated over all contours, like the stencil based fill does, so that
+        // holes formed by other contours are respected.
         if fill_rule == FillRule::EvenOdd {
-            for contour in &self.contours {
-                let mut crossing = false;
+            let mut crossing = false;
 
+            for contour in &self.contours {
                 for (p0, p1) in contour.point_pairs(&self.points) {
                     if (p1.pos.y > y) != (p0.pos.y > y)
                         && (x < (p0.pos.x - p1.pos.x) * (y - p1.pos.y) / (p0.pos.y - p1.pos.y) + p1.pos.x)
@@ -491,18 +545,14 @@ impl PathCache {
                         crossing = !crossing;
                     }
                 }
-
-                if crossing {
-                    return true;
-                }
             }
 
-            false
+            crossing
         } else {
             // NonZero
-            for contour in &self.contours {
-                let mut winding_number: i32 = 0;
+            let mut winding_number: i32 = 0;
 
+            for contour in &self.contours {
                 for (p0, p1) in contour.point_pairs(&self.points) {
                     if p0.pos.y <= y {
                         if p1.pos.y > y && Point::is_left(p0, p1, x, y) > 0.0 {
@@ -512,14 +562,45 @@ impl PathCache {
                         winding_number = winding_number.wrapping_sub(1);
                     }
                 }
+            }
+
+            winding_number != 0
+        }
+    }
 
-                if winding_number != 0 {
-                    return true;
+    /// Returns the index of the sub-path the point is in, for points inside the path.
+    ///
+    /// That is the last solid sub-path drawn around the point, or the last hole when the point is
+    /// only inside of holes, as possible with [`FillRule::NonZero`].
+    pub fn subpath_at(&self, x: f32, y: f32, fill_rule: FillRule) -> Option<usize> {
+        if !self.contains_point(x, y, fill_rule) {
+            return None;
+        }
+
+        let around = |contour: &&Contour| {
+            let mut crossing = false;
+
+            for (p0, p1) in contour.point_pairs(&self.points) {
+                if (p1.pos.y > y) != (p0.pos.y > y)
+                    && (x < (p0.pos.x - p1.pos.x) * (y - p1.pos.y) / (p0.pos.y - p1.pos.y) + p1.pos.x)
+                {
+                    crossing = !crossing;
                 }
             }
 
-            false
-        }
+            crossing
+        };
+
+        let last_solid = self
+            .contours
+            .iter()
+            .rev()
+            .filter(around)
+            .find(|contour| contour.solidity == Solidity::Solid);
+
+        last_solid
+            .or_else(|| self.contours.iter().rev().find(around))
+            .map(|contour| contour.subpath)
     }
 
     pub(crate) fn expand_fill(&mut self, fringe_width: f32, line_join: LineJoin, miter_limit: f32) {
@@ -724,6 +805,47 @@ impl PathCache {
         }
     }
 
+    /// Turns the triangle strips produced by [`Self::expand_stroke`] back into closed outlines.
+    ///
+    /// The strips alternate between the left and right side of the stroke. Open contours become a
+    /// single solid ring (left side forward, right side backward), closed contours an outer solid
+    /// ring and an inner hole.
+    pub(crate) fn stroke_outlines(&self) -> Vec<(Vec<Position>, Solidity)> {
+        let mut outlines = Vec::new();
+
+        for contour in &self.contours {
+            let left = contour.stroke.iter().step_by(2);
+            let right = contour.stroke.iter().skip(1).step_by(2);
+
+            let to_pos = |vertex: &Vertex| Position {
+                x: vertex.x,
+                y: vertex.y,
+            };
+
+            if contour.closed {
+                let left = outline_ring(left.map(to_pos));
+                let right = outline_ring(right.map(to_pos));
+
+                let (outer, inner) = if ring_area(&left).abs() >= ring_area(&right).abs() {
+                    (left, right)
+                } else {
+                    (right, left)
+                };
+
+                outlines.push((outer, Solidity::Solid));
+                outlines.push((inner, Solidity::Hole));
+            } else {
+                let right: Vec<_> = right.collect();
+                let ring = outline_ring(left.chain(right.into_iter().rev()).map(to_pos));
+                outlines.push((ring, Solidity::Solid));
+            }
+        }
+
+        outlines.retain(|(ring, _)| ring.len() > 2);
+
+        outlines
+    }
+
     fn calculate_joins(&mut self, stroke_width: f32, line_join: LineJoin, miter_limit: f32) {
         let inv_stroke_width = if stroke_width > 0.0 { 1.0 / stroke_width } else { 0.0 };
 
@@ -1088,6 +1210,44 @@ fn bevel_join(verts: &mut Vec<Vertex>, p0: &Point, p1: &Point, lw: f32, rw: f32,
     }
 }
 
+// Collects the points of an outline, dropping duplicate points and the zero-area spikes that round caps
+// produce by going back and forth through their center point.
+fn outline_ring(points: impl Iterator<Item = Position>) -> Vec<Position> {
+    const TOLERANCE: f32 = 1e-4;
+
+    let mut ring: Vec<Position> = Vec::new();
+
+    for point in points {
+        if ring.len() >= 2 && Position::equals(ring[ring.len() - 2], point, TOLERANCE) {
+            ring.pop();
+            continue;
+        }
+
+        if matches!(ring.last(), Some(last) if Position::equals(*last, point, TOLERANCE)) {
+            continue;
+        }
+
+        ring.push(point);
+    }
+
+    while ring.len() > 1 && Position::equals(ring[0], ring[ring.len() - 1], TOLERANCE) {
+        ring.pop();
+    }
+
+    ring
+}
+
+fn ring_area(ring: &[Position]) -> f32 {
+    let mut area = 0.0;
+
+    for (i, p1) in ring.iter().enumerate() {
+        let p0 = ring[(i + ring.len() - 1) % ring.len()];
+        area += p0.x * p1.y - p1.x * p0.y;
+    }
+
+    area * 0.5
+}
+
 #[cfg(test)]
 mod tests {
 
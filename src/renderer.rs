@@ -4,13 +4,16 @@ use imgref::ImgVec;
 use rgb::RGBA8;
 
 use crate::{
-    geometry::Position, paint::GlyphTexture, Color, CompositeOperationState, ErrorKind, FillRule, ImageFilter, ImageId,
-    ImageInfo, ImageSource, ImageStore,
+    geometry::Position, paint::GlyphTexture, Color, ColorSpace, CompositeOperationState, ErrorKind, FillRule,
+    ImageFilter, ImageId, ImageInfo, ImageSource, ImageStore,
 };
 
 mod opengl;
 pub use opengl::OpenGl;
 
+mod software;
+pub use software::Software;
+
 mod void;
 pub use void::Void;
 
@@ -63,6 +66,7 @@ pub struct Command {
     pub(crate) triangles_verts: Option<(usize, usize)>,
     pub(crate) image: Option<ImageId>,
     pub(crate) glyph_texture: GlyphTexture,
+    pub(crate) mask_image: Option<ImageId>,
     pub(crate) fill_rule: FillRule,
     pub(crate) composite_operation: CompositeOperationState,
 }
@@ -75,10 +79,46 @@ impl Command {
             triangles_verts: Default::default(),
             image: Default::default(),
             glyph_texture: Default::default(),
+            mask_image: Default::default(),
             fill_rule: Default::default(),
             composite_operation: Default::default(),
         }
     }
+
+    /// Number of draw calls and triangles the OpenGL renderer submits for this command.
+    pub(crate) fn draw_stats(&self) -> (usize, usize) {
+        let fans = || fan_or_strip_stats(self.drawables.iter().filter_map(|drawable| drawable.fill_verts));
+        let strips = || fan_or_strip_stats(self.drawables.iter().filter_map(|drawable| drawable.stroke_verts));
+
+        let add = |(a, b): (usize, usize), (c, d): (usize, usize)| (a + c, b + d);
+
+        match self.cmd_type {
+            CommandType::SetRenderTarget(_) | CommandType::ClearRect { .. } => (0, 0),
+            CommandType::ConvexFill { .. } => add(fans(), strips()),
+            CommandType::ConcaveFill { .. } => {
+                let cover = self
+                    .triangles_verts
+                    .map_or((0, 0), |(_, count)| (1, count.saturating_sub(2)));
+                add(add(fans(), strips()), cover)
+            }
+            CommandType::Stroke { .. } => strips(),
+            CommandType::StencilStroke { .. } => {
+                let (calls, triangles) = strips();
+                (calls * 3, triangles * 3)
+            }
+            CommandType::Triangles { .. } => self.triangles_verts.map_or((0, 0), |(_, count)| (1, count / 3)),
+            CommandType::RenderFilteredImage { .. } => {
+                self.triangles_verts.map_or((0, 0), |(_, count)| (2, count / 3 * 2))
+            }
+        }
+    }
+}
+
+// Fans and strips both have two triangles less than vertices
+fn fan_or_strip_stats(ranges: impl Iterator<Item = (usize, usize)>) -> (usize, usize) {
+    ranges.fold((0, 0), |(calls, triangles), (_, count)| {
+        (calls + 1, triangles + count.saturating_sub(2))
+    })
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
@@ -88,33 +128,86 @@ pub enum RenderTarget {
 }
 
 /// This is the main renderer trait that the [Canvas](../struct.Canvas.html) draws to.
+///
+/// The canvas tessellates paths and text into vertices and a list of [`Command`]s, which it hands
+/// to [`Renderer::render`] when flushed. Images are owned by the canvas' image store and created,
+/// updated and deleted through the renderer. [`OpenGl`] renders on the GPU, [`Software`] on the CPU
+/// into an in-memory buffer and [`Void`] discards everything.
 pub trait Renderer {
+    /// Backend specific storage of an image.
     type Image;
+    /// Handle of a texture owned by the underlying graphics API.
     type NativeTexture;
 
+    /// Resizes the screen render target.
     fn set_size(&mut self, width: u32, height: u32, dpi: f32);
 
+    /// Executes the recorded commands. The vertex ranges of the commands index into `verts`.
     fn render(&mut self, images: &mut ImageStore<Self::Image>, verts: &[Vertex], commands: Vec<Command>);
 
+    /// Allocates storage for a new image described by `info`.
     fn alloc_image(&mut self, info: ImageInfo) -> Result<Self::Image, ErrorKind>;
+    /// Wraps an existing texture of the underlying graphics API.
     fn create_image_from_native_texture(
         &mut self,
         native_texture: Self::NativeTexture,
         info: ImageInfo,
     ) -> Result<Self::Image, ErrorKind>;
+    /// Copies `data` into the image, with its top left corner at `x`, `y`.
     fn update_image(&mut self, image: &mut Self::Image, data: ImageSource, x: usize, y: usize)
         -> Result<(), ErrorKind>;
+    /// Returns the texture of the underlying graphics API that backs the image, if there is one.
     #[allow(unused_variables)]
     fn get_native_texture(&self, image: &Self::Image) -> Result<Self::NativeTexture, ErrorKind> {
         Err(ErrorKind::UnsupportedImageFormat)
     }
+    /// Releases the storage of a deleted image.
     fn delete_image(&mut self, image: Self::Image, image_id: ImageId);
 
+    /// Reads back the pixels of the screen render target, top row first.
     fn screenshot(&mut self) -> Result<ImgVec<RGBA8>, ErrorKind>;
+
+    /// Returns the largest width and height of images the renderer can allocate, if it has a limit.
+    fn max_texture_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the features supported by the renderer and the hardware it runs on.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            backend: "unknown",
+            max_texture_size: self.max_texture_size(),
+            msaa_samples: 0,
+            render_to_texture: true,
+        }
+    }
+
+    /// Sets whether the screen converts linear colors to sRGB when they are written, see [`ColorSpace`].
+    #[allow(unused_variables)]
+    fn set_color_space(&mut self, color_space: ColorSpace) {}
+}
+
+/// Features supported by a renderer, see [`Canvas::capabilities`](crate::Canvas::capabilities).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Name of the graphics API, like `"OpenGL"`, `"OpenGL ES"`, `"WebGL 2"` or `"Software"`.
+    pub backend: &'static str,
+    /// The largest width and height of images, if there is a limit.
+    pub max_texture_size: Option<usize>,
+    /// Number of samples per pixel of the screen, or 0 if it isn't multisampled.
+    pub msaa_samples: u32,
+    /// Whether drawing into images with [`RenderTarget::Image`] is supported.
+    pub render_to_texture: bool,
 }
 
 /// Vertex struct for specifying triangle geometry
+///
+/// With the `serde` feature enabled vertices can be serialized, so the output of
+/// [`Canvas::tessellate_fill`](crate::Canvas::tessellate_fill) and
+/// [`Canvas::tessellate_stroke`](crate::Canvas::tessellate_stroke) can be baked offline.
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(C)]
 pub struct Vertex {
     pub x: f32,
@@ -147,6 +240,7 @@ pub enum ShaderType {
     FilterImage,
     FillColor,
     TextureCopyUnclipped,
+    FillConicGradient,
 }
 
 impl Default for ShaderType {
@@ -165,6 +259,7 @@ impl ShaderType {
             Self::FilterImage => 4,
             Self::FillColor => 5,
             Self::TextureCopyUnclipped => 6,
+            Self::FillConicGradient => 7,
         }
     }
     pub fn to_f32(self) -> f32 {
@@ -12,16 +12,16 @@ use rgb::RGBA8;
 
 use crate::{
     renderer::{GlyphTexture, ImageId, Vertex},
-    BlendFactor, Color, CompositeOperationState, ErrorKind, FillRule, ImageFilter, ImageInfo, ImageSource, ImageStore,
-    Scissor,
+    BlendFactor, Color, ColorSpace, CompositeOperationState, ErrorKind, FillRule, ImageFilter, ImageInfo, ImageSource,
+    ImageStore, Scissor,
 };
 
 use glow::HasContext;
 
-use super::{Command, CommandType, Params, RenderTarget, Renderer, ShaderType};
+use super::{Capabilities, Command, CommandType, Params, RenderTarget, Renderer, ShaderType};
 
 mod program;
-use program::MainProgram;
+use program::{with_context, MainProgram};
 
 mod gl_texture;
 use gl_texture::GlTexture;
@@ -32,16 +32,39 @@ use framebuffer::Framebuffer;
 mod uniform_array;
 use uniform_array::UniformArray;
 
+/// Renderer using OpenGL 3, OpenGL ES 2/3 or WebGL.
+///
+/// femtovg shares the OpenGL context with the application and does not save or restore GL state.
+/// When interleaving custom GL calls with femtovg, keep in mind that
+/// [`Canvas::flush`](crate::Canvas::flush) changes the following state and leaves it as described:
+///
+/// * The bound framebuffer, which stays the one of the last render target, see [`Self::current_framebuffer`],
+///   and the viewport, which covers that framebuffer.
+/// * The current program, vertex array, `ARRAY_BUFFER` binding and vertex attributes 0 and 1, which
+///   are unbound respectively disabled again.
+/// * The texture units 0, 1 and 2, which hold the image, glyph and [mask](crate::Canvas::set_mask_image)
+///   textures while drawing. Their `TEXTURE_2D` bindings are cleared and unit 0 is left active.
+/// * Blending (enabled, with a changing blend function), face culling (disabled, with back faces
+///   culled and counter-clockwise front faces), depth and scissor tests (disabled), the stencil
+///   test (disabled) as well as the color mask, stencil mask, stencil function and stencil operation.
+/// * `FRAMEBUFFER_SRGB` on desktop OpenGL, depending on the [color space](crate::Canvas::set_color_space).
+///
+/// Uploading image data, for example when text is rendered into the glyph atlas, additionally changes
+/// the `UNPACK_*` pixel store parameters and the `TEXTURE_2D` binding of the active texture unit.
+///
+/// Applications that rely on any of this state should set it again after flushing.
 pub struct OpenGl {
     debug: bool,
     antialias: bool,
     is_opengles_2_0: bool,
+    max_texture_size: usize,
+    msaa_samples: u32,
     view: [f32; 2],
     screen_view: [f32; 2],
     // All types of the vertex/fragment shader, indexed by shader_type when has_glyph_texture is true
-    main_programs_with_glyph_texture: [Option<MainProgram>; 7],
+    main_programs_with_glyph_texture: [Option<MainProgram>; 8],
     // Same shader programs but with has_glyph_texture being false
-    main_programs_without_glyph_texture: [Option<MainProgram>; 7],
+    main_programs_without_glyph_texture: [Option<MainProgram>; 8],
     current_program: u8,
     current_program_needs_glyph_texture: bool,
     vert_arr: Option<<glow::Context as glow::HasContext>::VertexArray>,
@@ -157,16 +180,36 @@ impl OpenGl {
                         false,
                     )?)
                 },
+                Some(MainProgram::new(
+                    &context,
+                    antialias,
+                    ShaderType::FillConicGradient,
+                    with_glyph_texture,
+                )?),
             ])
         };
 
-        let main_programs_with_glyph_texture = generate_shader_program_variants(true)?;
-        let main_programs_without_glyph_texture = generate_shader_program_variants(false)?;
+        // Shader errors on old drivers are easier to make sense of knowing which driver it was
+        let describe_driver = |error| {
+            let (version, renderer) = unsafe {
+                (
+                    context.get_parameter_string(glow::VERSION),
+                    context.get_parameter_string(glow::RENDERER),
+                )
+            };
+
+            with_context(error, &format!("{version} on {renderer}"))
+        };
+
+        let main_programs_with_glyph_texture = generate_shader_program_variants(true).map_err(describe_driver)?;
+        let main_programs_without_glyph_texture = generate_shader_program_variants(false).map_err(describe_driver)?;
 
         let mut opengl = OpenGl {
             debug,
             antialias,
             is_opengles_2_0: false,
+            max_texture_size: 0,
+            msaa_samples: 0,
             view: [0.0, 0.0],
             screen_view: [0.0, 0.0],
             main_programs_with_glyph_texture,
@@ -183,6 +226,9 @@ impl OpenGl {
 
         unsafe {
             opengl.is_opengles_2_0 = is_opengles_2_0;
+            opengl.max_texture_size = opengl.context.get_parameter_i32(glow::MAX_TEXTURE_SIZE).max(0) as usize;
+            // The screen framebuffer is bound while the renderer is created
+            opengl.msaa_samples = opengl.context.get_parameter_i32(glow::SAMPLES).max(0) as u32;
 
             opengl.vert_arr = opengl.context.create_vertex_array().ok();
             opengl.vert_buff = opengl.context.create_buffer().ok();
@@ -246,7 +292,7 @@ impl OpenGl {
     }
 
     fn convex_fill(&mut self, images: &ImageStore<GlTexture>, cmd: &Command, gpu_paint: &Params) {
-        self.set_uniforms(images, gpu_paint, cmd.image, cmd.glyph_texture);
+        self.set_uniforms(images, gpu_paint, cmd.image, cmd.glyph_texture, cmd.mask_image);
 
         for drawable in &cmd.drawables {
             if let Some((start, count)) = drawable.fill_verts {
@@ -281,7 +327,7 @@ impl OpenGl {
             //glow::DepthMask(glow::FALSE);
         }
 
-        self.set_uniforms(images, stencil_paint, None, GlyphTexture::None);
+        self.set_uniforms(images, stencil_paint, None, GlyphTexture::None, None);
 
         unsafe {
             self.context
@@ -306,7 +352,7 @@ impl OpenGl {
             //glow::DepthMask(glow::TRUE);
         }
 
-        self.set_uniforms(images, fill_paint, cmd.image, cmd.glyph_texture);
+        self.set_uniforms(images, fill_paint, cmd.image, cmd.glyph_texture, cmd.mask_image);
 
         if self.antialias {
             unsafe {
@@ -349,7 +395,7 @@ impl OpenGl {
     }
 
     fn stroke(&mut self, images: &ImageStore<GlTexture>, cmd: &Command, paint: &Params) {
-        self.set_uniforms(images, paint, cmd.image, cmd.glyph_texture);
+        self.set_uniforms(images, paint, cmd.image, cmd.glyph_texture, cmd.mask_image);
 
         for drawable in &cmd.drawables {
             if let Some((start, count)) = drawable.stroke_verts {
@@ -373,7 +419,7 @@ impl OpenGl {
             self.context.stencil_op(glow::KEEP, glow::KEEP, glow::INCR);
         }
 
-        self.set_uniforms(images, paint2, cmd.image, cmd.glyph_texture);
+        self.set_uniforms(images, paint2, cmd.image, cmd.glyph_texture, cmd.mask_image);
 
         for drawable in &cmd.drawables {
             if let Some((start, count)) = drawable.stroke_verts {
@@ -385,7 +431,7 @@ impl OpenGl {
         }
 
         // Draw anti-aliased pixels.
-        self.set_uniforms(images, paint1, cmd.image, cmd.glyph_texture);
+        self.set_uniforms(images, paint1, cmd.image, cmd.glyph_texture, cmd.mask_image);
 
         unsafe {
             self.context.stencil_func(glow::EQUAL, 0x0, 0xff);
@@ -426,7 +472,7 @@ impl OpenGl {
     }
 
     fn triangles(&mut self, images: &ImageStore<GlTexture>, cmd: &Command, paint: &Params) {
-        self.set_uniforms(images, paint, cmd.image, cmd.glyph_texture);
+        self.set_uniforms(images, paint, cmd.image, cmd.glyph_texture, cmd.mask_image);
 
         if let Some((start, count)) = cmd.triangles_verts {
             unsafe {
@@ -443,6 +489,7 @@ impl OpenGl {
         paint: &Params,
         image_tex: Option<ImageId>,
         glyph_tex: GlyphTexture,
+        mask_tex: Option<ImageId>,
     ) {
         self.select_main_program(paint);
         let arr = UniformArray::from(paint);
@@ -458,7 +505,9 @@ impl OpenGl {
 
         let glyphtex = match glyph_tex {
             GlyphTexture::None => None,
-            GlyphTexture::AlphaMask(id) | GlyphTexture::ColorTexture(id) => images.get(id).map(|tex| tex.id()),
+            GlyphTexture::AlphaMask(id) | GlyphTexture::ColorTexture(id) | GlyphTexture::Sdf(id, _) => {
+                images.get(id).map(|tex| tex.id())
+            }
         };
 
         unsafe {
@@ -466,6 +515,13 @@ impl OpenGl {
             self.context.bind_texture(glow::TEXTURE_2D, glyphtex);
         }
 
+        let masktex = mask_tex.and_then(|id| images.get(id)).map(|tex| tex.id());
+
+        unsafe {
+            self.context.active_texture(glow::TEXTURE0 + 2);
+            self.context.bind_texture(glow::TEXTURE_2D, masktex);
+        }
+
         self.check_error("set_uniforms texture");
     }
 
@@ -536,6 +592,38 @@ impl OpenGl {
         }
     }
 
+    /// Returns the framebuffer object that femtovg renders into for the current render target, or
+    /// `None` for the default framebuffer (normally the window).
+    ///
+    /// This is the framebuffer left bound after [`Canvas::flush`](crate::Canvas::flush), so custom GL
+    /// calls can bind it again after drawing elsewhere, to continue drawing into the same target.
+    pub fn current_framebuffer(&self) -> Option<<glow::Context as glow::HasContext>::Framebuffer> {
+        match self.current_render_target {
+            RenderTarget::Screen => self.screen_target.as_ref().map(Framebuffer::fbo),
+            RenderTarget::Image(id) => match self.framebuffers.get(&id) {
+                Some(Ok(framebuffer)) => Some(framebuffer.fbo()),
+                _ => None,
+            },
+        }
+    }
+
+    /// Make the "Screen" RenderTarget render into an offscreen framebuffer of the given size, owned by
+    /// the renderer. This allows rendering with a context that has no window surface, for example a
+    /// pbuffer or surfaceless context in tests. The result can be read back with
+    /// [`Canvas::screenshot`](crate::Canvas::screenshot).
+    ///
+    /// The canvas size should be set to the same size. Calling this again replaces the framebuffer,
+    /// [`Self::set_screen_target`] replaces or removes it.
+    pub fn set_offscreen_target(&mut self, width: u32, height: u32) -> Result<(), ErrorKind> {
+        let framebuffer = Framebuffer::new_offscreen(&self.context, width, height)?;
+        framebuffer.bind();
+
+        self.screen_target = Some(framebuffer);
+        self.set_size(width, height, 1.0);
+
+        Ok(())
+    }
+
     fn render_filtered_image(
         &mut self,
         images: &mut ImageStore<GlTexture>,
@@ -656,6 +744,8 @@ impl OpenGl {
                 self.context.bind_texture(glow::TEXTURE_2D, None);
                 self.context.active_texture(glow::TEXTURE0 + 1);
                 self.context.bind_texture(glow::TEXTURE_2D, None);
+                self.context.active_texture(glow::TEXTURE0 + 2);
+                self.context.bind_texture(glow::TEXTURE_2D, None);
             }
 
             self.main_program().unbind();
@@ -664,9 +754,10 @@ impl OpenGl {
 
             let program = self.main_program();
             program.bind();
-            // Bind the two uniform samplers to texture units
+            // Bind the uniform samplers to texture units
             program.set_tex(0);
             program.set_glyphtex(1);
+            program.set_masktex(2);
             program.set_view(self.view);
         }
     }
@@ -711,6 +802,8 @@ impl Renderer for OpenGl {
             self.context.bind_texture(glow::TEXTURE_2D, None);
             self.context.active_texture(glow::TEXTURE0 + 1);
             self.context.bind_texture(glow::TEXTURE_2D, None);
+            self.context.active_texture(glow::TEXTURE0 + 2);
+            self.context.bind_texture(glow::TEXTURE_2D, None);
 
             self.context.bind_vertex_array(self.vert_arr);
 
@@ -778,6 +871,11 @@ impl Renderer for OpenGl {
 
             self.context.disable(glow::CULL_FACE);
             self.context.bind_buffer(glow::ARRAY_BUFFER, None);
+            self.context.active_texture(glow::TEXTURE0 + 2);
+            self.context.bind_texture(glow::TEXTURE_2D, None);
+            self.context.active_texture(glow::TEXTURE0 + 1);
+            self.context.bind_texture(glow::TEXTURE_2D, None);
+            self.context.active_texture(glow::TEXTURE0);
             self.context.bind_texture(glow::TEXTURE_2D, None);
         }
 
@@ -818,6 +916,10 @@ impl Renderer for OpenGl {
         let w = self.view[0] as usize;
         let h = self.view[1] as usize;
 
+        if let (RenderTarget::Screen, Some(framebuffer)) = (self.current_render_target, &self.screen_target) {
+            framebuffer.bind();
+        }
+
         let mut image = ImgVec::new(
             vec![
                 RGBA8 {
@@ -852,6 +954,41 @@ impl Renderer for OpenGl {
 
         Ok(ImgVec::new(flipped, w, h))
     }
+
+    fn set_color_space(&mut self, color_space: ColorSpace) {
+        // OpenGL ES and WebGL always convert when writing to an sRGB framebuffer, desktop OpenGL only when enabled
+        if self.context.version().is_embedded {
+            return;
+        }
+
+        unsafe {
+            match color_space {
+                ColorSpace::Srgb => self.context.disable(glow::FRAMEBUFFER_SRGB),
+                ColorSpace::Linear => self.context.enable(glow::FRAMEBUFFER_SRGB),
+            }
+        }
+    }
+
+    fn max_texture_size(&self) -> Option<usize> {
+        Some(self.max_texture_size).filter(|size| *size > 0)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let backend = if cfg!(target_arch = "wasm32") {
+            "WebGL 2"
+        } else if self.context.version().is_embedded {
+            "OpenGL ES"
+        } else {
+            "OpenGL"
+        };
+
+        Capabilities {
+            backend,
+            max_texture_size: self.max_texture_size(),
+            msaa_samples: self.msaa_samples,
+            render_to_texture: true,
+        }
+    }
 }
 
 impl Drop for OpenGl {
@@ -10,6 +10,7 @@ pub struct Framebuffer {
     context: Rc<glow::Context>,
     fbo: <glow::Context as glow::HasContext>::Framebuffer,
     stencil_rbo: Option<<glow::Context as glow::HasContext>::Renderbuffer>,
+    color_rbo: Option<<glow::Context as glow::HasContext>::Renderbuffer>,
 }
 
 impl Framebuffer {
@@ -18,10 +19,11 @@ impl Framebuffer {
             context: context.clone(),
             fbo,
             stencil_rbo: None,
+            color_rbo: None,
         }
     }
     pub fn new(context: &Rc<glow::Context>, texture: &GlTexture) -> Result<Self, ErrorKind> {
-        let fbo = unsafe { context.create_framebuffer().unwrap() };
+        let fbo = unsafe { context.create_framebuffer() }.map_err(ErrorKind::RenderTargetError)?;
         unsafe {
             context.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
         }
@@ -52,29 +54,7 @@ impl Framebuffer {
                 Some(stencil_rbo),
             );
 
-            let status = context.check_framebuffer_status(glow::FRAMEBUFFER);
-
-            if status != glow::FRAMEBUFFER_COMPLETE {
-                let reason = match status {
-                    glow::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => {
-                        format!("({status}) Framebuffer incomplete attachment")
-                    }
-                    //glow::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => format!("({}) Framebuffer incomplete draw buffer", status),
-                    //glow::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => format!("({}) Framebuffer incomplete layer targets", status),
-                    //FIXME: will be in next glow release: glow::FRAMEBUFFER_INCOMPLETE_DIMENSIONS => format!("({}) Framebuffer incomplete dimensions", status),
-                    glow::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => {
-                        format!("({status}) Framebuffer incomplete missing attachment")
-                    }
-                    glow::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => {
-                        format!("({status}) Framebuffer incomplete multisample")
-                    }
-                    //glow::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => format!("({}) Framebuffer incomplete read buffer", status),
-                    glow::FRAMEBUFFER_UNSUPPORTED => format!("({status}) Framebuffer unsupported"),
-                    _ => format!("({status}) Framebuffer not complete!"),
-                };
-
-                return Err(ErrorKind::RenderTargetError(reason));
-            }
+            Self::check_status(context)?;
 
             context.bind_framebuffer(glow::FRAMEBUFFER, None);
         }
@@ -83,9 +63,89 @@ impl Framebuffer {
             context: context.clone(),
             fbo,
             stencil_rbo: Some(stencil_rbo),
+            color_rbo: None,
         })
     }
 
+    /// Creates a framebuffer that owns its color and stencil storage, for rendering without a window.
+    pub fn new_offscreen(context: &Rc<glow::Context>, width: u32, height: u32) -> Result<Self, ErrorKind> {
+        let fbo = unsafe { context.create_framebuffer() }.map_err(ErrorKind::RenderTargetError)?;
+
+        let (color_rbo, stencil_rbo) = unsafe {
+            context.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
+
+            let color_rbo = context.create_renderbuffer().unwrap();
+            context.bind_renderbuffer(glow::RENDERBUFFER, Some(color_rbo));
+            context.renderbuffer_storage(glow::RENDERBUFFER, glow::RGBA8, width as i32, height as i32);
+            context.framebuffer_renderbuffer(
+                glow::FRAMEBUFFER,
+                glow::COLOR_ATTACHMENT0,
+                glow::RENDERBUFFER,
+                Some(color_rbo),
+            );
+
+            let stencil_rbo = context.create_renderbuffer().unwrap();
+            context.bind_renderbuffer(glow::RENDERBUFFER, Some(stencil_rbo));
+            context.renderbuffer_storage(glow::RENDERBUFFER, glow::STENCIL_INDEX8, width as i32, height as i32);
+            context.framebuffer_renderbuffer(
+                glow::FRAMEBUFFER,
+                glow::STENCIL_ATTACHMENT,
+                glow::RENDERBUFFER,
+                Some(stencil_rbo),
+            );
+            context.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+            (color_rbo, stencil_rbo)
+        };
+
+        // Dropping the framebuffer releases the render buffers, also on error
+        let framebuffer = Framebuffer {
+            context: context.clone(),
+            fbo,
+            stencil_rbo: Some(stencil_rbo),
+            color_rbo: Some(color_rbo),
+        };
+
+        unsafe {
+            Self::check_status(context)?;
+            context.bind_framebuffer(glow::FRAMEBUFFER, None);
+        }
+
+        Ok(framebuffer)
+    }
+
+    unsafe fn check_status(context: &glow::Context) -> Result<(), ErrorKind> {
+        let status = context.check_framebuffer_status(glow::FRAMEBUFFER);
+
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            let reason = match status {
+                glow::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => {
+                    format!("({status}) Framebuffer incomplete attachment")
+                }
+                //glow::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => format!("({}) Framebuffer incomplete draw buffer", status),
+                //glow::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => format!("({}) Framebuffer incomplete layer targets", status),
+                //FIXME: will be in next glow release: glow::FRAMEBUFFER_INCOMPLETE_DIMENSIONS => format!("({}) Framebuffer incomplete dimensions", status),
+                glow::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => {
+                    format!("({status}) Framebuffer incomplete missing attachment")
+                }
+                glow::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => {
+                    format!("({status}) Framebuffer incomplete multisample")
+                }
+                //glow::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => format!("({}) Framebuffer incomplete read buffer", status),
+                glow::FRAMEBUFFER_UNSUPPORTED => format!("({status}) Framebuffer unsupported"),
+                _ => format!("({status}) Framebuffer not complete!"),
+            };
+
+            return Err(ErrorKind::RenderTargetError(reason));
+        }
+
+        Ok(())
+    }
+
+    pub fn fbo(&self) -> <glow::Context as glow::HasContext>::Framebuffer {
+        self.fbo
+    }
+
     pub fn bind(&self) {
         unsafe {
             self.context.bind_framebuffer(glow::FRAMEBUFFER, Some(self.fbo));
@@ -131,6 +191,9 @@ impl Drop for Framebuffer {
             if let Some(stencil_rbo) = self.stencil_rbo {
                 self.context.delete_renderbuffer(stencil_rbo);
             }
+            if let Some(color_rbo) = self.color_rbo {
+                self.context.delete_renderbuffer(color_rbo);
+            }
         }
     }
 }
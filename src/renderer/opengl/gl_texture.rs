@@ -1,6 +1,7 @@
-use std::rc::Rc;
+use std::{borrow::Cow, rc::Rc};
 
 use glow::HasContext;
+use imgref::ImgRef;
 
 use crate::{ErrorKind, ImageFlags, ImageInfo, ImageSource, PixelFormat};
 
@@ -21,8 +22,9 @@ impl GlTexture {
     pub fn new(context: &Rc<glow::Context>, info: ImageInfo, opengles_2_0: bool) -> Result<Self, ErrorKind> {
         //let size = src.dimensions();
 
-        let id = unsafe {
-            let id = context.create_texture().unwrap();
+        let id = unsafe { context.create_texture() }.map_err(ErrorKind::TextureAllocationError)?;
+
+        unsafe {
             context.bind_texture(glow::TEXTURE_2D, Some(id));
             context.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
             if !opengles_2_0 {
@@ -30,8 +32,7 @@ impl GlTexture {
                 context.pixel_store_i32(glow::UNPACK_SKIP_PIXELS, 0);
                 context.pixel_store_i32(glow::UNPACK_SKIP_ROWS, 0);
             }
-            id
-        };
+        }
 
         let texture = Self { id, info, owned: true };
 
@@ -195,13 +196,13 @@ impl GlTexture {
             context.bind_texture(glow::TEXTURE_2D, Some(self.id));
             context.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
             if !opengles_2_0 {
-                context.pixel_store_i32(glow::UNPACK_ROW_LENGTH, size.width as i32);
+                context.pixel_store_i32(glow::UNPACK_ROW_LENGTH, src.stride() as i32);
             }
         }
 
         match src {
             ImageSource::Gray(data) => unsafe {
-                let format = if opengles_2_0 { glow::LUMINANCE } else { glow::R8 };
+                let format = if opengles_2_0 { glow::LUMINANCE } else { glow::RED };
 
                 context.tex_sub_image_2d(
                     glow::TEXTURE_2D,
@@ -212,7 +213,7 @@ impl GlTexture {
                     size.height as i32,
                     format,
                     glow::UNSIGNED_BYTE,
-                    glow::PixelUnpackData::Slice(data.buf().align_to().1),
+                    glow::PixelUnpackData::Slice(unpack_rows(data, opengles_2_0).align_to().1),
                 );
             },
             ImageSource::Rgb(data) => unsafe {
@@ -225,7 +226,7 @@ impl GlTexture {
                     size.height as i32,
                     glow::RGB,
                     glow::UNSIGNED_BYTE,
-                    glow::PixelUnpackData::Slice(data.buf().align_to().1),
+                    glow::PixelUnpackData::Slice(unpack_rows(data, opengles_2_0).align_to().1),
                 );
             },
             ImageSource::Rgba(data) => unsafe {
@@ -238,7 +239,7 @@ impl GlTexture {
                     size.height as i32,
                     glow::RGBA,
                     glow::UNSIGNED_BYTE,
-                    glow::PixelUnpackData::Slice(data.buf().align_to().1),
+                    glow::PixelUnpackData::Slice(unpack_rows(data, opengles_2_0).align_to().1),
                 );
             },
             #[cfg(target_arch = "wasm32")]
@@ -287,3 +288,12 @@ impl GlTexture {
         self.info
     }
 }
+
+// GLES 2.0 has no UNPACK_ROW_LENGTH, so sources with padded rows need to be repacked before the upload.
+fn unpack_rows<P: Copy>(data: ImgRef<'_, P>, opengles_2_0: bool) -> Cow<'_, [P]> {
+    if opengles_2_0 && data.stride() != data.width() {
+        Cow::Owned(data.pixels().collect())
+    } else {
+        Cow::Borrowed(data.buf())
+    }
+}
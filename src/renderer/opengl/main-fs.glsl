@@ -1,7 +1,7 @@
 
 precision highp float;
 
-#define UNIFORMARRAY_SIZE 14
+#define UNIFORMARRAY_SIZE 16
 
 uniform vec4 frag[UNIFORMARRAY_SIZE];
 
@@ -22,9 +22,18 @@ uniform vec4 frag[UNIFORMARRAY_SIZE];
 #define imageBlurFilterDirection frag[11].yz
 #define imageBlurFilterSigma frag[11].w
 #define imageBlurFilterCoeff frag[12].xyz
+#define sdfScale frag[12].w
+#define maskType int(frag[0].w)
+#define maskRowX frag[13].xyz
+#define maskRowY frag[14].xyz
+#define scissorFeather frag[1].w
+#define scissorFeatherExt vec2(frag[2].w, frag[3].w)
+#define scissorFeatherRowX frag[15].xyz
+#define scissorFeatherRowY vec3(frag[13].w, frag[14].w, frag[15].w)
 
 uniform sampler2D tex;
 uniform sampler2D glyphtex;
+uniform sampler2D masktex;
 uniform vec2 viewSize;
 
 varying vec2 ftcoord;
@@ -37,6 +46,7 @@ varying vec2 fpos;
  #define SHADER_TYPE_FilterImage 4
  #define SHADER_TYPE_FillColor 5
  #define SHADER_TYPE_TextureCopyUnclipped 6
+ #define SHADER_TYPE_FillConicGradient 7
 
 float sdroundrect(vec2 pt, vec2 ext, float rad) {
     vec2 ext2 = ext - vec2(rad,rad);
@@ -51,6 +61,22 @@ float scissorMask(vec2 p) {
     return clamp(sc.x,0.0,1.0) * clamp(sc.y,0.0,1.0);
 }
 
+// Soft edges of the scissor, fading from the edges inwards
+float scissorFeatherMask(vec2 p) {
+    if (scissorFeather <= 0.0) return 1.0;
+    vec3 pos = vec3(p, 1.0);
+    vec2 sc = (scissorFeatherExt - abs(vec2(dot(scissorFeatherRowX, pos), dot(scissorFeatherRowY, pos)))) / scissorFeather;
+    return clamp(sc.x,0.0,1.0) * clamp(sc.y,0.0,1.0);
+}
+
+// Alpha of the mask image set on the canvas
+float maskAlpha(vec2 p) {
+    if (maskType == 0) return 1.0;
+    vec3 pos = vec3(p, 1.0);
+    vec4 mask = texture2D(masktex, vec2(dot(maskRowX, pos), dot(maskRowY, pos)));
+    return maskType == 2 ? mask.x : mask.w;
+}
+
 #ifdef EDGE_AA
 // Stroke - from [0..1] to clipped pyramid, where the slope is 1px.
 float strokeMask() {
@@ -78,6 +104,14 @@ vec4 renderImageGradient() {
     return texture2D(tex, vec2(d, 0.0));//mix(innerCol,outerCol,d);
 }
 
+// Conic gradient; sample a texture using the angle around the center.
+vec4 renderConicGradient() {
+    vec2 pt = (paintMat * vec3(fpos, 1.0)).xy;
+
+    float d = fract(atan(pt.y, pt.x) / 6.28318530718);
+    return texture2D(tex, vec2(d, 0.0));
+}
+
 vec4 renderImage() {
     // Calculate color from texture
     vec2 pt = (paintMat * vec3(fpos, 1.0)).xy / extent;
@@ -152,6 +186,9 @@ void main(void) {
 #elif SELECT_SHADER == SHADER_TYPE_FillImageGradient
     // Image-based Gradient; sample a texture using the gradient position.
     result = renderImageGradient();
+#elif SELECT_SHADER == SHADER_TYPE_FillConicGradient
+    // Conic gradient
+    result = renderConicGradient();
 #elif SELECT_SHADER == SHADER_TYPE_FillImage
     // Image
     result = renderImage();
@@ -172,7 +209,7 @@ void main(void) {
 #error A shader variant must be selected with the SELECT_SHADER pre-processor variable
 #endif
 
-    float scissor = scissorMask(fpos);
+    float scissor = scissorMask(fpos) * scissorFeatherMask(fpos) * maskAlpha(fpos);
 
 #ifdef ENABLE_GLYPH_TEXTURE
     // Textured tris
@@ -180,6 +217,8 @@ void main(void) {
 
     if (glyphTextureType == 1) {
         mask = vec4(mask.x);
+    } else if (glyphTextureType == 3) {
+        mask = vec4(clamp((mask.x - 0.5) * sdfScale + 0.5, 0.0, 1.0));
     } else {
         result = vec4(1, 1, 1, 1);
         mask = vec4(mask.xyz * mask.w, mask.w);
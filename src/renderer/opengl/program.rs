@@ -13,7 +13,7 @@ pub(crate) struct Shader {
 
 impl Shader {
     pub fn new(context: &Rc<glow::Context>, src: &str, kind: u32) -> Result<Self, ErrorKind> {
-        let id = unsafe { context.create_shader(kind).unwrap() };
+        let id = unsafe { context.create_shader(kind) }.map_err(ErrorKind::ShaderCompileError)?;
 
         // Compile
         unsafe {
@@ -64,7 +64,7 @@ impl Program {
     pub fn new(context: &Rc<glow::Context>, shaders: &[Shader], attrib_locations: &[&str]) -> Result<Self, ErrorKind> {
         let program = Self {
             context: context.clone(),
-            id: unsafe { context.create_program().unwrap() },
+            id: unsafe { context.create_program() }.map_err(ErrorKind::ShaderLinkError)?,
         };
 
         // Attach stages
@@ -135,6 +135,7 @@ pub struct MainProgram {
     loc_viewsize: <glow::Context as glow::HasContext>::UniformLocation,
     loc_tex: Option<<glow::Context as glow::HasContext>::UniformLocation>,
     loc_glyphtex: Option<<glow::Context as glow::HasContext>::UniformLocation>,
+    loc_masktex: Option<<glow::Context as glow::HasContext>::UniformLocation>,
     loc_frag: Option<<glow::Context as glow::HasContext>::UniformLocation>,
 }
 
@@ -164,14 +165,26 @@ impl MainProgram {
             include_str!("main-fs.glsl")
         );
 
-        let vert_shader = Shader::new(context, &vert_shader_src, glow::VERTEX_SHADER)?;
-        let frag_shader = Shader::new(context, &frag_shader_src, glow::FRAGMENT_SHADER)?;
+        // Name the failing variant in errors, the info logs of some drivers are not very helpful
+        let variant = format!(
+            "{shader_type:?} shader{}",
+            if with_glyph_texture { " with glyph texture" } else { "" }
+        );
+
+        let vert_shader = Shader::new(context, &vert_shader_src, glow::VERTEX_SHADER)
+            .map_err(|error| with_context(error, &variant))?;
+        let frag_shader = Shader::new(context, &frag_shader_src, glow::FRAGMENT_SHADER)
+            .map_err(|error| with_context(error, &variant))?;
 
-        let program = Program::new(context, &[vert_shader, frag_shader], &["vertex", "tcoord"])?;
+        let program = Program::new(context, &[vert_shader, frag_shader], &["vertex", "tcoord"])
+            .map_err(|error| with_context(error, &variant))?;
 
-        let loc_viewsize = program.uniform_location("viewSize").unwrap();
+        let loc_viewsize = program
+            .uniform_location("viewSize")
+            .ok_or_else(|| ErrorKind::ShaderLinkError(format!("{variant}: uniform viewSize not found")))?;
         let loc_tex = program.uniform_location("tex");
         let loc_glyphtex = program.uniform_location("glyphtex");
+        let loc_masktex = program.uniform_location("masktex");
         let loc_frag = program.uniform_location("frag");
 
         Ok(Self {
@@ -180,6 +193,7 @@ impl MainProgram {
             loc_viewsize,
             loc_tex,
             loc_glyphtex,
+            loc_masktex,
             loc_frag,
         })
     }
@@ -196,6 +210,12 @@ impl MainProgram {
         }
     }
 
+    pub(crate) fn set_masktex(&self, tex: i32) {
+        unsafe {
+            self.context.uniform_1_i32(self.loc_masktex.as_ref(), tex);
+        }
+    }
+
     pub(crate) fn set_view(&self, view: [f32; 2]) {
         unsafe {
             self.context.uniform_2_f32_slice(Some(&self.loc_viewsize), &view);
@@ -216,3 +236,12 @@ impl MainProgram {
         self.program.unbind();
     }
 }
+
+/// Prefixes the message of shader errors with the given context.
+pub(crate) fn with_context(error: ErrorKind, context: &str) -> ErrorKind {
+    match error {
+        ErrorKind::ShaderCompileError(message) => ErrorKind::ShaderCompileError(format!("{context}: {message}")),
+        ErrorKind::ShaderLinkError(message) => ErrorKind::ShaderLinkError(format!("{context}: {message}")),
+        error => error,
+    }
+}
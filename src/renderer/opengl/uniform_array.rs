@@ -1,16 +1,13 @@
 use super::Params;
 
-const UNIFORMARRAY_SIZE: usize = 14;
+// OpenGL ES 2.0 only guarantees 16 uniform vectors in fragment shaders
+const UNIFORMARRAY_SIZE: usize = 16;
 
 pub struct UniformArray([f32; UNIFORMARRAY_SIZE * 4]);
 
 impl Default for UniformArray {
     fn default() -> Self {
-        Self([
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0,
-        ])
+        Self([0.0; UNIFORMARRAY_SIZE * 4])
     }
 }
 
@@ -86,6 +83,37 @@ impl UniformArray {
     pub fn set_image_blur_filter_coeff(&mut self, coeff: [f32; 3]) {
         self.0[48..51].copy_from_slice(&coeff);
     }
+
+    pub fn set_sdf_scale(&mut self, sdf_scale: f32) {
+        self.0[51] = sdf_scale;
+    }
+
+    // Only the two rows of the affine part are stored, x in frag[13] and y in frag[14]
+    pub fn set_mask_mat(&mut self, mat: [f32; 12]) {
+        self.0[52..55].copy_from_slice(&[mat[0], mat[4], mat[8]]);
+        self.0[56..59].copy_from_slice(&[mat[1], mat[5], mat[9]]);
+    }
+
+    // Uses the unused w component of the scissor matrix, so it must be set after it
+    pub fn set_mask_type(&mut self, mask_type: f32) {
+        self.0[3] = mask_type;
+    }
+
+    // Rows of the affine part like for the mask matrix, x in frag[15] and y in the w components of
+    // frag[13..16], so it must be set after the mask matrix
+    pub fn set_scissor_feather_mat(&mut self, mat: [f32; 12]) {
+        self.0[60..63].copy_from_slice(&[mat[0], mat[4], mat[8]]);
+        (self.0[55], self.0[59], self.0[63]) = (mat[1], mat[5], mat[9]);
+    }
+
+    // Use the unused w components of the scissor matrix, so they must be set after it
+    pub fn set_scissor_feather(&mut self, feather: f32) {
+        self.0[7] = feather;
+    }
+
+    pub fn set_scissor_feather_ext(&mut self, ext: [f32; 2]) {
+        (self.0[11], self.0[15]) = (ext[0], ext[1]);
+    }
 }
 
 impl From<&Params> for UniformArray {
@@ -109,6 +137,12 @@ impl From<&Params> for UniformArray {
         arr.set_image_blur_filter_direction(params.image_blur_filter_direction);
         arr.set_image_blur_filter_sigma(params.image_blur_filter_sigma);
         arr.set_image_blur_filter_coeff(params.image_blur_filter_coeff);
+        arr.set_sdf_scale(params.sdf_scale);
+        arr.set_mask_mat(params.mask_mat);
+        arr.set_mask_type(params.mask_type);
+        arr.set_scissor_feather_mat(params.scissor_feather_mat);
+        arr.set_scissor_feather(params.scissor_feather);
+        arr.set_scissor_feather_ext(params.scissor_feather_ext);
 
         arr
     }
@@ -1,5 +1,5 @@
 use crate::{
-    geometry::Position,
+    geometry::{Position, Rect},
     paint::{GlyphTexture, GradientColors},
     ImageFlags, ImageStore, PaintFlavor, PixelFormat, Scissor, Transform2D,
 };
@@ -21,10 +21,17 @@ pub struct Params {
     pub(crate) stroke_thr: f32,
     pub(crate) tex_type: f32,
     pub(crate) shader_type: ShaderType,
-    pub(crate) glyph_texture_type: u8, // 0 -> no glyph rendering, 1 -> alpha mask, 2 -> color texture
+    pub(crate) glyph_texture_type: u8, // 0 -> no glyph rendering, 1 -> alpha mask, 2 -> color texture, 3 -> sdf
     pub(crate) image_blur_filter_direction: [f32; 2],
     pub(crate) image_blur_filter_sigma: f32,
     pub(crate) image_blur_filter_coeff: [f32; 3],
+    pub(crate) sdf_scale: f32,
+    pub(crate) mask_mat: [f32; 12],
+    pub(crate) mask_type: f32, // 0 -> no mask, 1 -> alpha of an rgba image, 2 -> gray image
+    // Soft edges of the scissor, which are not affected by `clip_scissor`
+    pub(crate) scissor_feather_mat: [f32; 12],
+    pub(crate) scissor_feather_ext: [f32; 2],
+    pub(crate) scissor_feather: f32,
 }
 
 impl Params {
@@ -54,6 +61,12 @@ impl Params {
                         / fringe_width,
                 ];
 
+                if scissor.feather > 0.0 {
+                    params.scissor_feather_mat = params.scissor_mat;
+                    params.scissor_feather_ext = ext;
+                    params.scissor_feather = scissor.feather;
+                }
+
                 (ext, scissor_scale)
             }
         } else {
@@ -70,8 +83,17 @@ impl Params {
             GlyphTexture::None => 0,
             GlyphTexture::AlphaMask(_) => 1,
             GlyphTexture::ColorTexture(_) => 2,
+            GlyphTexture::Sdf(_, sdf_scale) => {
+                params.sdf_scale = *sdf_scale;
+                3
+            }
         };
 
+        // The paint space transform of image and gradient paints is applied before the canvas transform
+        let mut paint_transform = paint_flavor.transform();
+        paint_transform.multiply(global_transform);
+        let global_transform = &paint_transform;
+
         let inv_transform;
 
         match &paint_flavor {
@@ -89,6 +111,7 @@ impl Params {
                 height,
                 angle,
                 tint,
+                ..
             } => {
                 let image_info = match images.info(*id) {
                     Some(info) => info,
@@ -144,6 +167,7 @@ impl Params {
                 start: Position { x: start_x, y: start_y },
                 end: Position { x: end_x, y: end_y },
                 colors,
+                ..
             } => {
                 let large = 1e5f32;
                 let mut dx = end_x - start_x;
@@ -186,6 +210,7 @@ impl Params {
                 radius,
                 feather,
                 colors,
+                ..
             } => {
                 let mut transform = Transform2D::new_translation(x + width * 0.5, y + height * 0.5);
                 transform.multiply(global_transform);
@@ -211,6 +236,7 @@ impl Params {
                 in_radius,
                 out_radius,
                 colors,
+                ..
             } => {
                 let r = (in_radius + out_radius) * 0.5;
                 let f = out_radius - in_radius;
@@ -234,6 +260,20 @@ impl Params {
                     }
                 }
             }
+            &PaintFlavor::ConicGradient {
+                center: Position { x: cx, y: cy },
+                angle,
+                ..
+            } => {
+                let mut transform = Transform2D::identity();
+                transform.rotate(*angle);
+                transform.translate(*cx, *cy);
+                transform.multiply(global_transform);
+                inv_transform = transform.inversed();
+
+                // Conic gradients always sample the gradient texture
+                params.shader_type = ShaderType::FillConicGradient;
+            }
         }
 
         params.paint_mat = inv_transform.to_mat3x4();
@@ -241,6 +281,64 @@ impl Params {
         params
     }
 
+    /// Restricts the scissor to the given rectangle in render target pixels. Returns false if nothing
+    /// remains visible.
+    ///
+    /// For a rotated or skewed scissor the result is the bounding rectangle of the intersection in
+    /// scissor space, so it may reach a bit beyond `rect` at the corners.
+    pub(crate) fn clip_scissor(&mut self, rect: Rect, fringe_width: f32) -> bool {
+        if rect.is_empty() {
+            return false;
+        }
+
+        // Without a scissor the scissor matrix is all zeros
+        if self.scissor_mat == [0.0; 12] {
+            let transform = Transform2D::new_translation(rect.x + rect.w * 0.5, rect.y + rect.h * 0.5);
+
+            self.scissor_mat = transform.inversed().to_mat3x4();
+            self.scissor_ext = [rect.w * 0.5, rect.h * 0.5];
+            self.scissor_scale = [1.0 / fringe_width, 1.0 / fringe_width];
+
+            return true;
+        }
+
+        let m = self.scissor_mat;
+        let inv_transform = Transform2D([m[0], m[1], m[4], m[5], m[8], m[9]]);
+
+        // Bounds of the rectangle in scissor space, where the scissor is centered at the origin
+        let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+
+        for (x, y) in [
+            (rect.x, rect.y),
+            (rect.x + rect.w, rect.y),
+            (rect.x + rect.w, rect.y + rect.h),
+            (rect.x, rect.y + rect.h),
+        ] {
+            let (x, y) = inv_transform.transform_point(x, y);
+            bounds = [bounds[0].min(x), bounds[1].min(y), bounds[2].max(x), bounds[3].max(y)];
+        }
+
+        let [ex, ey] = self.scissor_ext;
+        let clipped = Rect::new(-ex, -ey, ex * 2.0, ey * 2.0).intersect(Rect::new(
+            bounds[0],
+            bounds[1],
+            bounds[2] - bounds[0],
+            bounds[3] - bounds[1],
+        ));
+
+        if clipped.is_empty() {
+            return false;
+        }
+
+        let mut transform = Transform2D::new_translation(clipped.x + clipped.w * 0.5, clipped.y + clipped.h * 0.5);
+        transform.multiply(&inv_transform.inversed());
+
+        self.scissor_mat = transform.inversed().to_mat3x4();
+        self.scissor_ext = [clipped.w * 0.5, clipped.h * 0.5];
+
+        true
+    }
+
     pub(crate) fn uses_glyph_texture(self) -> bool {
         self.glyph_texture_type != 0
     }
@@ -0,0 +1,814 @@
+use std::mem;
+
+use imgref::{ImgRef, ImgVec};
+use rgb::RGBA8;
+
+use crate::{
+    paint::GlyphTexture, BlendFactor, Color, ColorSpace, CompositeOperationState, ErrorKind, FillRule, ImageFilter,
+    ImageFlags, ImageInfo, ImageSource, ImageStore,
+};
+
+use super::{Capabilities, Command, CommandType, ImageId, Params, RenderTarget, Renderer, ShaderType, Vertex};
+
+/// Renderer that rasterizes into an in-memory RGBA buffer on the CPU.
+///
+/// It mirrors the OpenGL backend: the same shading, the same stencil based fills and the same
+/// blending, so it can be used to render in tests or on machines without a GPU. The rendered
+/// frame can be read back with [`Canvas::screenshot`](crate::Canvas::screenshot).
+///
+/// Rendering is done one pixel at a time and isn't meant to be fast.
+pub struct Software {
+    width: usize,
+    height: usize,
+    screen: Vec<RGBA8>,
+    stencil: Vec<u8>,
+    current_render_target: RenderTarget,
+    color_space: ColorSpace,
+}
+
+impl Software {
+    /// Creates a renderer with an empty frame, which gets its size from [`Canvas::set_size`](crate::Canvas::set_size).
+    pub fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            screen: Vec::new(),
+            stencil: Vec::new(),
+            current_render_target: RenderTarget::Screen,
+            color_space: ColorSpace::Srgb,
+        }
+    }
+}
+
+impl Default for Software {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Image storage of the [`Software`] renderer.
+pub struct SoftwareImage {
+    info: ImageInfo,
+    pixels: Vec<RGBA8>,
+}
+
+impl SoftwareImage {
+    /// Samples the image at normalized texture coordinates, returning the stored channels in `0.0..=1.0`.
+    fn sample(&self, s: f32, t: f32) -> [f32; 4] {
+        let width = self.info.width();
+        let height = self.info.height();
+
+        // The pixels are taken out while the image is being rendered to
+        if self.pixels.len() < width * height || width == 0 || height == 0 {
+            return [0.0; 4];
+        }
+
+        let flags = self.info.flags();
+
+        let texel = |x: i64, y: i64| {
+            let x = wrap(x, width, flags.contains(ImageFlags::REPEAT_X));
+            let y = wrap(y, height, flags.contains(ImageFlags::REPEAT_Y));
+            let px = self.pixels[y * width + x];
+            [px.r as f32, px.g as f32, px.b as f32, px.a as f32].map(|c| c / 255.0)
+        };
+
+        let x = s * width as f32 - 0.5;
+        let y = t * height as f32 - 0.5;
+
+        if flags.contains(ImageFlags::NEAREST) {
+            return texel(x.round() as i64, y.round() as i64);
+        }
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = mix(texel(x0, y0), texel(x0 + 1, y0), fx);
+        let bottom = mix(texel(x0, y0 + 1), texel(x0 + 1, y0 + 1), fx);
+
+        mix(top, bottom, fy)
+    }
+}
+
+fn wrap(coord: i64, size: usize, repeat: bool) -> usize {
+    if repeat {
+        coord.rem_euclid(size as i64) as usize
+    } else {
+        coord.clamp(0, size as i64 - 1) as usize
+    }
+}
+
+fn mix(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+// Pixel buffer that is currently being rendered to. Image targets are stored bottom row first, like
+// OpenGL textures that were rendered to through a framebuffer. An sRGB surface stores sRGB encoded
+// colors and blends in linear space, like an sRGB framebuffer.
+struct Surface {
+    pixels: Vec<RGBA8>,
+    width: usize,
+    height: usize,
+    flip_y: bool,
+    srgb: bool,
+}
+
+impl Surface {
+    fn index(&self, x: usize, y: usize) -> usize {
+        let row = if self.flip_y { self.height - 1 - y } else { y };
+        row * self.width + x
+    }
+
+    fn decode(&self, px: RGBA8) -> [f32; 4] {
+        let color = [px.r, px.g, px.b, px.a].map(|c| c as f32 / 255.0);
+
+        if self.srgb {
+            let linear = Color::rgbaf(color[0], color[1], color[2], color[3]).to_linear();
+            linear.to_array()
+        } else {
+            color
+        }
+    }
+
+    fn encode(&self, color: [f32; 4]) -> RGBA8 {
+        let [r, g, b, a] = if self.srgb {
+            let encode = |c: f32| {
+                if c <= 0.003_130_8 {
+                    c * 12.92
+                } else {
+                    1.055 * c.powf(1.0 / 2.4) - 0.055
+                }
+            };
+
+            [encode(color[0]), encode(color[1]), encode(color[2]), color[3]]
+        } else {
+            color
+        };
+
+        RGBA8::new(to_u8(r), to_u8(g), to_u8(b), to_u8(a))
+    }
+}
+
+// Per pixel stencil test and operation, following the stencil state the OpenGL renderer sets up
+#[derive(Copy, Clone)]
+enum Stencil {
+    // No stencil test
+    Disabled,
+    // Increments for front facing and decrements for back facing triangles, no color output
+    Winding,
+    // Passes where the masked stencil value is zero
+    EqualZero(u8),
+    // Passes where the stencil value is zero and increments it
+    EqualZeroIncrement,
+    // Passes where the masked stencil value is not zero, the stencil is reset everywhere
+    NotEqualZeroReset(u8),
+    // Resets the stencil without color output
+    Reset,
+}
+
+#[derive(Copy, Clone)]
+enum Primitive {
+    Triangles,
+    Strip,
+    Fan,
+}
+
+struct Context<'a> {
+    verts: &'a [Vertex],
+    image: Option<&'a SoftwareImage>,
+    glyph_image: Option<&'a SoftwareImage>,
+    mask_image: Option<&'a SoftwareImage>,
+    composite_operation: CompositeOperationState,
+}
+
+impl Software {
+    fn take_surface(&mut self, images: &mut ImageStore<SoftwareImage>, target: RenderTarget) -> Surface {
+        self.current_render_target = target;
+
+        let surface = match target {
+            RenderTarget::Image(id) => images.get_mut(id).map(|image| Surface {
+                pixels: mem::take(&mut image.pixels),
+                width: image.info.width(),
+                height: image.info.height(),
+                flip_y: true,
+                srgb: false,
+            }),
+            RenderTarget::Screen => None,
+        };
+
+        let surface = surface.unwrap_or_else(|| Surface {
+            pixels: mem::take(&mut self.screen),
+            width: self.width,
+            height: self.height,
+            flip_y: false,
+            srgb: self.color_space == ColorSpace::Linear,
+        });
+
+        if self.stencil.len() < surface.width * surface.height {
+            self.stencil.resize(surface.width * surface.height, 0);
+        }
+
+        surface
+    }
+
+    fn return_surface(&mut self, images: &mut ImageStore<SoftwareImage>, surface: Surface) {
+        match self.current_render_target {
+            RenderTarget::Image(id) if surface.flip_y => {
+                if let Some(image) = images.get_mut(id) {
+                    image.pixels = surface.pixels;
+                }
+            }
+            _ => self.screen = surface.pixels,
+        }
+    }
+
+    fn draw(
+        &mut self,
+        surface: &mut Surface,
+        ctx: &Context,
+        params: &Params,
+        (start, count): (usize, usize),
+        primitive: Primitive,
+        stencil: Stencil,
+    ) {
+        let verts = &ctx.verts[start..start + count];
+
+        let triangle_count = match primitive {
+            Primitive::Triangles => count / 3,
+            Primitive::Strip | Primitive::Fan => count.saturating_sub(2),
+        };
+
+        for i in 0..triangle_count {
+            let triangle = match primitive {
+                Primitive::Triangles => [verts[i * 3], verts[i * 3 + 1], verts[i * 3 + 2]],
+                // Every other strip triangle has its vertices swapped to keep a consistent winding
+                Primitive::Strip if i % 2 == 1 => [verts[i + 1], verts[i], verts[i + 2]],
+                Primitive::Strip => [verts[i], verts[i + 1], verts[i + 2]],
+                Primitive::Fan => [verts[0], verts[i + 1], verts[i + 2]],
+            };
+
+            let stencil_buffer = &mut self.stencil;
+
+            rasterize(triangle, surface.width, surface.height, |x, y, u, v, front_facing| {
+                let stencil_value = &mut stencil_buffer[y * surface.width + x];
+
+                let write_color = match stencil {
+                    Stencil::Disabled => true,
+                    Stencil::Winding => {
+                        *stencil_value = if front_facing {
+                            stencil_value.wrapping_add(1)
+                        } else {
+                            stencil_value.wrapping_sub(1)
+                        };
+                        false
+                    }
+                    Stencil::EqualZero(mask) => *stencil_value & mask == 0,
+                    Stencil::EqualZeroIncrement => {
+                        if *stencil_value != 0 {
+                            return;
+                        }
+                        // The increment only happens for fragments that aren't discarded
+                        if let Some(color) = shade(ctx, params, x as f32 + 0.5, y as f32 + 0.5, u, v) {
+                            *stencil_value = 1;
+                            blend(surface, ctx.composite_operation, x, y, color);
+                        }
+                        false
+                    }
+                    Stencil::NotEqualZeroReset(mask) => {
+                        let pass = *stencil_value & mask != 0;
+                        *stencil_value = 0;
+                        pass
+                    }
+                    Stencil::Reset => {
+                        *stencil_value = 0;
+                        false
+                    }
+                };
+
+                if write_color {
+                    if let Some(color) = shade(ctx, params, x as f32 + 0.5, y as f32 + 0.5, u, v) {
+                        blend(surface, ctx.composite_operation, x, y, color);
+                    }
+                }
+            });
+        }
+    }
+
+    fn render_command(&mut self, surface: &mut Surface, ctx: &Context, cmd: &Command) {
+        let fill_mask = match cmd.fill_rule {
+            FillRule::NonZero => 0xff,
+            FillRule::EvenOdd => 0x1,
+        };
+
+        match &cmd.cmd_type {
+            CommandType::ConvexFill { params } => {
+                for drawable in &cmd.drawables {
+                    if let Some(range) = drawable.fill_verts {
+                        self.draw(surface, ctx, params, range, Primitive::Fan, Stencil::Disabled);
+                    }
+
+                    if let Some(range) = drawable.stroke_verts {
+                        self.draw(surface, ctx, params, range, Primitive::Strip, Stencil::Disabled);
+                    }
+                }
+            }
+            CommandType::ConcaveFill {
+                stencil_params,
+                fill_params,
+            } => {
+                for drawable in &cmd.drawables {
+                    if let Some(range) = drawable.fill_verts {
+                        self.draw(surface, ctx, stencil_params, range, Primitive::Fan, Stencil::Winding);
+                    }
+                }
+
+                // Anti-aliased fringes outside of the filled area
+                for drawable in &cmd.drawables {
+                    if let Some(range) = drawable.stroke_verts {
+                        self.draw(
+                            surface,
+                            ctx,
+                            fill_params,
+                            range,
+                            Primitive::Strip,
+                            Stencil::EqualZero(fill_mask),
+                        );
+                    }
+                }
+
+                if let Some(range) = cmd.triangles_verts {
+                    self.draw(
+                        surface,
+                        ctx,
+                        fill_params,
+                        range,
+                        Primitive::Strip,
+                        Stencil::NotEqualZeroReset(fill_mask),
+                    );
+                }
+            }
+            CommandType::Stroke { params } => {
+                for drawable in &cmd.drawables {
+                    if let Some(range) = drawable.stroke_verts {
+                        self.draw(surface, ctx, params, range, Primitive::Strip, Stencil::Disabled);
+                    }
+                }
+            }
+            CommandType::StencilStroke { params1, params2 } => {
+                // Fill the stroke base without overlap, then the anti-aliased pixels, then clear the stencil
+                for (params, stencil) in [
+                    (params2, Stencil::EqualZeroIncrement),
+                    (params1, Stencil::EqualZero(0xff)),
+                    (params1, Stencil::Reset),
+                ] {
+                    for drawable in &cmd.drawables {
+                        if let Some(range) = drawable.stroke_verts {
+                            self.draw(surface, ctx, params, range, Primitive::Strip, stencil);
+                        }
+                    }
+                }
+            }
+            CommandType::Triangles { params } => {
+                if let Some(range) = cmd.triangles_verts {
+                    self.draw(surface, ctx, params, range, Primitive::Triangles, Stencil::Disabled);
+                }
+            }
+            CommandType::SetRenderTarget(_)
+            | CommandType::ClearRect { .. }
+            | CommandType::RenderFilteredImage { .. } => {}
+        }
+    }
+}
+
+fn clear_rect(surface: &mut Surface, x: u32, y: u32, width: u32, height: u32, color: Color) {
+    let color = surface.encode(color.to_array());
+
+    let x_end = (x as usize + width as usize).min(surface.width);
+    let y_end = (y as usize + height as usize).min(surface.height);
+
+    for y in (y as usize)..y_end {
+        for x in (x as usize)..x_end {
+            let index = surface.index(x, y);
+            surface.pixels[index] = color;
+        }
+    }
+}
+
+fn gaussian_blur(images: &mut ImageStore<SoftwareImage>, source: ImageId, target: ImageId, sigma: f32) {
+    // Same limit and incrementally computed coefficients as the OpenGL blur shader
+    let sigma = sigma.min(8.0);
+    let sample_count = (1.5 * sigma).ceil() as i64;
+
+    let coeff_x = 1. / ((2. * std::f32::consts::PI).sqrt() * sigma);
+    let coeff_y = f32::exp(-0.5 / (sigma * sigma));
+    let coeff_z = coeff_y * coeff_y;
+
+    let (width, height, pixels) = match images.get(source) {
+        Some(image) if image.pixels.len() >= image.info.width() * image.info.height() => {
+            (image.info.width(), image.info.height(), image.pixels.clone())
+        }
+        _ => return,
+    };
+
+    let blur_pass = |pixels: &[RGBA8], (dx, dy): (i64, i64)| {
+        let mut out = Vec::with_capacity(pixels.len());
+
+        for y in 0..height as i64 {
+            for x in 0..width as i64 {
+                let texel = |i: i64| {
+                    let sx = (x + i * dx).clamp(0, width as i64 - 1) as usize;
+                    let sy = (y + i * dy).clamp(0, height as i64 - 1) as usize;
+                    let px = pixels[sy * width + sx];
+                    [px.r as f32, px.g as f32, px.b as f32, px.a as f32]
+                };
+
+                let mut coeff = [coeff_x, coeff_y, coeff_z];
+                let mut sum = texel(0).map(|c| c * coeff[0]);
+                let mut coeff_sum = coeff[0];
+                coeff[0] *= coeff[1];
+                coeff[1] *= coeff[2];
+
+                for i in 1..sample_count.max(1) {
+                    let (before, after) = (texel(-i), texel(i));
+                    for c in 0..4 {
+                        sum[c] += (before[c] + after[c]) * coeff[0];
+                    }
+                    coeff_sum += 2.0 * coeff[0];
+                    coeff[0] *= coeff[1];
+                    coeff[1] *= coeff[2];
+                }
+
+                let [r, g, b, a] = sum.map(|c| (c / coeff_sum).round().clamp(0.0, 255.0) as u8);
+                out.push(RGBA8::new(r, g, b, a));
+            }
+        }
+
+        out
+    };
+
+    let horizontal = blur_pass(&pixels, (1, 0));
+    let blurred = blur_pass(&horizontal, (0, 1));
+
+    if let Some(image) = images.get_mut(target) {
+        if image.info.width() == width && image.info.height() == height {
+            image.pixels = blurred;
+        }
+    }
+}
+
+// Calls `fragment` with the pixel position, the interpolated texture coordinates and the facing for
+// every pixel whose center is covered by the triangle. Pixels on shared edges are only covered once.
+fn rasterize(
+    [v0, v1, mut v2]: [Vertex; 3],
+    width: usize,
+    height: usize,
+    mut fragment: impl FnMut(usize, usize, f32, f32, bool),
+) {
+    let edge = |a: &Vertex, b: &Vertex, x: f32, y: f32| (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x);
+
+    let area = edge(&v0, &v1, v2.x, v2.y);
+
+    if area == 0.0 || !area.is_finite() {
+        return;
+    }
+
+    let front_facing = area < 0.0;
+
+    let mut v1 = v1;
+    if front_facing {
+        mem::swap(&mut v1, &mut v2);
+    }
+    let area = area.abs();
+
+    let minx = v0.x.min(v1.x).min(v2.x).floor().max(0.0) as usize;
+    let miny = v0.y.min(v1.y).min(v2.y).floor().max(0.0) as usize;
+    let maxx = (v0.x.max(v1.x).max(v2.x).ceil().max(0.0) as usize).min(width);
+    let maxy = (v0.y.max(v1.y).max(v2.y).ceil().max(0.0) as usize).min(height);
+
+    // Top-left style tie breaking: a pixel center exactly on an edge belongs to only one of the two
+    // triangles sharing it, since they traverse the edge in opposite directions.
+    let owns_edge = |a: &Vertex, b: &Vertex| b.y < a.y || (b.y == a.y && b.x > a.x);
+    let inside = |w: f32, a: &Vertex, b: &Vertex| w > 0.0 || (w == 0.0 && owns_edge(a, b));
+
+    for y in miny..maxy {
+        let py = y as f32 + 0.5;
+
+        for x in minx..maxx {
+            let px = x as f32 + 0.5;
+
+            let w0 = edge(&v1, &v2, px, py);
+            let w1 = edge(&v2, &v0, px, py);
+            let w2 = edge(&v0, &v1, px, py);
+
+            if inside(w0, &v1, &v2) && inside(w1, &v2, &v0) && inside(w2, &v0, &v1) {
+                let (l0, l1, l2) = (w0 / area, w1 / area, w2 / area);
+                let u = l0 * v0.u + l1 * v1.u + l2 * v2.u;
+                let v = l0 * v0.v + l1 * v1.v + l2 * v2.v;
+
+                fragment(x, y, u, v, front_facing);
+            }
+        }
+    }
+}
+
+fn transform_point(mat: &[f32; 12], x: f32, y: f32) -> (f32, f32) {
+    (mat[0] * x + mat[4] * y + mat[8], mat[1] * x + mat[5] * y + mat[9])
+}
+
+fn sdroundrect(x: f32, y: f32, ext: [f32; 2], rad: f32) -> f32 {
+    let dx = x.abs() - (ext[0] - rad);
+    let dy = y.abs() - (ext[1] - rad);
+    dx.max(dy).min(0.0) + dx.max(0.0).hypot(dy.max(0.0)) - rad
+}
+
+fn texture_color(color: [f32; 4], tex_type: f32) -> [f32; 4] {
+    match tex_type as i32 {
+        1 => [color[0] * color[3], color[1] * color[3], color[2] * color[3], color[3]],
+        2 => [color[0]; 4],
+        _ => color,
+    }
+}
+
+fn scale(color: [f32; 4], factor: [f32; 4]) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| color[i] * factor[i])
+}
+
+// Soft edges of the scissor, fading from the edges inwards
+fn scissor_feather(params: &Params, x: f32, y: f32) -> f32 {
+    if params.scissor_feather <= 0.0 {
+        return 1.0;
+    }
+
+    let (sx, sy) = transform_point(&params.scissor_feather_mat, x, y);
+    let sx = (params.scissor_feather_ext[0] - sx.abs()) / params.scissor_feather;
+    let sy = (params.scissor_feather_ext[1] - sy.abs()) / params.scissor_feather;
+    sx.clamp(0.0, 1.0) * sy.clamp(0.0, 1.0)
+}
+
+// Alpha of the mask image set on the canvas
+fn mask_alpha(ctx: &Context, params: &Params, x: f32, y: f32) -> f32 {
+    let image = match (params.mask_type as i32, ctx.mask_image) {
+        (0, _) | (_, None) => return 1.0,
+        (_, Some(image)) => image,
+    };
+
+    let (s, t) = transform_point(&params.mask_mat, x, y);
+    let mask = image.sample(s, t);
+
+    if params.mask_type as i32 == 2 {
+        mask[0]
+    } else {
+        mask[3]
+    }
+}
+
+// CPU version of the fragment shader of the OpenGL renderer. Returns `None` for discarded fragments.
+fn shade(ctx: &Context, params: &Params, x: f32, y: f32, u: f32, v: f32) -> Option<[f32; 4]> {
+    let sample = |image: Option<&SoftwareImage>, s: f32, t: f32| image.map_or([0.0; 4], |image| image.sample(s, t));
+
+    let mut stroke_alpha = 1.0;
+
+    if params.shader_type != ShaderType::TextureCopyUnclipped {
+        stroke_alpha = (1.0f32).min((1.0 - (u * 2.0 - 1.0).abs()) * params.stroke_mult) * (1.0f32).min(v);
+
+        if stroke_alpha < params.stroke_thr {
+            return None;
+        }
+    }
+
+    let gradient_position = || {
+        let (px, py) = transform_point(&params.paint_mat, x, y);
+        ((sdroundrect(px, py, params.extent, params.radius) + params.feather * 0.5) / params.feather).clamp(0.0, 1.0)
+    };
+
+    let mut result = match params.shader_type {
+        ShaderType::FillGradient => mix(params.inner_col, params.outer_col, gradient_position()),
+        ShaderType::FillImageGradient => sample(ctx.image, gradient_position(), 0.0),
+        ShaderType::FillConicGradient => {
+            let (px, py) = transform_point(&params.paint_mat, x, y);
+            sample(ctx.image, (py.atan2(px) / std::f32::consts::TAU).rem_euclid(1.0), 0.0)
+        }
+        ShaderType::FillImage => {
+            let (px, py) = transform_point(&params.paint_mat, x, y);
+            let color = sample(ctx.image, px / params.extent[0], py / params.extent[1]);
+            scale(texture_color(color, params.tex_type), params.inner_col)
+        }
+        ShaderType::FillColor => params.inner_col,
+        ShaderType::TextureCopyUnclipped => {
+            let color = texture_color(sample(ctx.image, u, v), params.tex_type);
+            return Some(scale(color, params.inner_col));
+        }
+        ShaderType::Stencil => [1.0; 4],
+        // Blurring is done on whole images, see `gaussian_blur`
+        ShaderType::FilterImage => return None,
+    };
+
+    let scissor = {
+        let (sx, sy) = transform_point(&params.scissor_mat, x, y);
+        let sx = 0.5 - (sx.abs() - params.scissor_ext[0]) * params.scissor_scale[0];
+        let sy = 0.5 - (sy.abs() - params.scissor_ext[1]) * params.scissor_scale[1];
+        sx.clamp(0.0, 1.0) * sy.clamp(0.0, 1.0) * scissor_feather(params, x, y) * mask_alpha(ctx, params, x, y)
+    };
+
+    if params.uses_glyph_texture() {
+        let mask = sample(ctx.glyph_image, u, v);
+
+        let mask = if params.glyph_texture_type == 1 {
+            [mask[0]; 4]
+        } else if params.glyph_texture_type == 3 {
+            [((mask[0] - 0.5) * params.sdf_scale + 0.5).clamp(0.0, 1.0); 4]
+        } else {
+            result = [1.0; 4];
+            texture_color(mask, 1.0)
+        };
+
+        result = scale(result, mask.map(|m| m * scissor));
+    } else if params.shader_type != ShaderType::Stencil {
+        result = result.map(|c| c * stroke_alpha * scissor);
+    }
+
+    Some(result)
+}
+
+fn blend(surface: &mut Surface, state: CompositeOperationState, x: usize, y: usize, src: [f32; 4]) {
+    let index = surface.index(x, y);
+    let dst = surface.decode(surface.pixels[index]);
+    let src = src.map(|c| c.clamp(0.0, 1.0));
+
+    let factor = |factor: BlendFactor, channel: usize| match factor {
+        BlendFactor::Zero => 0.0,
+        BlendFactor::One => 1.0,
+        BlendFactor::SrcColor => src[channel],
+        BlendFactor::OneMinusSrcColor => 1.0 - src[channel],
+        BlendFactor::DstColor => dst[channel],
+        BlendFactor::OneMinusDstColor => 1.0 - dst[channel],
+        BlendFactor::SrcAlpha => src[3],
+        BlendFactor::OneMinusSrcAlpha => 1.0 - src[3],
+        BlendFactor::DstAlpha => dst[3],
+        BlendFactor::OneMinusDstAlpha => 1.0 - dst[3],
+        BlendFactor::SrcAlphaSaturate if channel == 3 => 1.0,
+        BlendFactor::SrcAlphaSaturate => src[3].min(1.0 - dst[3]),
+    };
+
+    let out = [0, 1, 2, 3].map(|channel| {
+        let (src_factor, dst_factor) = if channel == 3 {
+            (state.src_alpha, state.dst_alpha)
+        } else {
+            (state.src_rgb, state.dst_rgb)
+        };
+
+        src[channel] * factor(src_factor, channel) + dst[channel] * factor(dst_factor, channel)
+    });
+
+    surface.pixels[index] = surface.encode(out);
+}
+
+fn copy_rows<P: Copy>(
+    pixels: &mut [RGBA8],
+    width: usize,
+    x: usize,
+    y: usize,
+    src: ImgRef<'_, P>,
+    convert: impl Fn(P) -> RGBA8,
+) {
+    for (row_index, row) in src.rows().enumerate() {
+        let start = (y + row_index) * width + x;
+
+        for (dst, px) in pixels[start..start + row.len()].iter_mut().zip(row) {
+            *dst = convert(*px);
+        }
+    }
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl Renderer for Software {
+    type Image = SoftwareImage;
+    type NativeTexture = ();
+
+    fn set_size(&mut self, width: u32, height: u32, _dpi: f32) {
+        let (width, height) = (width as usize, height as usize);
+
+        if width != self.width || height != self.height {
+            self.width = width;
+            self.height = height;
+            self.screen.clear();
+            self.screen.resize(width * height, RGBA8::default());
+        }
+    }
+
+    fn render(&mut self, images: &mut ImageStore<Self::Image>, verts: &[Vertex], commands: Vec<Command>) {
+        let mut surface = self.take_surface(images, self.current_render_target);
+
+        for cmd in &commands {
+            match cmd.cmd_type {
+                CommandType::SetRenderTarget(target) => {
+                    self.return_surface(images, surface);
+                    surface = self.take_surface(images, target);
+                }
+                CommandType::ClearRect {
+                    x,
+                    y,
+                    width,
+                    height,
+                    color,
+                } => clear_rect(&mut surface, x, y, width, height, color),
+                CommandType::RenderFilteredImage { target_image, filter } => {
+                    if let Some(source) = cmd.image {
+                        match filter {
+                            ImageFilter::GaussianBlur { sigma } => gaussian_blur(images, source, target_image, sigma),
+                        }
+                    }
+                }
+                _ => {
+                    let glyph_image = match cmd.glyph_texture {
+                        GlyphTexture::None => None,
+                        GlyphTexture::AlphaMask(id) | GlyphTexture::ColorTexture(id) | GlyphTexture::Sdf(id, _) => {
+                            images.get(id)
+                        }
+                    };
+
+                    let ctx = Context {
+                        verts,
+                        image: cmd.image.and_then(|id| images.get(id)),
+                        glyph_image,
+                        mask_image: cmd.mask_image.and_then(|id| images.get(id)),
+                        composite_operation: cmd.composite_operation,
+                    };
+
+                    self.render_command(&mut surface, &ctx, cmd);
+                }
+            }
+        }
+
+        self.return_surface(images, surface);
+    }
+
+    fn alloc_image(&mut self, info: ImageInfo) -> Result<Self::Image, ErrorKind> {
+        Ok(SoftwareImage {
+            info,
+            pixels: vec![RGBA8::default(); info.width() * info.height()],
+        })
+    }
+
+    fn create_image_from_native_texture(
+        &mut self,
+        _native_texture: Self::NativeTexture,
+        _info: ImageInfo,
+    ) -> Result<Self::Image, ErrorKind> {
+        Err(ErrorKind::UnsupportedImageFormat)
+    }
+
+    fn update_image(
+        &mut self,
+        image: &mut Self::Image,
+        data: ImageSource,
+        x: usize,
+        y: usize,
+    ) -> Result<(), ErrorKind> {
+        let size = data.dimensions();
+
+        if x + size.width > image.info.width() {
+            return Err(ErrorKind::ImageUpdateOutOfBounds);
+        }
+
+        if y + size.height > image.info.height() {
+            return Err(ErrorKind::ImageUpdateOutOfBounds);
+        }
+
+        let pixels = &mut image.pixels;
+        let width = image.info.width();
+
+        match data {
+            ImageSource::Rgba(img) => copy_rows(pixels, width, x, y, img, |px| px),
+            ImageSource::Rgb(img) => copy_rows(pixels, width, x, y, img, |px| RGBA8::new(px.r, px.g, px.b, 255)),
+            // Matches single channel OpenGL textures, which sample as (value, 0, 0, 1)
+            ImageSource::Gray(img) => copy_rows(pixels, width, x, y, img, |px| RGBA8::new(*px, 0, 0, 255)),
+            #[cfg(target_arch = "wasm32")]
+            ImageSource::HtmlImageElement(_) => return Err(ErrorKind::UnsupportedImageFormat),
+        }
+
+        Ok(())
+    }
+
+    fn delete_image(&mut self, _image: Self::Image, _image_id: ImageId) {}
+
+    fn screenshot(&mut self) -> Result<ImgVec<RGBA8>, ErrorKind> {
+        Ok(ImgVec::new(self.screen.clone(), self.width, self.height))
+    }
+
+    fn set_color_space(&mut self, color_space: ColorSpace) {
+        self.color_space = color_space;
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            backend: "Software",
+            max_texture_size: None,
+            msaa_samples: 0,
+            render_to_texture: true,
+        }
+    }
+}
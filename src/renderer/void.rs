@@ -5,7 +5,7 @@ use rgb::RGBA8;
 
 use crate::{ErrorKind, ImageInfo, ImageSource, ImageStore};
 
-use super::{Command, ImageId, Renderer, Vertex};
+use super::{Capabilities, Command, ImageId, Renderer, Vertex};
 
 /// Void renderer used for testing
 pub struct Void;
@@ -55,6 +55,15 @@ impl Renderer for Void {
     fn screenshot(&mut self) -> Result<ImgVec<RGBA8>, ErrorKind> {
         Ok(ImgVec::new(Vec::new(), 0, 0))
     }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            backend: "Void",
+            max_texture_size: None,
+            msaa_samples: 0,
+            render_to_texture: false,
+        }
+    }
 }
 
 pub struct VoidImage {
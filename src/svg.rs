@@ -0,0 +1,367 @@
+//! Rendering of [`usvg`] trees.
+
+use usvg::{NodeKind, PathSegment, Units};
+
+use crate::{
+    geometry::Bounds, Canvas, Color, FillRule, ImageFlags, LineCap, LineJoin, Mask, Paint, Path, PixelFormat,
+    RenderTarget, Renderer, Transform2D,
+};
+
+/// Renders a parsed SVG tree onto the canvas, using the current canvas transform as the SVG's user space.
+///
+/// Group transforms, group and paint opacity, fill rules, clip paths, solid colors as well as linear
+/// and radial gradients are supported. Patterns, images, text nodes, masks and filters are skipped, so
+/// fills and strokes with a pattern paint are not drawn. Radial gradients are drawn around their
+/// center, a different focal point is ignored.
+///
+/// Clip paths are rendered into an image that masks the clipped content, see
+/// [`Canvas::set_mask_image`]. Clip paths in object bounding box units are ignored.
+///
+/// Returns the bounds of everything drawn in device coordinates, including the width of strokes. The
+/// bounds are not narrowed down by clip paths.
+pub fn render_svg_tree<T: Renderer>(canvas: &mut Canvas<T>, tree: &usvg::Tree) -> Bounds {
+    let mut bounds = Bounds::default();
+
+    canvas.save_with(|canvas| render_node(canvas, &tree.root, &mut bounds));
+
+    bounds
+}
+
+fn render_node<T: Renderer>(canvas: &mut Canvas<T>, node: &usvg::Node, bounds: &mut Bounds) {
+    match &*node.borrow() {
+        NodeKind::Group(group) => {
+            canvas.save();
+            canvas.set_transform(&to_transform(&group.transform));
+
+            let alpha = canvas.state().alpha * group.opacity.get() as f32;
+            canvas.set_global_alpha(alpha);
+
+            if let Some(clip_path) = &group.clip_path {
+                apply_clip_path(canvas, clip_path);
+            }
+
+            for child in node.children() {
+                render_node(canvas, &child, bounds);
+            }
+
+            canvas.restore();
+        }
+        NodeKind::Path(svg_path) => {
+            if svg_path.visibility != usvg::Visibility::Visible {
+                return;
+            }
+
+            canvas.save();
+            canvas.set_transform(&to_transform(&svg_path.transform));
+
+            let path = to_path(&svg_path.data);
+            let path_bounds = data_bounds(&svg_path.data);
+
+            let mut drawn = Bounds::default();
+
+            if let Some(fill) = &svg_path.fill {
+                if let Some(mut paint) = to_paint(&fill.paint, fill.opacity.get() as f32, path_bounds) {
+                    paint.set_fill_rule(match fill.rule {
+                        usvg::FillRule::NonZero => FillRule::NonZero,
+                        usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+                    });
+                    canvas.fill_path(&path, &paint);
+                    drawn = union(drawn, canvas.path_bbox(&path));
+                }
+            }
+
+            if let Some(stroke) = &svg_path.stroke {
+                if let Some(mut paint) = to_paint(&stroke.paint, stroke.opacity.get() as f32, path_bounds) {
+                    paint.set_line_width(stroke.width.get() as f32);
+                    paint.set_miter_limit(stroke.miterlimit.get() as f32);
+                    paint.set_line_cap(match stroke.linecap {
+                        usvg::LineCap::Butt => LineCap::Butt,
+                        usvg::LineCap::Round => LineCap::Round,
+                        usvg::LineCap::Square => LineCap::Square,
+                    });
+                    paint.set_line_join(match stroke.linejoin {
+                        usvg::LineJoin::Round => LineJoin::Round,
+                        usvg::LineJoin::Bevel => LineJoin::Bevel,
+                        _ => LineJoin::Miter,
+                    });
+                    canvas.stroke_path(&path, &paint);
+
+                    let outline = path.stroke_outline(&paint, canvas.tess_tol);
+                    drawn = union(drawn, canvas.path_bbox(&outline));
+                }
+            }
+
+            *bounds = union(*bounds, drawn);
+
+            canvas.restore();
+        }
+        _ => {}
+    }
+}
+
+fn apply_clip_path<T: Renderer>(canvas: &mut Canvas<T>, clip_path: &usvg::ClipPath) {
+    // Object bounding box units would need the bounds of the clipped element, which we don't track
+    if clip_path.units != Units::UserSpaceOnUse {
+        return;
+    }
+
+    let transform = to_transform(&clip_path.transform);
+    let mut clip_bounds = Bounds::default();
+
+    for_each_clip_path(&clip_path.root, transform, &mut |svg_path, path_transform| {
+        let [minx, miny, maxx, maxy] = data_bounds(&svg_path.data);
+        for (x, y) in [(minx, miny), (maxx, miny), (maxx, maxy), (minx, maxy)] {
+            let (x, y) = path_transform.transform_point(x, y);
+            clip_bounds.minx = clip_bounds.minx.min(x);
+            clip_bounds.miny = clip_bounds.miny.min(y);
+            clip_bounds.maxx = clip_bounds.maxx.max(x);
+            clip_bounds.maxy = clip_bounds.maxy.max(y);
+        }
+    });
+
+    // A clip path without any geometry clips everything away
+    if clip_bounds.minx > clip_bounds.maxx {
+        clip_bounds = Bounds {
+            minx: 0.0,
+            miny: 0.0,
+            maxx: 0.0,
+            maxy: 0.0,
+        };
+    }
+
+    // The scissor skips everything outside of the clip path's bounding box right away
+    canvas.intersect_scissor(
+        clip_bounds.minx,
+        clip_bounds.miny,
+        clip_bounds.maxx - clip_bounds.minx,
+        clip_bounds.maxy - clip_bounds.miny,
+    );
+
+    // Bounds of the mask on the render target
+    let state_transform = canvas.state().transform;
+    let corners = [
+        (clip_bounds.minx, clip_bounds.miny),
+        (clip_bounds.maxx, clip_bounds.miny),
+        (clip_bounds.maxx, clip_bounds.maxy),
+        (clip_bounds.minx, clip_bounds.maxy),
+    ]
+    .map(|(x, y)| state_transform.transform_point(x, y));
+
+    let minx = corners.iter().fold(f32::MAX, |min, p| min.min(p.0));
+    let miny = corners.iter().fold(f32::MAX, |min, p| min.min(p.1));
+    let maxx = corners.iter().fold(f32::MIN, |max, p| max.max(p.0));
+    let maxy = corners.iter().fold(f32::MIN, |max, p| max.max(p.1));
+
+    let minx = minx.floor().max(0.0);
+    let miny = miny.floor().max(0.0);
+    let maxx = maxx.ceil().min(canvas.width() as f32);
+    let maxy = maxy.ceil().min(canvas.height() as f32);
+
+    if minx >= maxx || miny >= maxy {
+        return;
+    }
+
+    let (width, height) = ((maxx - minx) as usize, (maxy - miny) as usize);
+    let flags = ImageFlags::PREMULTIPLIED | ImageFlags::FLIP_Y;
+    let Ok(mask_image) = canvas.create_image_empty(width, height, PixelFormat::Rgba8, flags) else {
+        return;
+    };
+    canvas.temporary_images.push(mask_image);
+
+    let previous_target = canvas.current_render_target;
+    canvas.set_render_target(RenderTarget::Image(mask_image));
+    canvas.clear_rect(0, 0, width as u32, height as u32, Color::rgba(0, 0, 0, 0));
+
+    // The clip shape is drawn through the mask of an outer clip path, so the new mask covers their intersection
+    let outer_mask = canvas.state().mask;
+    let to_layer = Transform2D::new_translation(-minx, -miny);
+
+    canvas.save();
+    canvas.reset();
+
+    let mut layer_transform = state_transform;
+    layer_transform.multiply(&to_layer);
+    canvas.state_mut().transform = layer_transform;
+
+    if let Some(Mask { image, mut transform }) = outer_mask {
+        transform.multiply(&to_layer);
+        canvas.state_mut().mask = Some(Mask { image, transform });
+    }
+
+    if let Some(nested) = &clip_path.clip_path {
+        apply_clip_path(canvas, nested);
+    }
+
+    for_each_clip_path(&clip_path.root, transform, &mut |svg_path, path_transform| {
+        let rule = match svg_path.fill.as_ref().map(|fill| fill.rule) {
+            Some(usvg::FillRule::EvenOdd) => FillRule::EvenOdd,
+            _ => FillRule::NonZero,
+        };
+
+        let paint = Paint::color(Color::white()).with_fill_rule(rule);
+
+        canvas.save();
+        canvas.set_transform(&path_transform);
+        canvas.fill_path(&to_path(&svg_path.data), &paint);
+        canvas.restore();
+    });
+
+    canvas.restore();
+    canvas.set_render_target(previous_target);
+
+    canvas.state_mut().mask = Some(Mask {
+        image: mask_image,
+        transform: Transform2D::new_translation(minx, miny),
+    });
+}
+
+// Calls `f` for every visible path of a clip path, with the transform from the path to the clip path's user space
+fn for_each_clip_path(node: &usvg::Node, transform: Transform2D, f: &mut impl FnMut(&usvg::Path, Transform2D)) {
+    for child in node.children() {
+        match &*child.borrow() {
+            NodeKind::Group(group) => {
+                let mut group_transform = to_transform(&group.transform);
+                group_transform.multiply(&transform);
+                for_each_clip_path(&child, group_transform, f);
+            }
+            NodeKind::Path(svg_path) if svg_path.visibility == usvg::Visibility::Visible => {
+                let mut path_transform = to_transform(&svg_path.transform);
+                path_transform.multiply(&transform);
+                f(svg_path, path_transform);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn union(a: Bounds, b: Bounds) -> Bounds {
+    Bounds {
+        minx: a.minx.min(b.minx),
+        miny: a.miny.min(b.miny),
+        maxx: a.maxx.max(b.maxx),
+        maxy: a.maxy.max(b.maxy),
+    }
+}
+
+fn to_transform(transform: &usvg::Transform) -> Transform2D {
+    Transform2D([
+        transform.a as f32,
+        transform.b as f32,
+        transform.c as f32,
+        transform.d as f32,
+        transform.e as f32,
+        transform.f as f32,
+    ])
+}
+
+fn to_path(data: &usvg::PathData) -> Path {
+    let mut path = Path::new();
+
+    for segment in data.segments() {
+        match segment {
+            PathSegment::MoveTo { x, y } => path.move_to(x as f32, y as f32),
+            PathSegment::LineTo { x, y } => path.line_to(x as f32, y as f32),
+            PathSegment::CurveTo { x1, y1, x2, y2, x, y } => {
+                path.bezier_to(x1 as f32, y1 as f32, x2 as f32, y2 as f32, x as f32, y as f32)
+            }
+            PathSegment::ClosePath => path.close(),
+        }
+    }
+
+    path
+}
+
+// Bounds of all points including the control points, as [minx, miny, maxx, maxy]
+fn data_bounds(data: &usvg::PathData) -> [f32; 4] {
+    let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+
+    let mut add = |x: f32, y: f32| {
+        bounds[0] = bounds[0].min(x);
+        bounds[1] = bounds[1].min(y);
+        bounds[2] = bounds[2].max(x);
+        bounds[3] = bounds[3].max(y);
+    };
+
+    for segment in data.segments() {
+        match segment {
+            PathSegment::MoveTo { x, y } | PathSegment::LineTo { x, y } => add(x as f32, y as f32),
+            PathSegment::CurveTo { x1, y1, x2, y2, x, y } => {
+                add(x1 as f32, y1 as f32);
+                add(x2 as f32, y2 as f32);
+                add(x as f32, y as f32);
+            }
+            PathSegment::ClosePath => {}
+        }
+    }
+
+    bounds
+}
+
+fn to_color(color: usvg::Color, alpha: f32) -> Color {
+    let mut color = Color::rgb(color.red, color.green, color.blue);
+    color.set_alphaf(alpha);
+    color
+}
+
+fn to_paint(paint: &usvg::Paint, opacity: f32, [minx, miny, maxx, maxy]: [f32; 4]) -> Option<Paint> {
+    // Maps a point in gradient space to path user space
+    let gradient_transform = |base: &usvg::BaseGradient| {
+        let mut transform = to_transform(&base.transform);
+
+        if base.units == Units::ObjectBoundingBox {
+            let mut bbox = Transform2D::identity();
+            bbox.scale(maxx - minx, maxy - miny);
+            bbox.translate(minx, miny);
+            transform.multiply(&bbox);
+        }
+
+        transform
+    };
+
+    let stops = |base: &usvg::BaseGradient| {
+        base.stops
+            .iter()
+            .map(|stop| {
+                (
+                    stop.offset.get() as f32,
+                    to_color(stop.color, stop.opacity.get() as f32 * opacity),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let paint = match paint {
+        usvg::Paint::Color(color) => Paint::color(to_color(*color, opacity)),
+        usvg::Paint::LinearGradient(gradient) => Paint::linear_gradient_stops(
+            gradient.x1 as f32,
+            gradient.y1 as f32,
+            gradient.x2 as f32,
+            gradient.y2 as f32,
+            stops(&gradient.base),
+        )
+        .with_transform(gradient_transform(&gradient.base)),
+        usvg::Paint::RadialGradient(gradient) => {
+            if gradient.fx != gradient.cx || gradient.fy != gradient.cy {
+                log::warn!("Focal point of radial gradient {} is not supported", gradient.id);
+            }
+
+            Paint::radial_gradient_stops(
+                gradient.cx as f32,
+                gradient.cy as f32,
+                0.0,
+                gradient.r.get() as f32,
+                stops(&gradient.base),
+            )
+            .with_transform(gradient_transform(&gradient.base))
+        }
+        usvg::Paint::Pattern(pattern) => {
+            log::warn!(
+                "Pattern {} is not supported, skipping the fill or stroke using it",
+                pattern.id
+            );
+            return None;
+        }
+    };
+
+    Some(paint.with_anti_alias(true))
+}
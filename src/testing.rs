@@ -0,0 +1,74 @@
+//! Helpers for checking rendering output in tests.
+//!
+//! Scenes are rendered with the [`Software`] renderer, so they don't need a GPU and produce the
+//! same pixels on every machine. The resulting images can be compared with each other or with
+//! reference images to catch changes in how fills and strokes are rasterized.
+//!
+//! # Example
+//! ```
+//! use femtovg::{testing, Color, Paint, Path};
+//!
+//! let image = testing::render_to_image(20, 20, |canvas| {
+//!     let mut path = Path::new();
+//!     path.rect(5.0, 5.0, 10.0, 10.0);
+//!     canvas.fill_path(&path, &Paint::color(Color::white()));
+//! });
+//!
+//! assert_eq!(image.len(), 20 * 20 * 4);
+//! assert_eq!(testing::pixel_difference(&image, &image, 0), 0);
+//! ```
+
+use crate::{renderer::Software, Canvas, Color};
+
+/// Renders the scene drawn by `build` into a `width` x `height` image and returns its RGBA bytes.
+///
+/// The canvas is cleared to transparent black and has a device pixel ratio of 1.0 before `build` is called.
+pub fn render_to_image<F: FnOnce(&mut Canvas<Software>)>(width: u32, height: u32, build: F) -> Vec<u8> {
+    let mut canvas = Canvas::new(Software::new()).expect("Cannot create software canvas");
+    canvas.set_size(width, height, 1.0);
+    canvas.clear_rect(0, 0, width, height, Color::rgba(0, 0, 0, 0));
+
+    build(&mut canvas);
+
+    let image = canvas.screenshot().expect("Cannot read back software canvas");
+
+    image
+        .pixels()
+        .flat_map(|pixel| [pixel.r, pixel.g, pixel.b, pixel.a])
+        .collect()
+}
+
+/// Returns the number of pixels where any channel of the two RGBA images differs by more than `tolerance`.
+///
+/// Images of different length are compared up to the shorter one, with every missing pixel counted as different.
+pub fn pixel_difference(actual: &[u8], expected: &[u8], tolerance: u8) -> usize {
+    let differing = actual
+        .chunks_exact(4)
+        .zip(expected.chunks_exact(4))
+        .filter(|(a, b)| a.iter().zip(b.iter()).any(|(a, b)| a.abs_diff(*b) > tolerance))
+        .count();
+
+    let missing = actual.len().abs_diff(expected.len()) / 4;
+
+    differing + missing
+}
+
+/// Compares an RGBA image against the reference image at `path`, like [`pixel_difference`].
+///
+/// If the reference image has a different size, all pixels are counted as different.
+#[cfg(feature = "image-loading")]
+pub fn reference_difference<P: AsRef<std::path::Path>>(
+    actual: &[u8],
+    width: u32,
+    height: u32,
+    path: P,
+    tolerance: u8,
+) -> Result<usize, crate::ErrorKind> {
+    let reference = ::image::open(path)?.to_rgba8();
+
+    if reference.dimensions() != (width, height) {
+        return Ok(width as usize * height as usize);
+    }
+
+    Ok(pixel_difference(actual, reference.as_raw(), tolerance))
+}
@@ -1,21 +1,22 @@
 use std::{
-    borrow::Borrow,
-    cell::RefCell,
-    collections::HashMap,
-    ffi::OsStr,
-    fs,
-    hash::{Hash, Hasher},
-    ops::Range,
-    path::Path as FilePath,
-    rc::Rc,
+    borrow::Borrow, cell::RefCell, collections::HashMap, ffi::OsStr, fs, hash::Hash, ops::Range,
+    path::Path as FilePath, rc::Rc,
 };
 
-use fnv::{FnvBuildHasher, FnvHashMap, FnvHasher};
+use fnv::FnvHashMap;
+#[cfg(feature = "text")]
+use fnv::{FnvBuildHasher, FnvHasher};
+#[cfg(feature = "text")]
 use lru::LruCache;
+#[cfg(feature = "text")]
 use rustybuzz::ttf_parser;
 use slotmap::{DefaultKey, SlotMap};
 
+#[cfg(feature = "text")]
+use std::hash::Hasher;
+#[cfg(feature = "text")]
 use unicode_bidi::BidiInfo;
+#[cfg(feature = "text")]
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
@@ -27,8 +28,11 @@ mod atlas;
 pub use atlas::Atlas;
 
 mod font;
-pub use font::FontMetrics;
-use font::{Font, GlyphRendering};
+use font::{Face, Font, GlyphRendering};
+pub use font::{FontMetrics, GlyphMetrics};
+
+mod sdf;
+pub(crate) use sdf::{SdfAtlas, SDF_GLYPH_SIZE, SDF_SPREAD};
 
 // This padding is an empty border around the glyph’s pixels but inside the
 // sampled area (texture coordinates) for the quad in render_atlas().
@@ -40,6 +44,7 @@ const GLYPH_PADDING: u32 = 1;
 const GLYPH_MARGIN: u32 = 1;
 
 const TEXTURE_SIZE: usize = 512;
+#[cfg(feature = "text")]
 const DEFAULT_LRU_CACHE_CAPACITY: usize = 1000;
 
 /// A font handle.
@@ -67,6 +72,19 @@ impl Default for Baseline {
     }
 }
 
+/// How filled text is rasterized, see [`Canvas::set_text_rendering`](crate::Canvas::set_text_rendering).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum TextRendering {
+    /// Glyphs are rendered into an atlas for each size they are drawn with, or drawn as paths when
+    /// they are large. Gives the sharpest results for text drawn at a fixed size. Default value.
+    #[default]
+    Raster,
+    /// Glyphs are rendered once into an atlas of signed distance fields, which the shader scales to
+    /// any size. Suited for text that is zoomed or animated, at the cost of slightly rounded corners.
+    /// Stroked text and bitmap glyphs are still rasterized.
+    Sdf,
+}
+
 /// Text horizontal alignment: `Left` (default), `Center`, `Right`.
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -105,6 +123,7 @@ pub(crate) struct RenderedGlyphId {
     line_width: u32,
     render_mode: RenderMode,
     subpixel_location: u8,
+    subpixel_location_y: u8,
 }
 
 impl RenderedGlyphId {
@@ -115,6 +134,7 @@ impl RenderedGlyphId {
         line_width: f32,
         mode: RenderMode,
         subpixel_location: u8,
+        subpixel_location_y: u8,
     ) -> Self {
         Self {
             glyph_index,
@@ -123,6 +143,7 @@ impl RenderedGlyphId {
             line_width: (line_width * 10.0).trunc() as u32,
             render_mode: mode,
             subpixel_location,
+            subpixel_location_y,
         }
     }
 }
@@ -138,6 +159,10 @@ pub(crate) struct RenderedGlyph {
     color_glyph: bool,
 }
 
+/// A glyph positioned by the shaper.
+///
+/// `byte_index` is the start of the cluster in the source text the glyph was shaped from. All glyphs
+/// of a cluster share it, and a ligature covers all characters of its cluster.
 #[derive(Copy, Clone, Debug)]
 pub struct ShapedGlyph {
     pub x: f32,
@@ -157,12 +182,14 @@ pub struct ShapedGlyph {
     pub bitmap_glyph: bool,
 }
 
+#[cfg(feature = "text")]
 #[derive(Clone, Debug, Default)]
 struct ShapedWord {
     glyphs: Vec<ShapedGlyph>,
     width: f32,
 }
 
+#[cfg(feature = "text")]
 #[derive(Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 struct ShapingId {
     size: u32,
@@ -170,13 +197,25 @@ struct ShapingId {
     font_ids: [Option<FontId>; 8],
 }
 
+#[cfg(feature = "text")]
 impl ShapingId {
-    fn new(font_size: f32, font_ids: [Option<FontId>; 8], word: &str, max_width: Option<f32>) -> Self {
+    fn new(
+        font_size: f32,
+        font_ids: [Option<FontId>; 8],
+        word: &str,
+        max_width: Option<f32>,
+        spacing: Spacing,
+        features: &[rustybuzz::Feature],
+    ) -> Self {
         let mut hasher = FnvHasher::default();
         word.hash(&mut hasher);
         if let Some(max_width) = max_width {
             (max_width.trunc() as i32).hash(&mut hasher);
         }
+        spacing.letter.to_bits().hash(&mut hasher);
+        spacing.word.to_bits().hash(&mut hasher);
+        spacing.tab.to_bits().hash(&mut hasher);
+        features.hash(&mut hasher);
 
         Self {
             size: (font_size * 10.0).trunc() as u32,
@@ -186,7 +225,58 @@ impl ShapingId {
     }
 }
 
+// OpenType features the shaper applies in addition to the default ones of the script
+#[cfg(feature = "text")]
+fn shaping_features(text_settings: &TextSettings) -> Vec<rustybuzz::Feature> {
+    let mut features = Vec::new();
+
+    if !text_settings.kerning {
+        features.push(rustybuzz::Feature::new(ttf_parser::Tag::from_bytes(b"kern"), 0, ..));
+    }
+
+    // Shorter tags are padded with spaces, like "cv1 "
+    features.extend(
+        text_settings
+            .font_features
+            .iter()
+            .map(|(tag, value)| rustybuzz::Feature::new(ttf_parser::Tag::from_bytes_lossy(tag.as_bytes()), *value, ..)),
+    );
+
+    features
+}
+
+// Extra space added after glyphs
+#[cfg(feature = "text")]
+#[derive(Copy, Clone)]
+struct Spacing {
+    letter: f32,
+    word: f32,
+    // Distance between tab stops, 0 to shape tabs like other characters
+    tab: f32,
+}
+
+#[cfg(feature = "text")]
+impl Spacing {
+    fn new(text_settings: &TextSettings) -> Self {
+        Self {
+            letter: text_settings.letter_spacing,
+            word: text_settings.word_spacing,
+            tab: text_settings.tab_width,
+        }
+    }
+
+    fn after(self, c: char) -> f32 {
+        if c == ' ' || c == '\u{a0}' {
+            self.letter + self.word
+        } else {
+            self.letter
+        }
+    }
+}
+
+#[cfg(feature = "text")]
 type ShapedWordsCache<H> = LruCache<ShapingId, Result<ShapedWord, ErrorKind>, H>;
+#[cfg(feature = "text")]
 type ShapingRunCache<H> = LruCache<ShapingId, TextMetrics, H>;
 
 pub(crate) struct FontTexture {
@@ -292,12 +382,16 @@ impl TextContext {
     }
 }
 
+#[cfg_attr(not(feature = "text"), derive(Default))]
 pub(crate) struct TextContextImpl {
     fonts: SlotMap<DefaultKey, Font>,
+    #[cfg(feature = "text")]
     shaping_run_cache: ShapingRunCache<FnvBuildHasher>,
+    #[cfg(feature = "text")]
     shaped_words_cache: ShapedWordsCache<FnvBuildHasher>,
 }
 
+#[cfg(feature = "text")]
 impl Default for TextContextImpl {
     fn default() -> Self {
         let fnv_run = FnvBuildHasher::default();
@@ -318,11 +412,15 @@ impl Default for TextContextImpl {
 }
 
 impl TextContextImpl {
+    #[cfg_attr(not(feature = "text"), allow(unused_variables))]
     pub fn resize_shaping_run_cache(&mut self, capacity: std::num::NonZeroUsize) {
+        #[cfg(feature = "text")]
         self.shaping_run_cache.resize(capacity);
     }
 
+    #[cfg_attr(not(feature = "text"), allow(unused_variables))]
     pub fn resize_shaped_words_cache(&mut self, capacity: std::num::NonZeroUsize) {
+        #[cfg(feature = "text")]
         self.shaped_words_cache.resize(capacity);
     }
 
@@ -360,7 +458,7 @@ impl TextContextImpl {
     ) -> Result<impl Iterator<Item = FontId> + '_, ErrorKind> {
         let data = std::fs::read(path)?;
 
-        let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+        let count = count_faces(&data);
         Ok((0..count).filter_map(move |index| self.add_font_mem_with_index(&data, index).ok()))
     }
 
@@ -395,6 +493,7 @@ impl TextContextImpl {
         self.fonts.get_mut(id.0)
     }
 
+    #[cfg(feature = "text")]
     pub fn find_font<F, T>(&mut self, font_ids: &[Option<FontId>; 8], mut callback: F) -> Result<T, ErrorKind>
     where
         F: FnMut((FontId, &mut Font)) -> (bool, T),
@@ -432,7 +531,24 @@ impl TextContextImpl {
         Err(ErrorKind::NoFontFound)
     }
 
+    pub fn find_font_by_style(&self, family: &str, weight: u16, italic: bool) -> Option<FontId> {
+        self.fonts
+            .iter()
+            .filter(|(_, font)| matches!(font.family(), Some(name) if name.eq_ignore_ascii_case(family)))
+            .min_by_key(|(_, font)| {
+                let metrics = font.metrics(1.0);
+                let slanted = metrics.italic() || metrics.oblique();
+
+                // A face with the wrong style is worse than any weight difference
+                let style_penalty = if slanted == italic { 0 } else { 1000 };
+
+                style_penalty + (i32::from(metrics.weight()) - i32::from(weight)).abs()
+            })
+            .map(|(id, _)| FontId(id))
+    }
+
     fn clear_caches(&mut self) {
+        #[cfg(feature = "text")]
         self.shaped_words_cache.clear();
     }
 
@@ -496,6 +612,48 @@ impl TextContextImpl {
     }
 }
 
+/// Glyphs of a shaped piece of text, see [`Canvas::shape_text`](crate::Canvas::shape_text).
+#[derive(Clone, Default, Debug)]
+pub struct ShapedRun {
+    /// The glyphs in visual order, positioned relative to an origin on the baseline.
+    pub glyphs: Vec<ShapedGlyph>,
+    width: f32,
+    text_len: usize,
+}
+
+impl ShapedRun {
+    pub(crate) fn new(metrics: TextMetrics, text_len: usize) -> Self {
+        Self {
+            glyphs: metrics.glyphs,
+            width: metrics.width,
+            text_len,
+        }
+    }
+
+    /// Total advance of the run.
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Returns the byte range of the source text that the glyph at `index` was shaped from.
+    ///
+    /// Glyphs of the same cluster, like the parts of a decomposed character, return the same range,
+    /// and a ligature returns the range of all characters it joins.
+    pub fn cluster_range(&self, index: usize) -> Option<Range<usize>> {
+        let start = self.glyphs.get(index)?.byte_index;
+
+        let end = self
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.byte_index)
+            .filter(|&byte_index| byte_index > start)
+            .min()
+            .unwrap_or(self.text_len);
+
+        Some(start..end)
+    }
+}
+
 /// Result of a shaping run.
 #[derive(Clone, Default, Debug)]
 pub struct TextMetrics {
@@ -504,15 +662,24 @@ pub struct TextMetrics {
     width: f32,
     height: f32,
     pub glyphs: Vec<ShapedGlyph>,
+    pub(crate) baseline: f32,
     pub(crate) final_byte_index: usize,
 }
 
 impl TextMetrics {
+    pub(crate) fn from_glyphs(glyphs: Vec<ShapedGlyph>) -> Self {
+        Self {
+            glyphs,
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn scale(&mut self, scale: f32) {
         self.x *= scale;
         self.y *= scale;
         self.width *= scale;
         self.height *= scale;
+        self.baseline *= scale;
 
         for glyph in &mut self.glyphs {
             glyph.x *= scale;
@@ -532,13 +699,45 @@ impl TextMetrics {
         self.height
     }
 
+    /// The x coordinate after the advance of the last glyph, where a following run of text starts.
+    ///
+    /// Like the other metrics this takes the text alignment into account, so for left aligned
+    /// text it's the x passed to the drawing call plus the advance width.
+    pub fn next_x(&self) -> f32 {
+        self.x + self.width
+    }
+
     pub(crate) fn has_bitmap_glyphs(&self) -> bool {
         self.glyphs.iter().any(|g| g.bitmap_glyph)
     }
 }
 
+#[cfg(feature = "text")]
+pub(crate) fn count_faces(data: &[u8]) -> u32 {
+    ttf_parser::fonts_in_collection(data).unwrap_or_else(|| u32::from(ttf_parser::Face::parse(data, 0).is_ok()))
+}
+
 // Shaper
 
+// Without the text feature there are no fonts to count
+#[cfg(not(feature = "text"))]
+pub(crate) fn count_faces(_data: &[u8]) -> u32 {
+    0
+}
+
+#[cfg(not(feature = "text"))]
+pub(crate) fn shape(
+    _x: f32,
+    _y: f32,
+    _context: &mut TextContextImpl,
+    _text_settings: &TextSettings,
+    _text: &str,
+    _max_width: Option<f32>,
+) -> Result<TextMetrics, ErrorKind> {
+    Err(ErrorKind::TextNotSupported)
+}
+
+#[cfg(feature = "text")]
 pub(crate) fn shape(
     x: f32,
     y: f32,
@@ -547,14 +746,24 @@ pub(crate) fn shape(
     text: &str,
     max_width: Option<f32>,
 ) -> Result<TextMetrics, ErrorKind> {
-    let id = ShapingId::new(text_settings.font_size, text_settings.font_ids, text, max_width);
+    let spacing = Spacing::new(text_settings);
+    let features = shaping_features(text_settings);
+    let id = ShapingId::new(
+        text_settings.font_size,
+        text_settings.font_ids,
+        text,
+        max_width,
+        spacing,
+        &features,
+    );
 
     if !context.shaping_run_cache.contains(&id) {
         let metrics = shape_run(
             context,
             text_settings.font_size,
             text_settings.font_ids,
-            text_settings.letter_spacing,
+            spacing,
+            &features,
             text,
             max_width,
         )?;
@@ -570,11 +779,13 @@ pub(crate) fn shape(
     Err(ErrorKind::UnknownError)
 }
 
+#[cfg(feature = "text")]
 fn shape_run(
     context: &mut TextContextImpl,
     font_size: f32,
     font_ids: [Option<FontId>; 8],
-    letter_spacing: f32,
+    spacing: Spacing,
+    features: &[rustybuzz::Feature],
     text: &str,
     max_width: Option<f32>,
 ) -> Result<TextMetrics, ErrorKind> {
@@ -584,6 +795,7 @@ fn shape_run(
         width: 0.0,
         height: 0.0,
         glyphs: Vec::with_capacity(text.len()),
+        baseline: 0.0,
         final_byte_index: 0,
     };
 
@@ -615,10 +827,10 @@ fn shape_run(
             let mut byte_index = run.start;
 
             for mut word_txt in sub_text.split_word_bounds() {
-                let id = ShapingId::new(font_size, font_ids, word_txt, max_width);
+                let id = ShapingId::new(font_size, font_ids, word_txt, max_width, spacing, features);
 
                 if !context.shaped_words_cache.contains(&id) {
-                    let word = shape_word(word_txt, hb_direction, context, font_size, &font_ids, letter_spacing);
+                    let word = shape_word(word_txt, hb_direction, context, font_size, &font_ids, spacing, features);
                     context.shaped_words_cache.put(id, word);
                 }
 
@@ -635,7 +847,7 @@ fn shape_run(
                                 let target_width = max_width - result.width;
                                 for glyph in word.glyphs.iter() {
                                     bytes_included = glyph.byte_index;
-                                    let glyph_width = glyph.advance_x + letter_spacing;
+                                    let glyph_width = glyph.advance_x + spacing.after(glyph.c);
 
                                     // nuance: we want to include the first glyph even if it breaks
                                     // the bounds. this is to allow pathologically small bounds to
@@ -653,7 +865,14 @@ fn shape_run(
                                 }
 
                                 let subword_txt = &word_txt[..bytes_included];
-                                let id = ShapingId::new(font_size, font_ids, subword_txt, Some(max_width));
+                                let id = ShapingId::new(
+                                    font_size,
+                                    font_ids,
+                                    subword_txt,
+                                    Some(max_width),
+                                    spacing,
+                                    features,
+                                );
                                 if !context.shaped_words_cache.contains(&id) {
                                     let subword = shape_word(
                                         subword_txt,
@@ -661,7 +880,8 @@ fn shape_run(
                                         context,
                                         font_size,
                                         &font_ids,
-                                        letter_spacing,
+                                        spacing,
+                                        features,
                                     );
                                     context.shaped_words_cache.put(id, subword);
                                 }
@@ -719,82 +939,215 @@ fn shape_run(
         }
     }
 
+    if spacing.tab > 0.0 {
+        advance_tabs_to_stops(&mut result, spacing);
+    }
+
     Ok(result)
 }
 
+// Widens tabs so that the glyphs after them start at the next multiple of the tab width
+#[cfg(feature = "text")]
+fn advance_tabs_to_stops(metrics: &mut TextMetrics, spacing: Spacing) {
+    let mut pen_x = 0.0;
+
+    for glyph in &mut metrics.glyphs {
+        if glyph.c == '\t' {
+            let stop = ((pen_x / spacing.tab).floor() + 1.0) * spacing.tab;
+            let advance = stop - pen_x - spacing.after(glyph.c);
+
+            metrics.width += advance - glyph.advance_x;
+            glyph.advance_x = advance;
+        }
+
+        pen_x += glyph.advance_x + spacing.after(glyph.c);
+    }
+}
+
+#[cfg(feature = "text")]
 fn shape_word(
     word: &str,
     hb_direction: rustybuzz::Direction,
     context: &mut TextContextImpl,
     font_size: f32,
     font_ids: &[Option<FontId>; 8],
-    letter_spacing: f32,
+    spacing: Spacing,
+    features: &[rustybuzz::Feature],
 ) -> Result<ShapedWord, ErrorKind> {
     // find_font will call the closure with each font matching the provided style
     // until a font capable of shaping the word is found
-    context.find_font(font_ids, |(font_id, font)| {
-        let face = font.face_ref();
-        // Call harfbuzz
-        let output = {
-            let mut buffer = rustybuzz::UnicodeBuffer::new();
-            buffer.push_str(word);
-            buffer.set_direction(hb_direction);
-
-            rustybuzz::shape(&face, &[], buffer)
-        };
+    let (has_missing, shaped_word) = context.find_font(font_ids, |(font_id, font)| {
+        let shaped_word = shape_with_font(
+            word,
+            hb_direction,
+            font_id,
+            font,
+            &font.face_ref(),
+            font_size,
+            spacing,
+            features,
+        );
+        (shaped_word.0, shaped_word)
+    })?;
 
-        let positions = output.glyph_positions();
-        let infos = output.glyph_infos();
+    // No single font has all the glyphs, so pick the font for each character separately
+    if has_missing && word.chars().nth(1).is_some() {
+        return Ok(
+            shape_with_fallbacks(word, hb_direction, context, font_size, font_ids, spacing, features)
+                .unwrap_or(shaped_word),
+        );
+    }
 
-        let mut shaped_word = ShapedWord {
-            glyphs: Vec::with_capacity(positions.len()),
-            width: 0.0,
-        };
+    Ok(shaped_word)
+}
+
+// Splits the word into runs of characters that are shaped with the first font of the paint having a
+// glyph for them. Other registered fonts are not considered, so the paint's font list decides which
+// fonts text may fall back to.
+#[cfg(feature = "text")]
+fn shape_with_fallbacks(
+    word: &str,
+    hb_direction: rustybuzz::Direction,
+    context: &TextContextImpl,
+    font_size: f32,
+    font_ids: &[Option<FontId>; 8],
+    spacing: Spacing,
+    features: &[rustybuzz::Feature],
+) -> Option<ShapedWord> {
+    let candidates: Vec<_> = font_ids
+        .iter()
+        .map_while(|id| *id)
+        .filter_map(|id| context.font(id).map(|font| (id, font, font.face_ref())))
+        .collect();
+
+    let mut runs: Vec<(Range<usize>, usize)> = Vec::new();
+
+    for (byte_index, c) in word.char_indices() {
+        let end = byte_index + c.len_utf8();
+        let index = candidates.iter().position(|(_, _, face)| face.glyph_index(c).is_some());
+
+        match runs.last_mut() {
+            // Characters that no font has stay with the current run
+            Some((range, current)) if index.is_none() || index == Some(*current) => range.end = end,
+            _ => runs.push((byte_index..end, index.unwrap_or(0))),
+        }
+    }
 
-        let mut has_missing = false;
+    // A single run means there's nothing to gain over the regular shaping
+    if runs.len() < 2 {
+        return None;
+    }
 
-        for (position, (info, c)) in positions.iter().zip(infos.iter().zip(word.chars())) {
-            if info.glyph_id == 0 {
-                has_missing = true;
-            }
+    // Right-to-left glyphs are in visual order, so the runs need to be in visual order as well
+    if hb_direction == rustybuzz::Direction::RightToLeft {
+        runs.reverse();
+    }
 
-            let scale = font.scale(font_size);
+    let mut shaped_word = ShapedWord {
+        glyphs: Vec::new(),
+        width: 0.0,
+    };
 
-            let mut g = ShapedGlyph {
-                x: 0.0,
-                y: 0.0,
-                c,
-                byte_index: info.cluster as usize,
-                font_id,
-                codepoint: info.glyph_id,
-                width: 0.0,
-                height: 0.0,
-                advance_x: position.x_advance as f32 * scale,
-                advance_y: position.y_advance as f32 * scale,
-                offset_x: position.x_offset as f32 * scale,
-                offset_y: position.y_offset as f32 * scale,
-                bearing_x: 0.0,
-                bearing_y: 0.0,
-                bitmap_glyph: false,
-            };
+    for (range, index) in runs {
+        let (font_id, font, face) = &candidates[index];
+        let (_, run) = shape_with_font(
+            &word[range.clone()],
+            hb_direction,
+            *font_id,
+            font,
+            face,
+            font_size,
+            spacing,
+            features,
+        );
 
-            if let Some(glyph) = font.glyph(&face, info.glyph_id as u16) {
-                g.width = glyph.metrics.width * scale;
-                g.height = glyph.metrics.height * scale;
-                g.bearing_x = glyph.metrics.bearing_x * scale;
-                g.bearing_y = glyph.metrics.bearing_y * scale;
-                g.bitmap_glyph = glyph.path.is_none();
-            }
+        shaped_word.width += run.width;
+        shaped_word.glyphs.extend(run.glyphs.into_iter().map(|mut glyph| {
+            glyph.byte_index += range.start;
+            glyph
+        }));
+    }
+
+    Some(shaped_word)
+}
 
-            shaped_word.width += g.advance_x + letter_spacing;
-            shaped_word.glyphs.push(g);
+// Shapes the text with a single font, also returning whether any glyphs were missing in it
+#[cfg(feature = "text")]
+#[allow(clippy::too_many_arguments)]
+fn shape_with_font(
+    word: &str,
+    hb_direction: rustybuzz::Direction,
+    font_id: FontId,
+    font: &Font,
+    face: &rustybuzz::Face<'_>,
+    font_size: f32,
+    spacing: Spacing,
+    features: &[rustybuzz::Feature],
+) -> (bool, ShapedWord) {
+    // Call harfbuzz
+    let output = {
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(word);
+        buffer.set_direction(hb_direction);
+
+        rustybuzz::shape(face, features, buffer)
+    };
+
+    let positions = output.glyph_positions();
+    let infos = output.glyph_infos();
+
+    let mut shaped_word = ShapedWord {
+        glyphs: Vec::with_capacity(positions.len()),
+        width: 0.0,
+    };
+
+    let mut has_missing = false;
+
+    for (position, info) in positions.iter().zip(infos) {
+        // The first character of the cluster, which stays correct for ligatures and right-to-left text
+        let c = word[info.cluster as usize..].chars().next().unwrap_or_default();
+
+        if info.glyph_id == 0 {
+            has_missing = true;
         }
 
-        (has_missing, shaped_word)
-    })
+        let scale = font.scale(font_size);
+
+        let mut g = ShapedGlyph {
+            x: 0.0,
+            y: 0.0,
+            c,
+            byte_index: info.cluster as usize,
+            font_id,
+            codepoint: info.glyph_id,
+            width: 0.0,
+            height: 0.0,
+            advance_x: position.x_advance as f32 * scale,
+            advance_y: position.y_advance as f32 * scale,
+            offset_x: position.x_offset as f32 * scale,
+            offset_y: position.y_offset as f32 * scale,
+            bearing_x: 0.0,
+            bearing_y: 0.0,
+            bitmap_glyph: false,
+        };
+
+        if let Some(glyph) = font.glyph(face, info.glyph_id as u16) {
+            g.width = glyph.metrics.width * scale;
+            g.height = glyph.metrics.height * scale;
+            g.bearing_x = glyph.metrics.bearing_x * scale;
+            g.bearing_y = glyph.metrics.bearing_y * scale;
+            g.bitmap_glyph = glyph.path.is_none();
+        }
+
+        shaped_word.width += g.advance_x + spacing.after(c);
+        shaped_word.glyphs.push(g);
+    }
+
+    (has_missing, shaped_word)
 }
 
 // Calculates the x,y coordinates for each glyph based on their advances. Calculates total width and height of the shaped text run
+#[cfg(feature = "text")]
 fn layout(
     x: f32,
     y: f32,
@@ -845,14 +1198,21 @@ fn layout(
         Baseline::Bottom => descender,
     };
 
+    res.baseline = cursor_y + alignment_offset_y;
+
     for glyph in &mut res.glyphs {
-        glyph.x = cursor_x + glyph.offset_x + glyph.bearing_x;
-        glyph.y = (cursor_y + alignment_offset_y).round() + glyph.offset_y - glyph.bearing_y;
+        if text_settings.subpixel_positioning {
+            glyph.x = cursor_x + glyph.offset_x + glyph.bearing_x;
+            glyph.y = cursor_y + alignment_offset_y + glyph.offset_y - glyph.bearing_y;
+        } else {
+            glyph.x = (cursor_x + glyph.offset_x).round() + glyph.bearing_x;
+            glyph.y = (cursor_y + alignment_offset_y).round() + glyph.offset_y - glyph.bearing_y;
+        }
 
         min_y = min_y.min(glyph.y);
         max_y = max_y.max(glyph.y + glyph.height);
 
-        cursor_x += glyph.advance_x + text_settings.letter_spacing;
+        cursor_x += glyph.advance_x + Spacing::new(text_settings).after(glyph.c);
         cursor_y += glyph.advance_y;
     }
 
@@ -864,6 +1224,13 @@ fn layout(
 
 // Renderer
 
+// Size of newly allocated glyph atlas textures, which is smaller than the default on renderers with a lower limit
+pub(crate) fn atlas_texture_size<T: Renderer>(canvas: &Canvas<T>) -> usize {
+    canvas
+        .max_texture_size()
+        .map_or(TEXTURE_SIZE, |max_size| TEXTURE_SIZE.min(max_size))
+}
+
 #[derive(Clone, Debug)]
 pub struct DrawCommand {
     pub image_id: ImageId,
@@ -901,6 +1268,7 @@ impl GlyphAtlas {
         font_size: f32,
         line_width: f32,
         mode: RenderMode,
+        subpixel_y: bool,
     ) -> Result<GlyphDrawCommands, ErrorKind> {
         let mut alpha_cmd_map = FnvHashMap::default();
         let mut color_cmd_map = FnvHashMap::default();
@@ -915,6 +1283,12 @@ impl GlyphAtlas {
 
         for glyph in &text_layout.glyphs {
             let subpixel_location = crate::geometry::quantize(glyph.x.fract(), 0.1) * 10.0;
+            let baseline_y = glyph.y + glyph.bearing_y;
+            let subpixel_location_y = if subpixel_y {
+                crate::geometry::quantize(baseline_y - baseline_y.floor(), 0.1) * 10.0
+            } else {
+                0.0
+            };
 
             let id = RenderedGlyphId::new(
                 glyph.codepoint,
@@ -923,10 +1297,17 @@ impl GlyphAtlas {
                 line_width,
                 mode,
                 subpixel_location as u8,
+                subpixel_location_y as u8,
             );
 
             if !self.rendered_glyphs.borrow().contains_key(&id) {
-                let glyph = self.render_glyph(canvas, font_size, line_width, mode, glyph)?;
+                let glyph = match self.render_glyph(canvas, font_size, line_width, mode, subpixel_y, glyph) {
+                    Ok(glyph) => glyph,
+                    Err(error) => {
+                        canvas.set_render_target(initial_render_target);
+                        return Err(error);
+                    }
+                };
 
                 self.rendered_glyphs.borrow_mut().insert(id, glyph);
             }
@@ -956,10 +1337,14 @@ impl GlyphAtlas {
                 let line_width_offset = if rendered.color_glyph { 0. } else { line_width_offset };
 
                 q.x0 = glyph.x.trunc() - line_width_offset - GLYPH_PADDING as f32;
-                q.y0 = (glyph.y + glyph.bearing_y).round()
-                    - rendered.bearing_y as f32
-                    - line_width_offset
-                    - GLYPH_PADDING as f32;
+                // With vertical subpixel positioning the fraction of the baseline is part of the rendered glyph
+                let quad_baseline_y = if subpixel_y {
+                    baseline_y.floor()
+                } else {
+                    baseline_y.round()
+                };
+
+                q.y0 = quad_baseline_y - rendered.bearing_y as f32 - line_width_offset - GLYPH_PADDING as f32;
                 q.x1 = q.x0 + rendered.width as f32;
                 q.y1 = q.y0 + rendered.height as f32;
 
@@ -986,6 +1371,7 @@ impl GlyphAtlas {
         font_size: f32,
         line_width: f32,
         mode: RenderMode,
+        subpixel_y: bool,
         glyph: &ShapedGlyph,
     ) -> Result<RenderedGlyph, ErrorKind> {
         let padding = GLYPH_PADDING + GLYPH_MARGIN;
@@ -1016,11 +1402,14 @@ impl GlyphAtlas {
 
         let line_width_offset = (line_width / 2.0).ceil();
 
+        // An extra row leaves room for the vertical subpixel offset
+        let subpixel_row = u32::from(subpixel_y);
         let width = glyph.width.ceil() as u32 + (line_width_offset * 2.0) as u32 + padding * 2;
-        let height = glyph.height.ceil() as u32 + (line_width_offset * 2.0) as u32 + padding * 2;
+        let height = glyph.height.ceil() as u32 + subpixel_row + (line_width_offset * 2.0) as u32 + padding * 2;
 
         let (dst_index, dst_image_id, (dst_x, dst_y)) =
             self.find_texture_or_alloc(canvas, width as usize, height as usize)?;
+        let texture_height = self.glyph_textures.borrow()[dst_index].atlas.size().1;
 
         // render glyph to image
         canvas.save();
@@ -1028,8 +1417,16 @@ impl GlyphAtlas {
 
         let rendered_bearing_y = glyph.bearing_y.round();
         let x_quant = crate::geometry::quantize(glyph.x.fract(), 0.1);
+        let baseline_y = glyph.y + glyph.bearing_y;
+        let y_quant = if subpixel_y {
+            crate::geometry::quantize(baseline_y - baseline_y.floor(), 0.1)
+        } else {
+            0.0
+        };
         let x = dst_x as f32 - glyph.bearing_x + line_width_offset + padding as f32 + x_quant;
-        let y = TEXTURE_SIZE as f32 - dst_y as f32 - rendered_bearing_y - line_width_offset - padding as f32;
+        // The atlas is rendered upside down, so moving the glyph down means decreasing y
+        let y =
+            texture_height as f32 - dst_y as f32 - rendered_bearing_y - line_width_offset - padding as f32 - y_quant;
 
         let rendered_glyph = RenderedGlyph {
             width: width - 2 * GLYPH_MARGIN,
@@ -1048,7 +1445,7 @@ impl GlyphAtlas {
                 canvas.set_render_target(RenderTarget::Image(dst_image_id));
                 canvas.clear_rect(
                     dst_x as u32,
-                    TEXTURE_SIZE as u32 - dst_y as u32 - height,
+                    texture_height as u32 - dst_y as u32 - height,
                     width,
                     height,
                     Color::black(),
@@ -1150,7 +1547,8 @@ impl GlyphAtlas {
 
         if texture_search_result.is_none() {
             // All atlases are exausted and a new one must be created
-            let mut atlas = Atlas::new(TEXTURE_SIZE, TEXTURE_SIZE);
+            let size = atlas_texture_size(canvas);
+            let mut atlas = Atlas::new(size, size);
 
             let loc = atlas
                 .add_rect(width, height)
@@ -1242,7 +1640,7 @@ pub(crate) fn render_direct<T: Renderer>(
     let text_context = canvas.text_context.clone();
     let text_context = text_context.borrow_mut();
 
-    let mut face_cache: HashMap<FontId, rustybuzz::Face> = HashMap::default();
+    let mut face_cache: HashMap<FontId, Face> = HashMap::default();
 
     for glyph in &text_layout.glyphs {
         let (glyph_rendering, scale) = {
@@ -1,11 +1,23 @@
+#[cfg(feature = "text")]
 use fnv::FnvHashMap;
+#[cfg(feature = "text")]
 use rustybuzz::ttf_parser;
+#[cfg(feature = "text")]
 use rustybuzz::ttf_parser::{Face as TtfFont, GlyphId};
-use std::cell::{Ref, RefCell};
-use std::collections::hash_map::Entry;
+use std::cell::Ref;
+#[cfg(feature = "text")]
+use std::{cell::RefCell, collections::hash_map::Entry};
+
+#[cfg(feature = "text")]
+pub(crate) use rustybuzz::Face;
 
 use crate::{ErrorKind, Path};
 
+/// Size and placement of a glyph relative to its origin on the baseline.
+///
+/// `bearing_x` is the distance from the origin to the left edge and `bearing_y` the distance from the
+/// baseline up to the top edge.
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
 pub struct GlyphMetrics {
     pub width: f32,
     pub height: f32,
@@ -18,6 +30,7 @@ pub struct Glyph {
     pub metrics: GlyphMetrics,
 }
 
+#[cfg_attr(not(feature = "text"), allow(dead_code))]
 pub(crate) enum GlyphRendering<'a> {
     RenderAsPath(Ref<'a, Path>),
     #[cfg(feature = "image-loading")]
@@ -25,12 +38,16 @@ pub(crate) enum GlyphRendering<'a> {
 }
 
 /// Information about a font.
-// TODO: underline, strikeout, subscript, superscript metrics
+// TODO: subscript, superscript metrics
 #[derive(Copy, Clone, Default, Debug)]
 pub struct FontMetrics {
     ascender: f32,
     descender: f32,
     height: f32,
+    underline_position: f32,
+    underline_thickness: f32,
+    strikeout_position: f32,
+    strikeout_thickness: f32,
     regular: bool,
     italic: bool,
     bold: bool,
@@ -41,10 +58,15 @@ pub struct FontMetrics {
 }
 
 impl FontMetrics {
+    #[cfg(feature = "text")]
     fn scale(&mut self, scale: f32) {
         self.ascender *= scale;
         self.descender *= scale;
         self.height *= scale;
+        self.underline_position *= scale;
+        self.underline_thickness *= scale;
+        self.strikeout_position *= scale;
+        self.strikeout_thickness *= scale;
     }
 
     /// The distance from the baseline to the top of the highest glyph
@@ -61,6 +83,26 @@ impl FontMetrics {
         self.height.round()
     }
 
+    /// The distance from the baseline to the top of the underline, negative below the baseline
+    pub fn underline_position(&self) -> f32 {
+        self.underline_position
+    }
+
+    /// The suggested thickness of the underline
+    pub fn underline_thickness(&self) -> f32 {
+        self.underline_thickness
+    }
+
+    /// The distance from the baseline to the top of the strikeout line
+    pub fn strikeout_position(&self) -> f32 {
+        self.strikeout_position
+    }
+
+    /// The suggested thickness of the strikeout line
+    pub fn strikeout_thickness(&self) -> f32 {
+        self.strikeout_thickness
+    }
+
     pub fn regular(&self) -> bool {
         self.regular
     }
@@ -90,24 +132,36 @@ impl FontMetrics {
     }
 }
 
+#[cfg(feature = "text")]
 pub(crate) struct Font {
     data: Box<dyn AsRef<[u8]>>,
     face_index: u32,
     units_per_em: u16,
     metrics: FontMetrics,
+    family: Option<String>,
     glyphs: RefCell<FnvHashMap<u16, Glyph>>,
 }
 
+#[cfg(feature = "text")]
 impl Font {
     pub fn new_with_data<T: AsRef<[u8]> + 'static>(data: T, face_index: u32) -> Result<Self, ErrorKind> {
         let ttf_font = TtfFont::parse(data.as_ref(), face_index).map_err(|_| ErrorKind::FontParseError)?;
 
         let units_per_em = ttf_font.units_per_em();
 
+        // Fonts without post or OS/2 tables get decorations at typical proportions of the em size
+        let em = units_per_em as f32;
+        let underline = ttf_font.underline_metrics();
+        let strikeout = ttf_font.strikeout_metrics();
+
         let metrics = FontMetrics {
             ascender: ttf_font.ascender() as f32,
             descender: ttf_font.descender() as f32,
             height: ttf_font.height() as f32,
+            underline_position: underline.map_or(-0.1 * em, |line| line.position as f32),
+            underline_thickness: underline.map_or(0.05 * em, |line| line.thickness as f32),
+            strikeout_position: strikeout.map_or(0.3 * em, |line| line.position as f32),
+            strikeout_thickness: strikeout.map_or(0.05 * em, |line| line.thickness as f32),
             regular: ttf_font.is_regular(),
             italic: ttf_font.is_italic(),
             bold: ttf_font.is_bold(),
@@ -117,17 +171,21 @@ impl Font {
             weight: ttf_font.weight().to_number(),
         };
 
+        let family = name(&ttf_font, ttf_parser::name_id::TYPOGRAPHIC_FAMILY)
+            .or_else(|| name(&ttf_font, ttf_parser::name_id::FAMILY));
+
         Ok(Self {
             data: Box::new(data),
             face_index,
             units_per_em,
             metrics,
+            family,
             glyphs: Default::default(),
         })
     }
 
-    pub fn face_ref(&self) -> rustybuzz::Face<'_> {
-        rustybuzz::Face::from_slice(self.data.as_ref().as_ref(), self.face_index).unwrap()
+    pub fn face_ref(&self) -> Face<'_> {
+        Face::from_slice(self.data.as_ref().as_ref(), self.face_index).unwrap()
     }
 
     pub fn metrics(&self, size: f32) -> FontMetrics {
@@ -138,11 +196,20 @@ impl Font {
         metrics
     }
 
+    pub fn family(&self) -> Option<&str> {
+        self.family.as_deref()
+    }
+
+    // The full name, like "Roboto Bold Italic", falling back to the family if the font has none
+    pub fn full_name(&self) -> Option<String> {
+        name(&self.face_ref(), ttf_parser::name_id::FULL_NAME).or_else(|| self.family.clone())
+    }
+
     pub fn scale(&self, size: f32) -> f32 {
         size / self.units_per_em as f32
     }
 
-    pub fn glyph(&self, face: &rustybuzz::Face<'_>, codepoint: u16) -> Option<Ref<'_, Glyph>> {
+    pub fn glyph(&self, face: &Face<'_>, codepoint: u16) -> Option<Ref<'_, Glyph>> {
         if let Entry::Vacant(entry) = self.glyphs.borrow_mut().entry(codepoint) {
             let mut path = Path::new();
 
@@ -188,7 +255,7 @@ impl Font {
 
     pub fn glyph_rendering_representation(
         &self,
-        face: &rustybuzz::Face<'_>,
+        face: &Face<'_>,
         codepoint: u16,
         _pixels_per_em: u16,
     ) -> Option<GlyphRendering> {
@@ -209,3 +276,65 @@ impl Font {
         })
     }
 }
+
+// Without the text feature there is nothing to parse fonts with, so no font or face ever exists
+#[cfg(not(feature = "text"))]
+pub(crate) struct Font(std::convert::Infallible);
+
+#[cfg(not(feature = "text"))]
+pub(crate) struct Face<'a>(std::convert::Infallible, std::marker::PhantomData<&'a ()>);
+
+#[cfg(not(feature = "text"))]
+impl Font {
+    pub fn new_with_data<T: AsRef<[u8]> + 'static>(_data: T, _face_index: u32) -> Result<Self, ErrorKind> {
+        Err(ErrorKind::TextNotSupported)
+    }
+
+    pub fn face_ref(&self) -> Face<'_> {
+        match self.0 {}
+    }
+
+    pub fn metrics(&self, _size: f32) -> FontMetrics {
+        match self.0 {}
+    }
+
+    pub fn family(&self) -> Option<&str> {
+        match self.0 {}
+    }
+
+    pub fn full_name(&self) -> Option<String> {
+        match self.0 {}
+    }
+
+    pub fn scale(&self, _size: f32) -> f32 {
+        match self.0 {}
+    }
+
+    pub fn glyph(&self, _face: &Face<'_>, _codepoint: u16) -> Option<Ref<'_, Glyph>> {
+        match self.0 {}
+    }
+
+    pub fn glyph_rendering_representation(
+        &self,
+        _face: &Face<'_>,
+        _codepoint: u16,
+        _pixels_per_em: u16,
+    ) -> Option<GlyphRendering<'_>> {
+        match self.0 {}
+    }
+}
+
+// Returns the first Unicode entry of the name table with the given id. These are stored as UTF-16BE.
+#[cfg(feature = "text")]
+fn name(face: &TtfFont<'_>, name_id: u16) -> Option<String> {
+    let name = face
+        .names()
+        .into_iter()
+        .find(|name| name.name_id == name_id && name.is_unicode())?;
+
+    let units = name
+        .name
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+    char::decode_utf16(units).collect::<Result<String, _>>().ok()
+}
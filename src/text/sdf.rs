@@ -0,0 +1,259 @@
+use std::cell::RefCell;
+
+use fnv::FnvHashMap;
+use imgref::Img;
+use rgb::alt::Gray;
+
+use super::{atlas_texture_size, Atlas, DrawCommand, FontId, FontTexture, Quad, TextMetrics, GLYPH_MARGIN};
+use crate::{geometry::Position, Canvas, ErrorKind, ImageFlags, ImageInfo, ImageSource, PixelFormat, Renderer};
+
+// Font size in pixels the distance fields are rendered at
+pub(crate) const SDF_GLYPH_SIZE: f32 = 48.0;
+// Distance in field pixels from the outline to where the field saturates, on both sides
+pub(crate) const SDF_SPREAD: f32 = 6.0;
+
+#[derive(Copy, Clone, Debug)]
+struct SdfGlyph {
+    texture_index: usize,
+    atlas_x: usize,
+    atlas_y: usize,
+    width: usize,
+    height: usize,
+    // Top left corner of the field relative to the glyph origin, in field pixels with y pointing up
+    left: f32,
+    top: f32,
+}
+
+/// Atlas of signed distance fields of glyph outlines.
+///
+/// Each glyph is rendered once at [`SDF_GLYPH_SIZE`], independent of the size and transform it's
+/// drawn with, and the shader reconstructs the edge from the interpolated distance.
+#[derive(Default)]
+pub(crate) struct SdfAtlas {
+    glyphs: RefCell<FnvHashMap<(FontId, u32), Option<SdfGlyph>>>,
+    textures: RefCell<Vec<FontTexture>>,
+}
+
+impl SdfAtlas {
+    /// Returns the quads of the glyphs of the layout, grouped by atlas texture.
+    pub(crate) fn render_atlas<T: Renderer>(
+        &self,
+        canvas: &mut Canvas<T>,
+        text_layout: &TextMetrics,
+        font_size: f32,
+    ) -> Result<Vec<DrawCommand>, ErrorKind> {
+        let mut cmd_map = FnvHashMap::default();
+        let field_scale = font_size / SDF_GLYPH_SIZE;
+
+        for glyph in &text_layout.glyphs {
+            let key = (glyph.font_id, glyph.codepoint);
+
+            let cached = self.glyphs.borrow().get(&key).copied();
+            let rendered = match cached {
+                Some(rendered) => rendered,
+                None => {
+                    let rendered = self.render_glyph(canvas, glyph.font_id, glyph.codepoint)?;
+                    self.glyphs.borrow_mut().insert(key, rendered);
+                    rendered
+                }
+            };
+
+            // Glyphs without an outline, like spaces
+            let rendered = if let Some(rendered) = rendered {
+                rendered
+            } else {
+                continue;
+            };
+
+            let (image_id, texture_size) = {
+                let textures = self.textures.borrow();
+                let texture = &textures[rendered.texture_index];
+                (texture.image_id, texture.atlas.size())
+            };
+            let cmd = cmd_map.entry(rendered.texture_index).or_insert_with(|| DrawCommand {
+                image_id,
+                quads: Vec::new(),
+            });
+
+            let origin_x = glyph.x - glyph.bearing_x;
+            let origin_y = glyph.y + glyph.bearing_y;
+            let itw = 1.0 / texture_size.0 as f32;
+            let ith = 1.0 / texture_size.1 as f32;
+
+            let mut q = Quad {
+                x0: origin_x + rendered.left * field_scale,
+                y0: origin_y - rendered.top * field_scale,
+                s0: rendered.atlas_x as f32 * itw,
+                t0: rendered.atlas_y as f32 * ith,
+                s1: (rendered.atlas_x + rendered.width) as f32 * itw,
+                t1: (rendered.atlas_y + rendered.height) as f32 * ith,
+                ..Default::default()
+            };
+            q.x1 = q.x0 + rendered.width as f32 * field_scale;
+            q.y1 = q.y0 + rendered.height as f32 * field_scale;
+
+            cmd.quads.push(q);
+        }
+
+        Ok(cmd_map.drain().map(|(_, cmd)| cmd).collect())
+    }
+
+    fn render_glyph<T: Renderer>(
+        &self,
+        canvas: &mut Canvas<T>,
+        font_id: FontId,
+        codepoint: u32,
+    ) -> Result<Option<SdfGlyph>, ErrorKind> {
+        let (polylines, left, top, width, height) = {
+            let text_context = canvas.text_context.borrow();
+            let font = text_context.font(font_id).ok_or(ErrorKind::NoFontFound)?;
+            let face = font.face_ref();
+            let scale = font.scale(SDF_GLYPH_SIZE);
+
+            let glyph = match font.glyph(&face, codepoint as u16) {
+                Some(glyph) => glyph,
+                None => return Ok(None),
+            };
+
+            let path = match &glyph.path {
+                Some(path) => path,
+                None => return Ok(None),
+            };
+
+            let polylines: Vec<Vec<Position>> = path
+                .polylines(0.1 / scale)
+                .into_iter()
+                .map(|polyline| {
+                    polyline
+                        .into_iter()
+                        .map(|point| Position {
+                            x: point.x * scale,
+                            y: point.y * scale,
+                        })
+                        .collect()
+                })
+                .collect();
+
+            let metrics = &glyph.metrics;
+            let min_x = (metrics.bearing_x * scale).floor();
+            let max_x = ((metrics.bearing_x + metrics.width) * scale).ceil();
+            let max_y = (metrics.bearing_y * scale).ceil();
+            let min_y = ((metrics.bearing_y - metrics.height) * scale).floor();
+
+            (
+                polylines,
+                min_x - SDF_SPREAD,
+                max_y + SDF_SPREAD,
+                (max_x - min_x + 2.0 * SDF_SPREAD) as usize,
+                (max_y - min_y + 2.0 * SDF_SPREAD) as usize,
+            )
+        };
+
+        let (texture_index, image_id, (x, y)) = self.find_texture_or_alloc(
+            canvas,
+            width + 2 * GLYPH_MARGIN as usize,
+            height + 2 * GLYPH_MARGIN as usize,
+        )?;
+
+        let field = distance_field(&polylines, left, top, width, height);
+        let atlas_x = x + GLYPH_MARGIN as usize;
+        let atlas_y = y + GLYPH_MARGIN as usize;
+        canvas.update_image(
+            image_id,
+            ImageSource::Gray(Img::new(&field, width, height)),
+            atlas_x,
+            atlas_y,
+        )?;
+
+        Ok(Some(SdfGlyph {
+            texture_index,
+            atlas_x,
+            atlas_y,
+            width,
+            height,
+            left,
+            top,
+        }))
+    }
+
+    // Returns (texture index, image id, location in the texture)
+    fn find_texture_or_alloc<T: Renderer>(
+        &self,
+        canvas: &mut Canvas<T>,
+        width: usize,
+        height: usize,
+    ) -> Result<(usize, crate::ImageId, (usize, usize)), ErrorKind> {
+        let found = self
+            .textures
+            .borrow_mut()
+            .iter_mut()
+            .enumerate()
+            .find_map(|(index, texture)| {
+                texture
+                    .atlas
+                    .add_rect(width, height)
+                    .map(|loc| (index, texture.image_id, loc))
+            });
+
+        if let Some(found) = found {
+            return Ok(found);
+        }
+
+        let size = atlas_texture_size(canvas);
+        let mut atlas = Atlas::new(size, size);
+        let loc = atlas
+            .add_rect(width, height)
+            .ok_or(ErrorKind::FontSizeTooLargeForAtlas)?;
+
+        let info = ImageInfo::new(ImageFlags::empty(), size, size, PixelFormat::Gray8);
+        let image_id = canvas.images.alloc(&mut canvas.renderer, info)?;
+
+        // The margins around the fields are sampled by the linear filtering, so they must read as outside
+        let empty = vec![Gray(0); size * size];
+        canvas.update_image(image_id, ImageSource::Gray(Img::new(&empty, size, size)), 0, 0)?;
+
+        let mut textures = self.textures.borrow_mut();
+        textures.push(FontTexture { atlas, image_id });
+
+        Ok((textures.len() - 1, image_id, loc))
+    }
+}
+
+// Renders the signed distance to the outline for each pixel, mapping the outline to 0.5 and inside
+// points to larger values. Glyphs are filled with the even-odd rule, like the other text renderers do.
+fn distance_field(polylines: &[Vec<Position>], left: f32, top: f32, width: usize, height: usize) -> Vec<Gray<u8>> {
+    let mut field = Vec::with_capacity(width * height);
+
+    for row in 0..height {
+        for column in 0..width {
+            let p = Position {
+                x: left + column as f32 + 0.5,
+                y: top - row as f32 - 0.5,
+            };
+
+            let mut inside = false;
+            let mut min_dist_sq = f32::MAX;
+
+            for polyline in polylines.iter().filter(|polyline| polyline.len() > 1) {
+                let mut a = polyline[polyline.len() - 1];
+
+                for &b in polyline {
+                    if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y) {
+                        inside = !inside;
+                    }
+
+                    min_dist_sq = min_dist_sq.min(Position::segment_distance(p, a, b));
+                    a = b;
+                }
+            }
+
+            let dist = min_dist_sq.sqrt();
+            let signed = if inside { dist } else { -dist };
+            let value = (0.5 + signed / (2.0 * SDF_SPREAD)).clamp(0.0, 1.0);
+
+            field.push(Gray((value * 255.0).round() as u8));
+        }
+    }
+
+    field
+}
@@ -0,0 +1,522 @@
+use femtovg::{
+    renderer::{Software, Void},
+    Canvas, Color, CompositeOperation, ImageFlags, Paint, Path,
+};
+use rgb::RGBA8;
+
+mod common;
+
+#[test]
+fn flush_reports_drawing() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    assert!(!canvas.flush());
+
+    let mut path = Path::new();
+    path.rect(10.0, 10.0, 20.0, 20.0);
+    canvas.fill_path(&path, &Paint::color(Color::black()));
+
+    assert!(canvas.flush());
+    assert!(!canvas.flush());
+}
+
+#[test]
+fn flush_reports_drawing_read_back_by_screenshot() {
+    let mut canvas = common::canvas(100, 100);
+
+    let mut path = Path::new();
+    path.rect(10.0, 10.0, 20.0, 20.0);
+    canvas.fill_path(&path, &Paint::color(Color::black()));
+
+    canvas.screenshot().unwrap();
+
+    assert!(canvas.flush());
+    assert!(!canvas.flush());
+}
+
+#[test]
+fn render_stats_count_flushed_work() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut path = Path::new();
+    path.rect(10.0, 10.0, 20.0, 20.0);
+    canvas.fill_path(&path, &Paint::color(Color::black()));
+    canvas.flush();
+
+    let stats = canvas.render_stats();
+    assert_eq!(stats.flushes, 1);
+    assert!(stats.draw_calls >= 1);
+    assert!(stats.triangles >= 2);
+
+    canvas.reset_render_stats();
+    assert_eq!(canvas.render_stats(), Default::default());
+
+    canvas
+        .create_image_from_rgba(2, 2, 8, &[0; 16], femtovg::ImageFlags::empty())
+        .unwrap();
+    canvas.flush();
+
+    let stats = canvas.render_stats();
+    assert_eq!((stats.flushes, stats.draw_calls, stats.texture_uploads), (1, 0, 1));
+}
+
+#[test]
+fn clear_covers_current_target() {
+    use femtovg::{ImageFlags, PixelFormat, RenderTarget};
+
+    let mut canvas = common::canvas(8, 6);
+    let image = canvas
+        .create_image_empty(3, 2, PixelFormat::Rgba8, ImageFlags::empty())
+        .unwrap();
+
+    canvas.clear(Color::rgb(255, 0, 0));
+    canvas.set_render_target(RenderTarget::Image(image));
+    canvas.clear(Color::rgb(0, 0, 255));
+    canvas.set_render_target(RenderTarget::Screen);
+    canvas.flush();
+
+    let screen = canvas.screenshot().unwrap();
+    assert!(screen.pixels().all(|px| px == RGBA8::new(255, 0, 0, 255)));
+}
+
+#[test]
+fn premultiplied_screenshot_round_trip() {
+    use femtovg::ImageFlags;
+
+    let color = Color::rgba_premultiplied(64, 32, 0, 128);
+    assert!((color.r - 0.5).abs() < 1e-6 && (color.g - 0.25).abs() < 1e-6);
+    assert_eq!(Color::rgba_premultiplied(10, 10, 10, 0), Color::rgba(0, 0, 0, 0));
+
+    let mut canvas = common::canvas(4, 4);
+    canvas.clear(Color::rgba(0, 0, 0, 0));
+
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 4.0, 4.0);
+    canvas.fill_path(&path, &Paint::color(Color::rgba(255, 255, 255, 128)));
+    let captured = canvas.screenshot().unwrap();
+    assert_eq!(captured.buf()[0], RGBA8::new(128, 128, 128, 128));
+
+    // Compositing the capture onto black gives the same result as drawing onto black directly
+    let image = canvas
+        .create_image(captured.as_ref(), ImageFlags::PREMULTIPLIED)
+        .unwrap();
+    canvas.clear(Color::black());
+    canvas.fill_path(&path, &Paint::image(image, 0.0, 0.0, 4.0, 4.0, 0.0, 1.0));
+    assert_eq!(canvas.screenshot().unwrap().buf()[0], RGBA8::new(128, 128, 128, 255));
+}
+
+#[test]
+fn size_accessors_return_last_set_size() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    assert_eq!((canvas.width(), canvas.height()), (0, 0));
+
+    canvas.set_size(640, 480, 2.0);
+    assert_eq!((canvas.width(), canvas.height()), (640, 480));
+    assert_eq!(canvas.device_pixel_ratio(), 2.0);
+}
+
+#[test]
+fn clear_rect_f_replaces_with_antialiased_edges() {
+    let mut canvas = common::canvas(5, 1);
+    canvas.clear(Color::white());
+
+    // Neither the transform nor the global alpha apply
+    canvas.translate(10.0, 0.0);
+    canvas.set_global_alpha(0.5);
+    canvas.clear_rect_f(1.5, 0.0, 2.0, 1.0, Color::rgba(255, 0, 0, 128));
+
+    let screen = canvas.screenshot().unwrap();
+    let px = |x: usize| screen[(x, 0)];
+    assert_eq!(px(0), RGBA8::new(255, 255, 255, 255));
+    assert_eq!(px(2), RGBA8::new(128, 0, 0, 128));
+    assert_eq!(px(4), RGBA8::new(255, 255, 255, 255));
+    assert!(px(1).g > 100 && px(1).g < 155 && px(1).r > 128 && px(1).a > 128);
+    assert_eq!(px(1), px(3));
+}
+
+#[test]
+fn rotate_around_keeps_pivot_fixed() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.translate(5.0, 0.0);
+
+    canvas.save();
+    canvas.rotate_around(10.0, 20.0, std::f32::consts::FRAC_PI_2);
+
+    let (x, y) = canvas.transform().transform_point(10.0, 20.0);
+    assert!((x - 15.0).abs() < 1e-4 && (y - 20.0).abs() < 1e-4);
+
+    let (x, y) = canvas.transform().transform_point(11.0, 20.0);
+    assert!((x - 15.0).abs() < 1e-4 && (y - 21.0).abs() < 1e-4);
+
+    canvas.restore();
+    assert_eq!(canvas.transform().transform_point(11.0, 20.0), (16.0, 20.0));
+}
+
+#[test]
+fn save_with_restores_state() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.translate(1.0, 2.0);
+
+    let value = canvas.save_with(|canvas| {
+        canvas.scale(3.0, 3.0);
+        // Left unbalanced on purpose
+        canvas.save();
+        canvas.rotate(1.0);
+        42
+    });
+
+    assert_eq!(value, 42);
+    assert_eq!(canvas.transform().transform_point(0.0, 0.0), (1.0, 2.0));
+    assert_eq!(canvas.transform().transform_point(1.0, 0.0), (2.0, 2.0));
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        canvas.save_with(|canvas| {
+            canvas.translate(10.0, 10.0);
+            panic!("drawing failed");
+        })
+    }));
+
+    assert!(panicked.is_err());
+    assert_eq!(canvas.transform().transform_point(0.0, 0.0), (1.0, 2.0));
+}
+
+#[test]
+fn save_depth_tracks_unbalanced_saves() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    assert_eq!(canvas.save_depth(), 0);
+
+    canvas.save();
+    canvas.save();
+    assert_eq!(canvas.save_depth(), 2);
+    canvas.save_with(|canvas| assert_eq!(canvas.save_depth(), 3));
+
+    canvas.restore();
+    canvas.restore();
+    canvas.restore();
+    assert_eq!(canvas.save_depth(), 0);
+
+    canvas.set_check_balanced_saves(true);
+    assert!(canvas.check_balanced_saves());
+    canvas.flush();
+
+    canvas.save();
+    let flushed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| canvas.flush()));
+    assert!(flushed.is_err());
+}
+
+#[test]
+fn draw_list_recorded_on_other_thread_matches_direct_drawing() {
+    fn assert_send<T: Send>() {}
+    assert_send::<femtovg::DrawList>();
+
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 8.0, 8.0);
+    let paint = Paint::color(Color::rgb(200, 100, 50));
+
+    let list = {
+        let (path, paint) = (path.clone(), paint.clone());
+        std::thread::spawn(move || {
+            let mut list = femtovg::DrawList::new();
+            list.save();
+            list.translate(10.0, 10.0);
+            list.fill_path(&path, &paint);
+            list.restore();
+            list.restore();
+            list.translate(4.0, 20.0);
+            list.fill_path(&path, &paint);
+            list
+        })
+        .join()
+        .unwrap()
+    };
+    assert_eq!(list.len(), 7);
+
+    let mut canvas = common::canvas(40, 40);
+    canvas.translate(2.0, 2.0);
+
+    let recorded = common::redraw(&mut canvas, |canvas| canvas.execute(&list).unwrap());
+
+    // The canvas state is left untouched
+    assert_eq!(canvas.transform().0[4], 2.0);
+    assert_eq!(canvas.save_depth(), 0);
+
+    let direct = common::redraw(&mut canvas, |canvas| {
+        canvas.save_with(|canvas| {
+            canvas.translate(10.0, 10.0);
+            canvas.fill_path(&path, &paint);
+        });
+        canvas.save_with(|canvas| {
+            canvas.translate(4.0, 20.0);
+            canvas.fill_path(&path, &paint);
+        });
+    });
+
+    assert_eq!(recorded.buf(), direct.buf());
+    assert_eq!(common::pixel(&recorded, 15, 15).r, 200);
+}
+
+#[test]
+fn draw_list_state_and_image_operations_match_direct_drawing() {
+    let mut canvas = common::canvas(40, 40);
+    let image = canvas
+        .create_image_from_rgba(2, 2, 8, &[255; 16], ImageFlags::empty())
+        .unwrap();
+
+    let mut path = Path::new();
+    path.circle(20.0, 20.0, 12.0);
+    let fill = Paint::color(Color::rgb(200, 100, 50));
+    let mut stroke = Paint::color(Color::rgb(50, 100, 200)).with_line_width(3.0);
+    stroke.set_line_dash(&[4.0, 2.0]);
+
+    let draw = |list: &mut femtovg::DrawList| {
+        list.scissor(0.0, 0.0, 30.0, 40.0);
+        list.rotate(0.3);
+        list.fill_path(&path, &fill);
+        list.global_composite_operation(CompositeOperation::Lighter);
+        list.stroke_path(&path, &stroke);
+        list.reset_transform();
+        list.draw_image_source_rect(image, [0.0, 0.0, 2.0, 2.0], [2.0, 2.0, 6.0, 6.0], 0.5);
+    };
+
+    let mut list = femtovg::DrawList::new();
+    draw(&mut list);
+    let recorded = common::redraw(&mut canvas, |canvas| canvas.execute(&list).unwrap());
+
+    let direct = common::redraw(&mut canvas, |canvas| {
+        canvas.save_with(|canvas| {
+            canvas.scissor(0.0, 0.0, 30.0, 40.0);
+            canvas.rotate(0.3);
+            canvas.fill_path(&path, &fill);
+            canvas.global_composite_operation(CompositeOperation::Lighter);
+            canvas.stroke_path(&path, &stroke);
+            canvas.reset_transform();
+            canvas
+                .draw_image_source_rect(image, [0.0, 0.0, 2.0, 2.0], [2.0, 2.0, 6.0, 6.0], 0.5)
+                .unwrap();
+        });
+    });
+
+    assert_eq!(recorded.buf(), direct.buf());
+    // Cut off by the scissor
+    assert_eq!(common::pixel(&recorded, 34, 20), RGBA8::new(0, 0, 0, 255));
+    assert!((127..=128).contains(&common::pixel(&recorded, 4, 4).r));
+
+    // Images are looked up when the list is executed
+    canvas.delete_image(image);
+    assert!(canvas.execute(&list).is_err());
+}
+
+#[test]
+fn draw_list_errors_restore_the_canvas_state() {
+    use femtovg::{DrawList, PixelFormat, Transform2D};
+
+    let mut canvas = Canvas::new(Void).unwrap();
+    let image = canvas
+        .create_image_empty(4, 4, PixelFormat::Rgba8, ImageFlags::empty())
+        .unwrap();
+    canvas.delete_image(image);
+
+    let mut list = DrawList::new();
+    list.save();
+    list.translate(10.0, 10.0);
+    list.save();
+    list.draw_image_source_rect(image, [0.0, 0.0, 4.0, 4.0], [0.0, 0.0, 4.0, 4.0], 1.0);
+    list.restore();
+    list.restore();
+
+    canvas.save();
+    assert!(canvas.execute(&list).is_err());
+    assert_eq!(canvas.save_depth(), 1);
+    assert_eq!(canvas.transform(), Transform2D::identity());
+
+    canvas.restore();
+    assert_eq!(canvas.save_depth(), 0);
+}
+
+#[test]
+fn dirty_rects_limit_rendering_on_flush() {
+    let mut canvas = common::canvas(40, 40);
+
+    canvas.clear(Color::rgb(255, 0, 0));
+    canvas.flush();
+
+    canvas.translate(5.0, 0.0);
+    canvas.add_dirty_rect(5.0, 10.0, 10.0, 5.5);
+    canvas.add_dirty_rect(10.0, 20.0, 5.0, 5.0);
+    canvas.reset_transform();
+
+    let bounds: femtovg::Bounds = canvas.dirty_bounds().unwrap();
+    assert_eq!(
+        (bounds.minx, bounds.miny, bounds.maxx, bounds.maxy),
+        (10.0, 10.0, 20.0, 25.0)
+    );
+
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 40.0, 40.0);
+
+    canvas.clear(Color::black());
+    canvas.fill_path(&path, &Paint::color(Color::rgb(0, 0, 255)));
+    canvas.scissor(15.0, 0.0, 40.0, 40.0);
+    canvas.fill_path(&path, &Paint::color(Color::white()));
+    canvas.flush();
+    assert!(canvas.dirty_bounds().is_none());
+
+    let screen = canvas.screenshot().unwrap();
+    let pixel = |x: usize, y: usize| {
+        let p = screen[(x, y)];
+        (p.r, p.g, p.b)
+    };
+
+    assert_eq!(pixel(12, 12), (0, 0, 255));
+    assert_eq!(pixel(17, 24), (255, 255, 255));
+    assert_eq!(pixel(5, 12), (255, 0, 0));
+    assert_eq!(pixel(25, 12), (255, 0, 0));
+    assert_eq!(pixel(12, 30), (255, 0, 0));
+}
+
+#[test]
+#[cfg(feature = "text")]
+fn errors_have_descriptive_messages() {
+    use femtovg::ErrorKind;
+    use std::error::Error;
+
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let err = canvas.add_font("this/font/does/not/exist.ttf").unwrap_err();
+    assert!(matches!(err, ErrorKind::IoError(_)));
+    assert!(err.to_string().starts_with("io error: "));
+    assert!(err.source().is_some());
+
+    let err = canvas.add_font_mem(&[0; 16]).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse font data");
+    assert!(err.source().is_none());
+}
+
+#[test]
+#[cfg(not(feature = "text"))]
+fn text_functions_fail_without_the_text_feature() {
+    use femtovg::ErrorKind;
+
+    let mut canvas = Canvas::new(Void).unwrap();
+    let paint = Paint::color(Color::black());
+
+    assert!(matches!(
+        canvas.add_font_mem(&[0; 16]),
+        Err(ErrorKind::TextNotSupported)
+    ));
+    assert!(matches!(
+        canvas.add_font(common::ROBOTO_REGULAR),
+        Err(ErrorKind::TextNotSupported)
+    ));
+    assert!(matches!(
+        canvas.fill_text(10.0, 10.0, "Hello", &paint),
+        Err(ErrorKind::TextNotSupported)
+    ));
+    assert!(matches!(
+        canvas.measure_text(10.0, 10.0, "Hello", &paint),
+        Err(ErrorKind::TextNotSupported)
+    ));
+    assert_eq!(Canvas::<Void>::count_faces(&[0; 16]), 0);
+}
+
+#[test]
+fn logical_size_is_scaled_by_dpi() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    canvas.set_logical_size(400.0, 300.5, 1.5);
+    assert_eq!((canvas.width(), canvas.height()), (600, 451));
+    assert_eq!(canvas.device_pixel_ratio(), 1.5);
+
+    canvas.set_logical_size(100.0, 100.0, 1.0);
+    assert_eq!((canvas.width(), canvas.height()), (100, 100));
+}
+
+#[test]
+fn screen_and_canvas_coordinates_convert() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.translate(10.0, 20.0);
+    canvas.scale(2.0, 4.0);
+
+    let (x, y) = canvas.screen_to_canvas(30.0, 60.0);
+    assert!((x - 10.0).abs() < 1e-5 && (y - 10.0).abs() < 1e-5);
+
+    let (x, y) = canvas.canvas_to_screen(x, y);
+    assert!((x - 30.0).abs() < 1e-4 && (y - 60.0).abs() < 1e-4);
+}
+
+#[test]
+fn capabilities_describe_the_renderer() {
+    let mut canvas = Canvas::new(Software::new()).unwrap();
+    let capabilities = canvas.capabilities();
+    assert_eq!(capabilities.backend, "Software");
+    assert_eq!(capabilities.max_texture_size, None);
+    assert_eq!(capabilities.msaa_samples, 0);
+    assert!(capabilities.render_to_texture);
+
+    canvas.set_max_texture_size(Some(1024));
+    assert_eq!(canvas.capabilities().max_texture_size, Some(1024));
+
+    let canvas = Canvas::new(Void).unwrap();
+    assert_eq!(canvas.capabilities().backend, "Void");
+    assert!(!canvas.capabilities().render_to_texture);
+}
+
+#[test]
+fn current_transform_matrix_returns_coefficients() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    assert_eq!(canvas.current_transform_matrix(), [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+    canvas.translate(10.0, 20.0);
+    canvas.scale(2.0, 3.0);
+    assert_eq!(canvas.current_transform_matrix(), [2.0, 0.0, 0.0, 3.0, 10.0, 20.0]);
+    assert_eq!(canvas.current_transform_matrix(), canvas.transform().0);
+}
+
+#[test]
+fn restore_to_unwinds_to_depth() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.translate(1.0, 0.0);
+    canvas.save();
+
+    let depth = canvas.save_depth();
+    let transform = canvas.transform();
+
+    for _ in 0..3 {
+        canvas.save();
+        canvas.translate(5.0, 5.0);
+    }
+
+    canvas.restore_to(depth);
+    assert_eq!(canvas.save_depth(), depth);
+    assert_eq!(canvas.transform(), transform);
+
+    // Restoring to a deeper level than the current one does nothing
+    canvas.restore_to(depth + 2);
+    assert_eq!(canvas.save_depth(), depth);
+    canvas.restore_to(usize::MAX);
+    assert_eq!(canvas.save_depth(), depth);
+
+    canvas.restore_to(0);
+    assert_eq!(canvas.save_depth(), 0);
+    assert_eq!(canvas.transform(), transform);
+}
+
+#[test]
+fn screenshot_can_be_unpremultiplied() {
+    let mut canvas = common::canvas(4, 4);
+    canvas.clear_rect(0, 0, 4, 4, Color::rgba(0, 0, 0, 0));
+
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 4.0, 4.0);
+    canvas.fill_path(&path, &Paint::fill(Color::rgba(255, 128, 0, 128)));
+    assert!(canvas.screenshot_premultiplied());
+
+    let premultiplied = canvas.screenshot().unwrap().buf()[0];
+    assert_eq!((premultiplied.r, premultiplied.g, premultiplied.a), (128, 64, 128));
+
+    canvas.set_screenshot_premultiplied(false);
+    let straight = canvas.screenshot().unwrap().buf()[0];
+    assert_eq!((straight.r, straight.g, straight.b, straight.a), (255, 128, 0, 128));
+}
@@ -0,0 +1,51 @@
+//! Helpers shared by the integration tests.
+
+// Every test crate uses a different subset of the helpers
+#![allow(dead_code)]
+
+use femtovg::{renderer::Software, Canvas, Color};
+use imgref::ImgVec;
+use rgb::RGBA8;
+
+pub const ROBOTO_REGULAR: &str = "examples/assets/Roboto-Regular.ttf";
+
+/// Returns a software rendered canvas of the given size with a device pixel ratio of 1.0.
+pub fn canvas(width: u32, height: u32) -> Canvas<Software> {
+    let mut canvas = Canvas::new(Software::new()).unwrap();
+    canvas.set_size(width, height, 1.0);
+    canvas
+}
+
+/// Same as [`canvas`], with the Roboto Regular font loaded.
+#[cfg(feature = "text")]
+pub fn canvas_with_font(width: u32, height: u32) -> Canvas<Software> {
+    let mut canvas = canvas(width, height);
+    canvas.add_font(ROBOTO_REGULAR).expect("Font not found");
+    canvas
+}
+
+/// Draws a scene onto a `width` x `height` canvas cleared to black and returns what ended up on screen.
+pub fn render(width: u32, height: u32, draw: impl FnOnce(&mut Canvas<Software>)) -> ImgVec<RGBA8> {
+    let mut canvas = canvas(width, height);
+    canvas.clear(Color::black());
+
+    draw(&mut canvas);
+
+    canvas.screenshot().unwrap()
+}
+
+/// Clears the canvas to black, draws a scene and returns what ended up on screen.
+///
+/// Unlike [`render`] this keeps the canvas, for tests that check how its settings affect drawing.
+pub fn redraw(canvas: &mut Canvas<Software>, draw: impl FnOnce(&mut Canvas<Software>)) -> ImgVec<RGBA8> {
+    canvas.clear(Color::black());
+
+    draw(canvas);
+
+    canvas.screenshot().unwrap()
+}
+
+/// Returns the pixel at `x`, `y` of a rendered image.
+pub fn pixel(image: &ImgVec<RGBA8>, x: usize, y: usize) -> RGBA8 {
+    image[(x, y)]
+}
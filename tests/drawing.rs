@@ -0,0 +1,490 @@
+use femtovg::{
+    renderer::{Software, Void},
+    Canvas, Color, DebugMode, FillRule, Paint, Path,
+};
+use rgb::RGBA8;
+
+mod common;
+
+#[test]
+fn dashed_stroke_leaves_gaps() {
+    let mut canvas = common::canvas(40, 10);
+
+    let mut path = Path::new();
+    path.move_to(0.0, 5.0);
+    path.line_to(40.0, 5.0);
+
+    let mut paint = Paint::color(Color::white())
+        .with_line_width(4.0)
+        .with_line_dash(&[10.0, 10.0]);
+
+    for (offset, lit_x, dark_x) in [(0.0, 5, 15), (10.0, 15, 5)] {
+        paint.set_line_dash_offset(offset);
+
+        let screen = common::redraw(&mut canvas, |canvas| canvas.stroke_path(&path, &paint));
+        let red = |x: usize| screen[(x, 5)].r;
+
+        assert_eq!(red(lit_x), 255);
+        assert_eq!(red(lit_x + 20), 255);
+        assert_eq!(red(dark_x), 0);
+        assert_eq!(red(dark_x + 20), 0);
+    }
+
+    // The triangles returned for the stroke leave the same gaps
+    paint.set_line_dash_offset(0.0);
+    let triangles = canvas.tessellate_stroke(&path, &paint);
+    assert!(!triangles.is_empty());
+    assert!(triangles.iter().all(|vertex| vertex.x < 11.0 || vertex.x > 19.0));
+}
+
+#[test]
+fn dash_offset_is_exact_when_zoomed_in() {
+    let mut path = Path::new();
+    path.move_to(0.0, 1.0);
+    path.line_to(40.0, 1.0);
+
+    let paint = Paint::color(Color::white())
+        .with_line_width(1.0)
+        .with_line_dash(&[10.0, 10.0])
+        .with_line_dash_offset(0.15);
+
+    let screen = common::render(160, 8, |canvas| {
+        canvas.scale(4.0, 4.0);
+        canvas.stroke_path(&path, &paint);
+    });
+    let red = |x: usize| screen[(x, 4)].r;
+
+    // The first dash ends at 9.85, 39.4 device pixels
+    assert_eq!(red(38), 255);
+    assert!(red(39) > 50 && red(39) < 200, "{}", red(39));
+    assert_eq!(red(40), 0);
+}
+
+#[test]
+fn dotted_stroke_draws_separate_dots() {
+    let mut path = Path::new();
+    path.move_to(4.0, 5.0);
+    path.line_to(40.0, 5.0);
+
+    let paint = Paint::color(Color::white()).dotted(2.0, 8.0);
+    assert_eq!(paint.line_width(), 4.0);
+    assert_eq!(paint.line_cap_start(), femtovg::LineCap::Round);
+    assert_eq!(paint.line_cap_end(), femtovg::LineCap::Round);
+
+    let screen = common::render(40, 10, |canvas| canvas.stroke_path(&path, &paint));
+    let red = |x: usize| screen[(x, 5)].r;
+
+    // Dots are centered at 4, 12, 20, ...
+    for center in [4, 12, 20, 28] {
+        assert!(red(center) > 200);
+        assert_eq!(red(center + 4), 0);
+    }
+}
+
+#[test]
+fn software_renderer_fills_rect() {
+    let image = common::render(20, 20, |canvas| {
+        let mut path = Path::new();
+        path.rect(5.0, 5.0, 10.0, 10.0);
+        canvas.fill_path(&path, &Paint::color(Color::rgb(255, 0, 0)));
+    });
+    assert_eq!((image.width(), image.height()), (20, 20));
+
+    let pixel = |x: usize, y: usize| image[(x, y)];
+
+    assert_eq!(pixel(10, 10), RGBA8::new(255, 0, 0, 255));
+    assert_eq!(pixel(5, 5), RGBA8::new(255, 0, 0, 255));
+    assert_eq!(pixel(4, 10), RGBA8::new(0, 0, 0, 255));
+    assert_eq!(pixel(15, 10), RGBA8::new(0, 0, 0, 255));
+}
+
+#[test]
+fn software_renderer_respects_fill_rule() {
+    let mut canvas = common::canvas(40, 40);
+
+    let mut star = Path::new();
+    let points = (0..5).map(|i| {
+        let angle = -std::f32::consts::FRAC_PI_2 + i as f32 * 4.0 * std::f32::consts::PI / 5.0;
+        (20.0 + 18.0 * angle.cos(), 20.0 + 18.0 * angle.sin())
+    });
+    for (i, (x, y)) in points.enumerate() {
+        if i == 0 {
+            star.move_to(x, y);
+        } else {
+            star.line_to(x, y);
+        }
+    }
+    star.close();
+
+    let center_alpha = |canvas: &mut Canvas<Software>| common::pixel(&canvas.screenshot().unwrap(), 20, 20).a;
+
+    canvas.fill_path(&star, &Paint::color(Color::white()).with_fill_rule(FillRule::EvenOdd));
+    assert_eq!(center_alpha(&mut canvas), 0);
+
+    canvas.fill_path(&star, &Paint::color(Color::white()).with_fill_rule(FillRule::NonZero));
+    assert_eq!(center_alpha(&mut canvas), 255);
+}
+
+#[test]
+fn even_odd_star_fill_matches_contains_point() {
+    // Pentagram drawn as a single self-intersecting contour
+    let mut star = Path::new();
+    for i in 0..5 {
+        let angle = -std::f32::consts::FRAC_PI_2 + i as f32 * 4.0 * std::f32::consts::PI / 5.0;
+        let (x, y) = (30.0 + 28.0 * angle.cos(), 30.0 + 28.0 * angle.sin());
+        if i == 0 {
+            star.move_to(x, y);
+        } else {
+            star.line_to(x, y);
+        }
+    }
+    star.close();
+
+    let mut canvas = common::canvas(60, 60);
+
+    for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+        canvas.clear_rect(0, 0, 60, 60, Color::rgba(0, 0, 0, 0));
+        canvas.fill_path(&star, &Paint::color(Color::white()).with_fill_rule(fill_rule));
+        let screen = canvas.screenshot().unwrap();
+
+        // Away from the anti-aliased edges the coverage has to agree with the hit test
+        for y in 1..59 {
+            for x in 1..59 {
+                let alpha = common::pixel(&screen, x, y).a;
+                let uniform = (y - 1..=y + 1).all(|y| (x - 1..=x + 1).all(|x| common::pixel(&screen, x, y).a == alpha));
+                if uniform && (alpha == 0 || alpha == 255) {
+                    let inside = canvas.contains_point(&star, x as f32 + 0.5, y as f32 + 0.5, fill_rule);
+                    assert_eq!(inside, alpha == 255, "{fill_rule:?} ({x}, {y})");
+                }
+            }
+        }
+
+        // The inner pentagon is a hole under even-odd, the points are filled either way
+        let center = common::pixel(&screen, 30, 30).a;
+        assert_eq!(center, if fill_rule == FillRule::EvenOdd { 0 } else { 255 });
+        assert_eq!(common::pixel(&screen, 30, 6).a, 255);
+    }
+}
+
+#[test]
+fn debug_modes_rewrite_drawing() {
+    let mut canvas = common::canvas(40, 40);
+
+    let mut path = Path::new();
+    path.rect(10.0, 10.0, 20.0, 20.0);
+    let paint = Paint::color(Color::white());
+
+    let pixel = |canvas: &mut Canvas<Software>, x: usize, y: usize| canvas.screenshot().unwrap()[(x, y)];
+
+    // Only the triangle edges are drawn, including the diagonal of the rectangle
+    canvas.set_debug_mode(DebugMode::Wireframe);
+    canvas.fill_path(&path, &paint);
+    assert_eq!(pixel(&mut canvas, 25, 15).a, 0);
+    assert_eq!(pixel(&mut canvas, 20, 20), RGBA8::new(0, 255, 0, 255));
+
+    // Overlapping draws accumulate
+    canvas.clear_rect(0, 0, 40, 40, Color::rgbaf(0.0, 0.0, 0.0, 0.0));
+    canvas.set_debug_mode(DebugMode::Overdraw);
+    canvas.fill_path(&path, &paint);
+    let once = pixel(&mut canvas, 20, 20);
+    canvas.fill_path(&path, &paint);
+    let twice = pixel(&mut canvas, 20, 20);
+    assert!(once.a > 0 && twice.a > once.a);
+}
+
+#[test]
+fn box_shadow_falls_off_smoothly() {
+    let screen = common::render(60, 60, |canvas| {
+        canvas.draw_box_shadow(20.0, 20.0, 20.0, 20.0, 0.0, 8.0, Color::white());
+        canvas.flush();
+    });
+    let row: Vec<u8> = (0..60).map(|x| common::pixel(&screen, x, 30).r).collect();
+
+    // Opaque well inside, half covered at the edge and fading out towards the outside
+    assert!(row[30] > 245);
+    assert!((row[20] as i32 - 128).abs() < 20);
+    assert_eq!(row[2], 0);
+    assert!(row[2..=30].windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!((row[15] as i32 - row[44] as i32).abs() <= 2);
+}
+
+#[test]
+fn linear_color_space_blends_gamma_correct() {
+    use femtovg::ColorSpace;
+
+    let render = |color_space: ColorSpace| {
+        let mut canvas = common::canvas(4, 1);
+        canvas.set_color_space(color_space);
+
+        let screen = common::redraw(&mut canvas, |canvas| {
+            let mut path = Path::new();
+            path.rect(0.0, 0.0, 2.0, 1.0);
+            canvas.fill_path(&path, &Paint::color(Color::rgba(255, 255, 255, 128)));
+            path = Path::new();
+            path.rect(2.0, 0.0, 2.0, 1.0);
+            canvas.fill_path(&path, &Paint::color(Color::rgb(200, 100, 50)));
+        });
+        (screen.buf()[0], screen.buf()[3])
+    };
+
+    let (blended, solid) = render(ColorSpace::Srgb);
+    assert_eq!(blended, RGBA8::new(128, 128, 128, 255));
+    assert_eq!(solid, RGBA8::new(200, 100, 50, 255));
+
+    // Half of the light is encoded brighter than half of the sRGB value, opaque colors come out unchanged
+    let (blended, solid) = render(ColorSpace::Linear);
+    assert_eq!(blended, RGBA8::new(188, 188, 188, 255));
+    assert_eq!(solid, RGBA8::new(200, 100, 50, 255));
+}
+
+#[test]
+fn antialias_feather_sharpens_edges() {
+    let mut canvas = common::canvas(10, 10);
+    assert_eq!(canvas.antialias_feather(), 1.0);
+
+    let mut path = Path::new();
+    path.rect(2.3, 0.0, 5.0, 10.0);
+
+    let edge_coverage = |canvas: &mut Canvas<Software>| {
+        let screen = common::redraw(canvas, |canvas| {
+            canvas.fill_path(&path, &Paint::color(Color::white()));
+        });
+        (common::pixel(&screen, 1, 5).r, common::pixel(&screen, 2, 5).r)
+    };
+
+    let (outside, soft) = edge_coverage(&mut canvas);
+    assert!(outside < 64 && soft > 128 && soft < 255);
+
+    canvas.set_antialias_feather(0.25);
+    assert_eq!(canvas.antialias_feather(), 0.25);
+
+    let (outside, sharp) = edge_coverage(&mut canvas);
+    assert_eq!(outside, 0);
+    assert!(sharp > soft);
+
+    // The feather survives resizing
+    canvas.set_size(10, 10, 1.0);
+    assert_eq!(edge_coverage(&mut canvas).1, sharp);
+}
+
+#[test]
+fn pixel_snapping_keeps_hairlines_crisp() {
+    let mut canvas = common::canvas(10, 10);
+
+    let mut path = Path::new();
+    path.move_to(0.0, 5.0);
+    path.line_to(10.0, 5.0);
+    path.move_to(3.2, 0.0);
+    path.line_to(3.2, 10.0);
+
+    let paint = Paint::color(Color::white()).with_line_width(1.0);
+
+    let render = |canvas: &mut Canvas<Software>| {
+        let screen = common::redraw(canvas, |canvas| canvas.stroke_path(&path, &paint));
+        let red = |x: usize, y: usize| screen[(x, y)].r;
+        [red(8, 4), red(8, 5), red(2, 8), red(3, 8)]
+    };
+
+    let [above, below, left, right] = render(&mut canvas);
+    assert!(above > 0 && above < 255 && below > 0 && below < 255);
+    assert!(left > 0 && right > 0);
+
+    canvas.set_pixel_snapping(true);
+    assert_eq!(render(&mut canvas), [0, 255, 0, 255]);
+
+    // Snapping doesn't leak into the cached path used for other draws
+    canvas.set_pixel_snapping(false);
+    let [above, ..] = render(&mut canvas);
+    assert!(above > 0);
+}
+
+#[test]
+fn fill_and_stroke_path_draws_stroke_on_top() {
+    let mut path = Path::new();
+    path.rect(4.0, 4.0, 12.0, 12.0);
+
+    let fill = Paint::color(Color::rgb(255, 0, 0));
+    let stroke = Paint::color(Color::rgb(0, 0, 255)).with_line_width(2.0);
+
+    let separate = common::render(20, 20, |canvas| {
+        canvas.fill_path(&path, &fill);
+        canvas.stroke_path(&path, &stroke);
+    });
+    let combined = common::render(20, 20, |canvas| canvas.fill_and_stroke_path(&path, &fill, &stroke));
+
+    assert_eq!(combined.buf(), separate.buf());
+    assert_eq!(common::pixel(&combined, 4, 10).b, 255);
+    assert_eq!(common::pixel(&combined, 10, 10).r, 255);
+}
+
+#[test]
+fn non_scaling_stroke_ignores_transform_scale() {
+    let mut canvas = common::canvas(20, 20);
+    canvas.scale(4.0, 4.0);
+
+    let mut path = Path::new();
+    path.move_to(0.0, 2.5);
+    path.line_to(5.0, 2.5);
+
+    let mut render = |paint: &Paint| {
+        let screen = common::redraw(&mut canvas, |canvas| canvas.stroke_path(&path, paint));
+        (0..20).filter(|&y| common::pixel(&screen, 10, y).r > 128).count()
+    };
+
+    let paint = Paint::color(Color::white()).with_line_width(2.0);
+    assert_eq!(render(&paint), 8);
+
+    let paint = paint.with_non_scaling_stroke(true);
+    assert!(paint.non_scaling_stroke());
+    assert_eq!(render(&paint), 2);
+}
+
+#[test]
+fn draw_points_matches_filled_circles() {
+    use femtovg::testing;
+
+    let points = [(10.0, 10.0), (30.0, 12.5), (21.5, 30.0)];
+
+    let dots = testing::render_to_image(40, 40, |canvas| {
+        canvas.draw_points(&points, 6.0, &Paint::fill(Color::white()));
+    });
+    let circles = testing::render_to_image(40, 40, |canvas| {
+        for (x, y) in points {
+            let mut path = Path::new();
+            path.circle(x, y, 6.0);
+            canvas.fill_path(&path, &Paint::fill(Color::white()));
+        }
+    });
+
+    let coverage = |image: &[u8]| image.chunks(4).map(|pixel| pixel[3] as u32).sum::<u32>();
+    // Only the anti-aliased edges may differ slightly, the tessellations aren't identical
+    assert!(coverage(&dots).abs_diff(coverage(&circles)) < coverage(&circles) / 20);
+    assert_eq!(testing::pixel_difference(&dots, &circles, 48), 0);
+
+    // One draw call for the whole batch, and points smaller than a pixel fade instead of vanishing
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(40, 40, 1.0);
+    canvas.reset_render_stats();
+    canvas.draw_points(&points, 2.0, &Paint::fill(Color::white()));
+    canvas.flush();
+    assert_eq!(canvas.render_stats().draw_calls, 1);
+
+    let single = testing::render_to_image(40, 40, |canvas| {
+        canvas.draw_point(10.0, 10.0, 0.25, &Paint::fill(Color::white()));
+    });
+    let center = &single[(10 * 40 + 10) * 4..][..4];
+    assert!(center[3] > 0 && center[3] < 64, "{center:?}");
+}
+
+#[test]
+fn draw_lines_matches_stroked_segments() {
+    use femtovg::testing;
+
+    let lines = [
+        (4.0, 10.0, 36.0, 10.0),
+        (10.0, 4.0, 10.0, 36.0),
+        (16.0, 16.0, 34.0, 30.0),
+    ];
+    let paint = Paint::stroke(Color::white(), 3.0);
+
+    let batched = testing::render_to_image(40, 40, |canvas| {
+        canvas.draw_lines(&lines, &paint);
+    });
+    let stroked = testing::render_to_image(40, 40, |canvas| {
+        for (x0, y0, x1, y1) in lines {
+            let mut path = Path::new();
+            path.move_to(x0, y0);
+            path.line_to(x1, y1);
+            canvas.stroke_path(&path, &paint);
+        }
+    });
+
+    assert_eq!(testing::pixel_difference(&batched, &stroked, 2), 0);
+
+    // Square caps extend the segments by half the line width on both ends
+    let square = testing::render_to_image(40, 40, |canvas| {
+        canvas.draw_lines(&lines[..1], &paint.clone().with_line_cap(femtovg::LineCap::Square));
+    });
+    assert_eq!(square[(10 * 40 + 3) * 4 + 3], 255);
+    assert_eq!(batched[(10 * 40 + 3) * 4 + 3], 0);
+
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(40, 40, 1.0);
+    canvas.reset_render_stats();
+    canvas.draw_lines(&lines, &paint);
+    canvas.flush();
+    assert_eq!(canvas.render_stats().draw_calls, 1);
+}
+
+#[test]
+fn feathered_scissor_fades_edges() {
+    use femtovg::testing;
+
+    let draw = |clip: &dyn Fn(&mut Canvas<Software>)| {
+        testing::render_to_image(50, 50, |canvas| {
+            clip(canvas);
+            canvas.reset_transform();
+
+            let mut path = Path::new();
+            path.rect(0.0, 0.0, 50.0, 50.0);
+            canvas.fill_path(&path, &Paint::fill(Color::white()));
+        })
+    };
+
+    let feathered = draw(&|canvas| canvas.scissor_feathered(5.0, 5.0, 40.0, 40.0, 10.0));
+    let alpha = |x: usize| feathered[(25 * 50 + x) * 4 + 3];
+
+    assert_eq!(alpha(2), 0);
+    assert!(alpha(5) < alpha(10) && alpha(10) < alpha(14) && alpha(14) < 255);
+    assert!(alpha(10).abs_diff(140) <= 2, "{}", alpha(10));
+    assert_eq!(alpha(25), 255);
+
+    // The feather scales with the transform and is kept when intersecting
+    let scaled = draw(&|canvas| {
+        canvas.scale(2.0, 2.0);
+        canvas.scissor_feathered(2.5, 2.5, 20.0, 20.0, 5.0);
+    });
+    assert_eq!(testing::pixel_difference(&feathered, &scaled, 2), 0);
+
+    let intersected = draw(&|canvas| {
+        canvas.scissor_feathered(5.0, 5.0, 40.0, 40.0, 10.0);
+        canvas.scale(0.5, 0.5);
+        canvas.intersect_scissor(0.0, 0.0, 100.0, 100.0);
+    });
+    assert_eq!(testing::pixel_difference(&feathered, &intersected, 2), 0);
+}
+
+#[test]
+#[cfg(feature = "image-loading")]
+fn rendered_scene_matches_reference_image() {
+    use femtovg::testing;
+
+    let scene = |line_width: f32| {
+        move |canvas: &mut Canvas<Software>| {
+            let mut path = Path::new();
+            path.rect(4.0, 4.0, 12.0, 8.0);
+            canvas.fill_path(&path, &Paint::color(Color::rgb(200, 40, 40)));
+
+            let mut path = Path::new();
+            path.circle(22.0, 16.0, 6.0);
+            canvas.stroke_path(&path, &Paint::color(Color::white()).with_line_width(line_width));
+        }
+    };
+
+    let image = testing::render_to_image(32, 24, scene(2.0));
+    assert_eq!(image.len(), 32 * 24 * 4);
+    assert_eq!(
+        testing::pixel_difference(&image, &testing::render_to_image(32, 24, scene(2.0)), 0),
+        0
+    );
+
+    let reference = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/reference/rect_and_circle.png");
+    assert_eq!(testing::reference_difference(&image, 32, 24, reference, 1).unwrap(), 0);
+
+    let changed = testing::render_to_image(32, 24, scene(3.0));
+    assert!(testing::reference_difference(&changed, 32, 24, reference, 1).unwrap() > 0);
+    assert_eq!(
+        testing::reference_difference(&image, 24, 32, reference, 1).unwrap(),
+        32 * 24
+    );
+}
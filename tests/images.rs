@@ -0,0 +1,293 @@
+use femtovg::{
+    renderer::{Software, Void},
+    Canvas, Color, ErrorKind, ImageFlags, Paint, Path, Transform2D,
+};
+
+mod common;
+
+#[test]
+fn image_info_reports_size_and_format() {
+    use femtovg::PixelFormat;
+
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let id = canvas
+        .create_image_empty(32, 16, PixelFormat::Gray8, ImageFlags::REPEAT_X)
+        .unwrap();
+
+    assert_eq!(canvas.image_size(id).unwrap(), (32, 16));
+
+    let info = canvas.image_info(id).unwrap();
+    assert_eq!(info.format(), PixelFormat::Gray8);
+    assert_eq!(info.flags(), ImageFlags::REPEAT_X);
+
+    canvas.delete_image(id);
+    assert!(canvas.image_info(id).is_err());
+}
+
+#[test]
+fn create_image_from_padded_rgba() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    // 3x2 image with two bytes of padding per row
+    let data = vec![0u8; 14 * 2];
+
+    let id = canvas
+        .create_image_from_rgba(3, 2, 14, &data, ImageFlags::empty())
+        .unwrap();
+    assert_eq!(canvas.image_size(id).unwrap(), (3, 2));
+
+    assert!(canvas
+        .create_image_from_rgba(3, 2, 14, &data[..20], ImageFlags::empty())
+        .is_err());
+    assert!(canvas
+        .create_image_from_rgba(3, 2, 8, &data, ImageFlags::empty())
+        .is_err());
+}
+
+#[test]
+fn create_image_from_rgba_rejects_bad_sizes() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    // Empty images, including a zero stride that would pass the row size check
+    assert!(canvas
+        .create_image_from_rgba(0, 1, 0, &[], ImageFlags::empty())
+        .is_err());
+    assert!(canvas
+        .create_image_from_rgba(1, 0, 4, &[], ImageFlags::empty())
+        .is_err());
+
+    // Row size and buffer size overflowing usize
+    assert!(canvas
+        .create_image_from_rgba(usize::MAX / 2, 1, usize::MAX, &[0; 16], ImageFlags::empty())
+        .is_err());
+    assert!(canvas
+        .create_image_from_rgba(1, usize::MAX / 2, 8, &[0; 16], ImageFlags::empty())
+        .is_err());
+}
+
+#[test]
+fn blur_image_spreads_pixels() {
+    let mut canvas = common::canvas(9, 9);
+
+    // A single white pixel in the middle of a black image
+    let mut data = vec![0u8; 9 * 9 * 4];
+    data.chunks_mut(4).for_each(|px| px[3] = 255);
+    data[(4 * 9 + 4) * 4..][..3].fill(255);
+    let image = canvas
+        .create_image_from_rgba(9, 9, 9 * 4, &data, ImageFlags::empty())
+        .unwrap();
+
+    let blurred = canvas.blur_image(image, 4.0).unwrap();
+    assert_eq!(canvas.image_size(blurred).unwrap(), (9, 9));
+
+    let screen = common::redraw(&mut canvas, |canvas| {
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 9.0, 9.0);
+        canvas.fill_path(&path, &Paint::image(blurred, 0.0, 0.0, 9.0, 9.0, 0.0, 1.0));
+        canvas.flush();
+    });
+
+    let pixel = |x: usize, y: usize| screen[(x, y)];
+    assert!(pixel(4, 4).r < 255);
+    assert!(pixel(4, 4).r > pixel(3, 4).r);
+    assert!(pixel(3, 4).r > 0 && pixel(4, 6).r > 0);
+    assert_eq!(pixel(3, 4), pixel(5, 4));
+    assert_eq!(pixel(0, 0).a, 255);
+
+    canvas.delete_image(image);
+    assert!(canvas.blur_image(image, 4.0).is_err());
+}
+
+#[test]
+fn nine_slice_keeps_corners() {
+    let mut canvas = common::canvas(9, 6);
+
+    // 3x3 image with a different red value in every pixel
+    let data: Vec<u8> = (0..9u8).flat_map(|i| [i * 20, 0, 0, 255]).collect();
+    let image = canvas
+        .create_image_from_rgba(3, 3, 3 * 4, &data, ImageFlags::NEAREST)
+        .unwrap();
+
+    let screen = common::redraw(&mut canvas, |canvas| {
+        canvas
+            .draw_image_nine_slice(image, 0.0, 0.0, 9.0, 6.0, [1.0, 1.0, 1.0, 1.0])
+            .unwrap();
+    });
+    let red = |x: usize, y: usize| screen[(x, y)].r;
+
+    assert_eq!([red(0, 0), red(8, 0), red(0, 5), red(8, 5)], [0, 40, 120, 160]);
+    assert_eq!([red(1, 0), red(7, 0), red(4, 0)], [20, 20, 20]);
+    assert_eq!([red(0, 1), red(0, 4), red(8, 3)], [60, 60, 100]);
+    assert_eq!([red(1, 1), red(4, 3), red(7, 4)], [80, 80, 80]);
+
+    assert!(canvas
+        .draw_image_nine_slice(image, 0.0, 0.0, 9.0, 6.0, [0.0; 4])
+        .is_ok());
+    canvas.delete_image(image);
+    assert!(canvas
+        .draw_image_nine_slice(image, 0.0, 0.0, 9.0, 6.0, [0.0; 4])
+        .is_err());
+}
+
+#[test]
+fn flipped_images_are_drawn_upside_down() {
+    // Red top row, blue bottom row
+    let data = [255, 0, 0, 255, 255, 0, 0, 255, 0, 0, 255, 255, 0, 0, 255, 255];
+
+    let column = |flags: ImageFlags| {
+        let screen = common::render(20, 20, |canvas| {
+            let image = canvas
+                .create_image_from_rgba(2, 2, 8, &data, flags | ImageFlags::NEAREST)
+                .unwrap();
+
+            let mut path = Path::new();
+            path.rect(0.0, 0.0, 20.0, 20.0);
+            canvas.fill_path(&path, &Paint::image(image, 0.0, 0.0, 10.0, 10.0, 0.0, 1.0));
+        });
+
+        [2, 7, 12, 17].map(|y| {
+            let pixel = common::pixel(&screen, 2, y);
+            if pixel.r > 128 {
+                'r'
+            } else if pixel.b > 128 {
+                'b'
+            } else {
+                '.'
+            }
+        })
+    };
+
+    assert_eq!(column(ImageFlags::REPEAT_Y), ['r', 'b', 'r', 'b']);
+    assert_eq!(column(ImageFlags::FLIP_Y | ImageFlags::REPEAT_Y), ['b', 'r', 'b', 'r']);
+    assert_eq!(
+        column(ImageFlags::FLIP_Y | ImageFlags::REPEAT_X | ImageFlags::REPEAT_Y),
+        ['b', 'r', 'b', 'r']
+    );
+    assert_eq!(column(ImageFlags::FLIP_Y)[..2], ['b', 'r']);
+}
+
+#[test]
+fn draw_image_source_rect_draws_atlas_cell() {
+    // Two 2x2 cells, red on the left and green on the right
+    let red = [255, 0, 0, 255];
+    let green = [0, 255, 0, 255];
+    let data = [red, red, green, green, red, red, green, green].concat();
+
+    let draw = |src: [f32; 4], alpha: f32| {
+        let screen = common::render(20, 20, |canvas| {
+            let image = canvas
+                .create_image_from_rgba(4, 2, 16, &data, ImageFlags::NEAREST)
+                .unwrap();
+            canvas
+                .draw_image_source_rect(image, src, [5.0, 5.0, 10.0, 10.0], alpha)
+                .unwrap();
+        });
+
+        [(6, 6), (14, 14), (2, 2), (17, 10)].map(|(x, y)| common::pixel(&screen, x, y))
+    };
+
+    let [inside, corner, before, after] = draw([2.0, 0.0, 2.0, 2.0], 1.0);
+    assert_eq!((inside.r, inside.g), (0, 255));
+    assert_eq!((corner.r, corner.g), (0, 255));
+    assert_eq!((before.r, before.g), (0, 0));
+    assert_eq!((after.r, after.g), (0, 0));
+
+    // Clamped to the image bounds, which leaves just the right cell
+    let [inside, corner, ..] = draw([2.0, -3.0, 10.0, 10.0], 1.0);
+    assert_eq!((inside.r, inside.g, corner.r, corner.g), (0, 255, 0, 255));
+
+    let [inside, ..] = draw([0.0, 0.0, 2.0, 2.0], 0.5);
+    assert!(inside.r.abs_diff(128) <= 1 && inside.g == 0);
+
+    let [inside, ..] = draw([4.0, 0.0, 2.0, 2.0], 1.0);
+    assert_eq!((inside.r, inside.g), (0, 0));
+
+    let mut canvas = Canvas::new(Void).unwrap();
+    let image = canvas
+        .create_image_from_rgba(4, 2, 16, &data, ImageFlags::empty())
+        .unwrap();
+    canvas.delete_image(image);
+    assert!(canvas.draw_image_source_rect(image, [0.0; 4], [0.0; 4], 1.0).is_err());
+}
+
+#[test]
+#[cfg(feature = "text")]
+fn max_texture_size_limits_images_and_atlases() {
+    use femtovg::{testing, PixelFormat};
+
+    let mut canvas = Canvas::new(Software::new()).unwrap();
+    assert_eq!(canvas.max_texture_size(), None);
+
+    canvas.set_max_texture_size(Some(64));
+    assert_eq!(canvas.max_texture_size(), Some(64));
+    assert!(canvas
+        .create_image_empty(64, 64, PixelFormat::Rgba8, ImageFlags::empty())
+        .is_ok());
+    assert!(matches!(
+        canvas.create_image_empty(65, 8, PixelFormat::Rgba8, ImageFlags::empty()),
+        Err(ErrorKind::TextureAllocationError(_))
+    ));
+
+    // The glyphs don't fit into a single small atlas, so they have to spill into more pages
+    let text = |max_texture_size: Option<usize>| {
+        testing::render_to_image(200, 60, |canvas| {
+            canvas.set_max_texture_size(max_texture_size);
+            let font = canvas.add_font(common::ROBOTO_REGULAR).unwrap();
+            let paint = Paint::color(Color::white()).with_font(&[font]).with_font_size(18.0);
+            canvas.fill_text(2.0, 20.0, "ABCDEFGHIJKLMNOPQRS", &paint).unwrap();
+            canvas.fill_text(2.0, 50.0, "abcdefghijklmnopqrs", &paint).unwrap();
+        })
+    };
+
+    let limited = text(Some(64));
+    assert!(limited.chunks(4).any(|pixel| pixel[0] > 128));
+    assert_eq!(testing::pixel_difference(&limited, &text(None), 0), 0);
+}
+
+#[test]
+fn mask_image_multiplies_alpha() {
+    use rgb::alt::Gray;
+
+    let mut canvas = common::canvas(20, 20);
+
+    // Opaque top right and bottom left quadrants, half transparent bottom right one
+    let gray = [Gray(0), Gray(255), Gray(255), Gray(128)];
+    let mask = canvas
+        .create_image(imgref::Img::new(&gray[..], 2, 2), ImageFlags::NEAREST)
+        .unwrap();
+
+    let mut rect = Path::new();
+    rect.rect(0.0, 0.0, 20.0, 20.0);
+
+    let draw = |canvas: &mut Canvas<Software>| {
+        canvas.clear_rect(0, 0, 20, 20, Color::rgba(0, 0, 0, 0));
+        canvas.fill_path(&rect, &Paint::fill(Color::white()));
+        let screen = canvas.screenshot().unwrap();
+        [(5, 5), (15, 5), (5, 15), (15, 15)].map(|(x, y)| common::pixel(&screen, x, y).a)
+    };
+
+    canvas.save();
+    canvas.scale(10.0, 10.0);
+    canvas.set_mask_image(mask, &Transform2D::identity()).unwrap();
+    canvas.reset_transform();
+    assert_eq!(draw(&mut canvas), [0, 255, 255, 128]);
+
+    canvas.restore();
+    assert_eq!(draw(&mut canvas), [255; 4]);
+
+    // Beyond the mask its edge pixels are repeated
+    canvas
+        .set_mask_image(mask, &Transform2D([10.0, 0.0, 0.0, 10.0, 10.0, 0.0]))
+        .unwrap();
+    assert_eq!(draw(&mut canvas), [0, 0, 255, 255]);
+
+    canvas.clear_mask();
+    assert_eq!(draw(&mut canvas), [255; 4]);
+
+    canvas.delete_image(mask);
+    assert!(matches!(
+        canvas.set_mask_image(mask, &Transform2D::identity()),
+        Err(ErrorKind::ImageIdNotFound)
+    ));
+}
@@ -0,0 +1,139 @@
+//! Renders through the OpenGL backend on a headless EGL device, if the machine has one.
+
+#![cfg(target_os = "linux")]
+
+use femtovg::{
+    renderer::{OpenGl, Void},
+    Canvas, Color, FillRule, ImageFlags, Paint, Path, PixelFormat, Transform2D,
+};
+use glow::HasContext;
+use glutin::{
+    api::egl::{device::Device, display::Display},
+    config::{ConfigSurfaceTypes, ConfigTemplateBuilder},
+    context::{ContextApi, ContextAttributesBuilder, Version},
+    prelude::*,
+};
+use imgref::ImgVec;
+use rgb::RGBA8;
+
+mod common;
+
+/// Draws a scene onto a surfaceless GL context cleared to black and returns what ended up on screen, or
+/// `None` when there is no EGL device to render with. The scene also gets the context to inspect GL state.
+fn render_gl(width: u32, height: u32, draw: impl FnOnce(&mut Canvas<OpenGl>, &glow::Context)) -> Option<ImgVec<RGBA8>> {
+    let Some(device) = Device::query_devices().ok().and_then(|mut devices| devices.next()) else {
+        eprintln!("No EGL device, skipping");
+        return None;
+    };
+
+    let display = unsafe { Display::with_device(&device, None) }.ok()?;
+    let template = ConfigTemplateBuilder::new()
+        .with_surface_type(ConfigSurfaceTypes::empty())
+        .with_stencil_size(8)
+        .build();
+    let config = unsafe { display.find_configs(template) }.ok()?.next()?;
+    let attributes = ContextAttributesBuilder::new()
+        .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 2))))
+        .build(None);
+    let context = unsafe { display.create_context(&config, &attributes) }.ok()?;
+    let _context = context.make_current_surfaceless().ok()?;
+
+    let mut renderer =
+        unsafe { OpenGl::new_from_function_cstr(|name| display.get_proc_address(name) as *const _) }.unwrap();
+    let gl = unsafe { glow::Context::from_loader_function_cstr(|name| display.get_proc_address(name) as *const _) };
+    renderer.set_offscreen_target(width, height).unwrap();
+
+    let mut canvas = Canvas::new(renderer).unwrap();
+    canvas.set_size(width, height, 1.0);
+    canvas.clear_rect(0, 0, width, height, Color::black());
+
+    draw(&mut canvas, &gl);
+
+    Some(canvas.screenshot().unwrap())
+}
+
+fn is_filled(screen: &ImgVec<RGBA8>, x: usize, y: usize) -> bool {
+    common::pixel(screen, x, y).r > 128
+}
+
+#[test]
+fn even_odd_fills_use_the_parity_over_all_contours() {
+    // Pentagram, with a second copy inside the pentagon in the middle to cross it four times
+    let mut star = Path::new();
+    for (scale, dx, dy) in [(1.0, 0.0, 0.0), (0.2, 40.0, 42.0)] {
+        star.move_to(dx + 50.0 * scale, dy);
+        star.line_to(dx + 21.0 * scale, dy + 90.0 * scale);
+        star.line_to(dx + 98.0 * scale, dy + 35.0 * scale);
+        star.line_to(dx + 2.0 * scale, dy + 35.0 * scale);
+        star.line_to(dx + 79.0 * scale, dy + 90.0 * scale);
+        star.close();
+    }
+
+    // Two overlapping rectangles and a third one nested in both
+    let mut rects = Path::new();
+    rects.rect(110.0, 10.0, 60.0, 60.0);
+    rects.rect(130.0, 30.0, 60.0, 60.0);
+    rects.rect(145.0, 45.0, 10.0, 10.0);
+
+    let mut reference = Canvas::new(Void).unwrap();
+    reference.set_size(200, 100, 1.0);
+
+    for fill_rule in [FillRule::EvenOdd, FillRule::NonZero] {
+        let Some(screen) = render_gl(200, 100, |canvas, _| {
+            let paint = Paint::color(Color::white()).with_fill_rule(fill_rule);
+            canvas.fill_path(&star, &paint);
+            canvas.fill_path(&rects, &paint);
+        }) else {
+            return;
+        };
+
+        for (x, y, path) in [
+            (50, 10, &star),
+            (37, 50, &star),
+            (50, 46, &star),
+            (50, 51, &star),
+            (120, 20, &rects),
+            (140, 40, &rects),
+            (150, 50, &rects),
+            (180, 80, &rects),
+        ] {
+            assert_eq!(
+                is_filled(&screen, x, y),
+                reference.contains_point(path, x as f32 + 0.5, y as f32 + 0.5, fill_rule),
+                "{fill_rule:?} at {x}, {y}"
+            );
+        }
+
+        // The pentagon in the middle of the star and the overlap of the rectangles are only covered twice
+        assert_eq!(is_filled(&screen, 37, 50), fill_rule == FillRule::NonZero);
+        assert_eq!(is_filled(&screen, 140, 40), fill_rule == FillRule::NonZero);
+    }
+}
+
+#[test]
+fn flush_unbinds_the_texture_units() {
+    render_gl(16, 16, |canvas, gl| {
+        let image = canvas
+            .create_image_empty(4, 4, PixelFormat::Rgba8, ImageFlags::empty())
+            .unwrap();
+        let mask = canvas
+            .create_image_empty(4, 4, PixelFormat::Rgba8, ImageFlags::empty())
+            .unwrap();
+
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 16.0, 16.0);
+
+        canvas.set_mask_image(mask, &Transform2D::identity()).unwrap();
+        canvas.fill_path(&path, &Paint::image(image, 0.0, 0.0, 4.0, 4.0, 0.0, 1.0));
+        canvas.flush();
+
+        unsafe {
+            assert_eq!(gl.get_parameter_i32(glow::ACTIVE_TEXTURE), glow::TEXTURE0 as i32);
+
+            for unit in 0..3 {
+                gl.active_texture(glow::TEXTURE0 + unit);
+                assert_eq!(gl.get_parameter_i32(glow::TEXTURE_BINDING_2D), 0, "texture unit {unit}");
+            }
+        }
+    });
+}
@@ -0,0 +1,244 @@
+use femtovg::{Color, Paint, Path};
+
+mod common;
+
+#[test]
+fn color_lerp() {
+    let red = Color::rgbaf(1.0, 0.0, 0.0, 1.0);
+    let green = Color::rgbaf(0.0, 1.0, 0.0, 0.0);
+
+    assert_eq!(red.lerp(&green, 0.0), red);
+    assert_eq!(red.lerp(&green, 1.0), green);
+    assert_eq!(red.lerp(&green, 0.25), Color::rgbaf(0.75, 0.25, 0.0, 0.75));
+    assert_eq!(red.lerp(&green, 2.0), green);
+
+    // Halfway in linear light is brighter than halfway in sRGB space
+    let mid = red.lerp_linear(&green, 0.5);
+    assert!((mid.r - 0.735).abs() < 1e-3 && (mid.g - 0.735).abs() < 1e-3);
+    assert_eq!(mid.a, 0.5);
+    assert!((red.lerp_linear(&green, 1.0).g - 1.0).abs() < 1e-5);
+}
+
+#[test]
+fn color_from_name() {
+    assert_eq!(Color::from_name("rebeccapurple"), Some(Color::rgb(0x66, 0x33, 0x99)));
+    assert_eq!(Color::from_name("AliceBlue"), Some(Color::hex("f0f8ff")));
+    assert_eq!(Color::from_name("YELLOWGREEN"), Some(Color::hex("9acd32")));
+    assert_eq!(Color::from_name("grey"), Color::from_name("gray"));
+    assert_eq!(Color::from_name("transparent"), Some(Color::rgbaf(0.0, 0.0, 0.0, 0.0)));
+    assert_eq!(Color::from_name("notacolor"), None);
+    assert_eq!(Color::from_name(""), None);
+}
+
+#[test]
+fn colors_can_be_used_as_map_keys() {
+    use std::collections::HashMap;
+
+    let mut cache = HashMap::new();
+    cache.insert(Color::rgb(255, 0, 0), 1);
+    cache.insert(Color::rgbaf(0.0, 0.0, 0.0, 1.0), 2);
+
+    assert_eq!(cache.get(&Color::rgbf(1.0, 0.0, 0.0)), Some(&1));
+    assert_eq!(cache.get(&Color::rgbaf(-0.0, 0.0, -0.0, 1.0)), Some(&2));
+    assert_eq!(cache.get(&Color::rgb(254, 0, 0)), None);
+
+    let nan = Color::rgbaf(f32::NAN, 0.0, 0.0, 1.0);
+    assert_eq!(nan, nan);
+    cache.insert(nan, 3);
+    assert_eq!(cache.get(&Color::rgbaf(-f32::NAN, 0.0, 0.0, 1.0)), Some(&3));
+}
+
+#[test]
+fn color_order_agrees_with_equality() {
+    use std::cmp::Ordering;
+
+    let nan = Color::rgbaf(f32::NAN, 0.0, 0.0, 1.0);
+    assert_eq!(
+        nan.partial_cmp(&Color::rgbaf(-f32::NAN, 0.0, 0.0, 1.0)),
+        Some(Ordering::Equal)
+    );
+    assert_eq!(nan.partial_cmp(&Color::rgbf(1.0, 0.0, 0.0)), Some(Ordering::Greater));
+    assert_eq!(
+        Color::rgbaf(-0.0, 0.0, 0.0, 1.0).partial_cmp(&Color::black()),
+        Some(Ordering::Equal)
+    );
+    assert!(Color::rgbf(0.5, 1.0, 1.0) < Color::rgbf(1.0, 0.0, 0.0));
+    assert!(Color::rgbaf(0.0, 0.0, 0.0, 0.5) < Color::black());
+}
+
+#[test]
+fn gradient_stops_are_sorted_and_padded() {
+    let mut canvas = common::canvas(100, 1);
+
+    let red = Color::rgb(255, 0, 0);
+    let green = Color::rgb(0, 255, 0);
+    let blue = Color::rgb(0, 0, 255);
+
+    let mut render = |stops: &[(f32, Color)]| {
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 100.0, 1.0);
+
+        let screen = common::redraw(&mut canvas, |canvas| {
+            canvas.fill_path(
+                &path,
+                &Paint::linear_gradient_stops(0.0, 0.0, 100.0, 0.0, stops.iter().copied()),
+            );
+            canvas.flush();
+        });
+        screen
+            .pixels()
+            .map(|pixel| (pixel.r, pixel.g, pixel.b))
+            .collect::<Vec<_>>()
+    };
+
+    let sorted = render(&[(0.0, red), (0.5, green), (1.0, blue)]);
+    assert_eq!(render(&[(1.0, blue), (0.0, red), (0.5, green)]), sorted);
+
+    // Duplicate offsets form a hard edge in the given order
+    let hard = render(&[(0.0, red), (0.5, red), (0.5, blue), (1.0, blue)]);
+    assert_eq!(hard[45], (255, 0, 0));
+    assert_eq!(hard[55], (0, 0, 255));
+
+    // The last color extends to the end
+    let padded = render(&[(0.0, red), (0.2, green), (0.6, blue)]);
+    assert_eq!(padded[90], (0, 0, 255));
+}
+
+#[test]
+fn conic_gradient_follows_angle() {
+    let red = Color::rgb(255, 0, 0);
+    let blue = Color::rgb(0, 0, 255);
+
+    // Starting at the top, the two halves are red to the right and blue to the left
+    let paint = Paint::conic_gradient(
+        10.5,
+        10.5,
+        -std::f32::consts::FRAC_PI_2,
+        [(0.0, red), (0.5, red), (0.5, blue), (1.0, blue)],
+    );
+
+    let screen = common::render(21, 21, |canvas| {
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 21.0, 21.0);
+        canvas.fill_path(&path, &paint);
+        canvas.flush();
+    });
+    let pixel = |x: usize, y: usize| {
+        let pixel = screen[(x, y)];
+        (pixel.r, pixel.g, pixel.b)
+    };
+
+    assert_eq!(pixel(18, 3), (255, 0, 0));
+    assert_eq!(pixel(18, 18), (255, 0, 0));
+    assert_eq!(pixel(2, 18), (0, 0, 255));
+    assert_eq!(pixel(2, 3), (0, 0, 255));
+}
+
+#[test]
+fn conic_gradient_wraps_around_seamlessly() {
+    let red = Color::rgb(255, 0, 0);
+    let blue = Color::rgb(0, 0, 255);
+
+    // Blue in the middle of the turn blends back to red towards the start angle
+    let paint = Paint::conic_gradient(10.5, 10.5, 0.0, [(0.0, red), (0.5, blue)]);
+
+    let screen = common::render(21, 21, |canvas| {
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, 21.0, 21.0);
+        canvas.fill_path(&path, &paint);
+        canvas.flush();
+    });
+
+    // Just before and after the start angle
+    for (x, y) in [(20, 9), (20, 11)] {
+        let pixel = common::pixel(&screen, x, y);
+        assert!(pixel.r > 200 && pixel.b < 50, "{pixel:?} at {x}, {y}");
+    }
+
+    let pixel = common::pixel(&screen, 0, 10);
+    assert!(pixel.r < 50 && pixel.b > 200, "{pixel:?}");
+}
+
+#[test]
+fn paint_transform_maps_paint_space_to_user_space() {
+    use femtovg::Transform2D;
+
+    let black = Color::rgb(0, 0, 0);
+    let white = Color::rgb(255, 255, 255);
+
+    // A horizontal hard edge at x = 10, rotated by a quarter turn around (10, 10) becomes a vertical one
+    let mut rotation = Transform2D::identity();
+    rotation.rotate(std::f32::consts::FRAC_PI_2);
+
+    let mut transform = Transform2D::new_translation(-10.0, -10.0);
+    transform.multiply(&rotation);
+    transform.multiply(&Transform2D::new_translation(10.0, 10.0));
+
+    let paint =
+        Paint::linear_gradient_stops(0.0, 0.0, 20.0, 0.0, [(0.5, black), (0.5, white)]).with_transform(transform);
+    assert_eq!(paint.transform(), transform);
+
+    let mut canvas = common::canvas(20, 20);
+    canvas.clear(Color::rgb(255, 0, 0));
+
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 20.0, 20.0);
+    canvas.fill_path(&path, &paint);
+    canvas.flush();
+
+    let screen = canvas.screenshot().unwrap();
+    let red = |x: usize, y: usize| screen[(x, y)].r;
+
+    assert_eq!([red(3, 3), red(16, 3)], [0, 0]);
+    assert_eq!([red(3, 16), red(16, 16)], [255, 255]);
+    assert_eq!(common::pixel(&screen, 3, 16).g, 255);
+
+    // Solid colors ignore the transform
+    assert_eq!(
+        Paint::color(white).with_transform(transform).transform(),
+        Transform2D::identity()
+    );
+}
+
+#[test]
+fn paint_with_alpha_fades_colors_and_gradients() {
+    let white = Color::rgb(255, 255, 255);
+
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 10.0, 2.0);
+
+    let render = |paint: &Paint| {
+        let screen = common::render(10, 2, |canvas| {
+            canvas.fill_path(&path, paint);
+            canvas.flush();
+        });
+        common::pixel(&screen, 5, 1).r
+    };
+
+    let solid = render(&Paint::color(white).with_alpha(0.5));
+    assert!((126..=129).contains(&solid), "{solid}");
+
+    let gradient = Paint::linear_gradient_stops(0.0, 0.0, 10.0, 0.0, [(0.0, white), (0.3, white), (1.0, white)]);
+    let faded = render(&gradient.with_alpha(0.5));
+    assert!((126..=129).contains(&faded), "{faded}");
+}
+
+#[test]
+fn fill_and_stroke_paint_constructors() {
+    let fill = Paint::fill(Color::rgb(10, 20, 30));
+    let stroke = Paint::stroke(Color::rgb(10, 20, 30), 3.5);
+
+    assert_eq!(fill.line_width(), Paint::color(Color::black()).line_width());
+    assert_eq!(stroke.line_width(), 3.5);
+    assert!(fill.anti_alias() && stroke.anti_alias());
+
+    let screen = common::render(20, 20, |canvas| {
+        let mut path = Path::new();
+        path.move_to(2.0, 10.0);
+        path.line_to(18.0, 10.0);
+        canvas.stroke_path(&path, &Paint::stroke(Color::white(), 6.0).with_anti_alias(false));
+    });
+
+    let lit = |y: usize| screen[(10, y)].r > 128;
+    assert!(lit(8) && lit(12) && !lit(5) && !lit(14));
+}
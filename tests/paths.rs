@@ -0,0 +1,611 @@
+use femtovg::{renderer::Void, Canvas, Color, ErrorKind, FillRule, Paint, Path, Solidity, Transform2D, Verb};
+
+mod common;
+
+#[test]
+fn path_with_single_move_to() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let mut path = Path::new();
+    path.move_to(10.0, 10.0);
+    canvas.fill_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+    canvas.stroke_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+}
+
+#[test]
+fn path_with_two_lines() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let mut path = Path::new();
+    path.line_to(10.0, 10.0);
+    path.line_to(10.0, 10.0);
+    canvas.fill_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+    canvas.stroke_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+}
+
+#[test]
+fn path_with_close_points() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let mut path = Path::new();
+    path.move_to(10.0, 10.0);
+    path.line_to(10.0001, 10.0);
+    path.line_to(10.0001, 10.000001);
+    canvas.fill_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+    canvas.stroke_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+}
+
+#[test]
+fn path_with_points_at_limits() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let mut path = Path::new();
+    path.move_to(10.0, 10.0);
+    path.line_to(std::f32::MAX, std::f32::MAX);
+    path.quad_to(10.0, 10.0, -std::f32::MAX, std::f32::MAX);
+    path.bezier_to(10.0, 10.0, std::f32::MAX, 5000.0, -std::f32::MAX, -std::f32::MAX);
+    path.rounded_rect_varying(
+        -std::f32::MAX,
+        -std::f32::MAX,
+        std::f32::MAX,
+        std::f32::MAX,
+        std::f32::MAX,
+        std::f32::MAX,
+        std::f32::MAX,
+        std::f32::MAX,
+    );
+    path.close();
+
+    canvas.fill_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+    canvas.stroke_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+}
+
+#[test]
+fn path_with_points_around_zero() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let mut path = Path::new();
+    path.move_to(0.0, 0.0);
+    path.line_to(0.0, 0.0);
+    path.line_to(0.0001, 0.0000003);
+    path.quad_to(0.002, 0.0001, -0.002, 0.0001);
+    path.bezier_to(0.0001, 0.002, -0.002, 0.0001, -0.002, 0.0001);
+    path.rounded_rect_varying(
+        -std::f32::MAX,
+        -std::f32::MAX,
+        std::f32::MAX,
+        std::f32::MAX,
+        std::f32::MAX,
+        0.0001,
+        0.0001,
+        0.0001,
+    );
+
+    path.close();
+
+    canvas.fill_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+    canvas.stroke_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+}
+
+#[test]
+fn degenerate_stroke() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let mut path = Path::new();
+    path.move_to(0.5, 0.5);
+    path.line_to(2., 2.);
+    path.line_to(2., 2.);
+    path.line_to(4., 2.);
+    canvas.stroke_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+}
+
+#[test]
+fn degenerate_arc_to() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let mut path = Path::new();
+    path.move_to(10.0, 10.0);
+    path.arc_to(10.0, 10.0001, 10.0, 10.0001, 2.0);
+    canvas.fill_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+    canvas.stroke_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+}
+
+#[test]
+fn degenerate_arc() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    let mut path = Path::new();
+    path.move_to(10.0, 10.0);
+    path.arc(10.0, 10.0, 10.0, 0.0, std::f32::MAX, Solidity::Hole);
+
+    canvas.fill_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+    canvas.stroke_path(&path, &Paint::color(Color::rgb(100, 100, 100)));
+}
+
+#[test]
+fn arc_direction_and_connecting_line() {
+    let end = |path: &Path| match path.verbs().last() {
+        Some(Verb::BezierTo(_, _, _, _, x, y)) => (x, y),
+        _ => panic!("arc should end with a bezier"),
+    };
+    let near = |(x, y): (f32, f32), (ex, ey): (f32, f32)| (x - ex).abs() < 1e-3 && (y - ey).abs() < 1e-3;
+
+    let mut path = Path::new();
+    path.move_to(0.0, 0.0);
+    path.arc(20.0, 10.0, 10.0, 0.0, std::f32::consts::FRAC_PI_2, Solidity::Hole);
+
+    let verbs: Vec<_> = path.verbs().collect();
+    assert!(matches!(verbs[1], Verb::LineTo(x, y) if near((x, y), (30.0, 10.0))));
+    assert!(near(end(&path), (20.0, 20.0)));
+
+    // Sweeping the other way around ends at the same angle after three quarters of a turn
+    let mut path = Path::new();
+    path.arc(20.0, 10.0, 10.0, 0.0, std::f32::consts::FRAC_PI_2, Solidity::Solid);
+    assert!(matches!(path.verbs().next(), Some(Verb::MoveTo(..))));
+    assert_eq!(
+        path.verbs().filter(|verb| matches!(verb, Verb::BezierTo(..))).count(),
+        3
+    );
+    assert!(near(end(&path), (20.0, 20.0)));
+}
+
+#[test]
+fn sector_and_annular_sector_fill_slices() {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    let screen = common::render(40, 20, |canvas| {
+        let mut path = Path::new();
+        path.sector(10.0, 10.0, 9.0, 0.0, FRAC_PI_2);
+        path.annular_sector(30.0, 10.0, 4.0, 9.0, PI, -FRAC_PI_2);
+        canvas.fill_path(&path, &Paint::color(Color::white()));
+    });
+    let lit = |x: usize, y: usize| screen[(x, y)].r > 128;
+
+    // Clockwise quarter from the positive x axis covers the bottom right quadrant
+    assert!(lit(14, 14) && lit(11, 11));
+    assert!(!lit(6, 14) && !lit(14, 6) && !lit(6, 6));
+
+    // Counter-clockwise quarter from the negative x axis covers the bottom left quadrant, minus the hole
+    assert!(lit(25, 15));
+    assert!(!lit(29, 11) && !lit(35, 15) && !lit(25, 5));
+}
+
+#[test]
+fn polyline_connects_points() {
+    let points = [(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)];
+
+    let mut path = Path::new();
+    path.polyline(&points, false);
+
+    let verbs: Vec<_> = path.verbs().collect();
+    assert_eq!(verbs.len(), 3);
+    assert!(matches!(verbs[0], Verb::MoveTo(x, y) if (x, y) == points[0]));
+    assert!(matches!(verbs[2], Verb::LineTo(x, y) if (x, y) == points[2]));
+
+    let mut path = Path::new();
+    path.polyline(&points, true);
+    assert!(matches!(path.verbs().last(), Some(Verb::Close)));
+
+    let mut path = Path::new();
+    path.polyline(&[], true);
+    assert!(path.is_empty());
+}
+
+#[test]
+fn catmull_rom_passes_through_points() {
+    let points = [(0.0, 0.0), (10.0, 10.0), (20.0, 0.0), (30.0, 10.0)];
+
+    let mut path = Path::new();
+    path.catmull_rom(&points, 1.0);
+
+    let verbs: Vec<_> = path.verbs().collect();
+    assert_eq!(verbs.len(), 4);
+    assert!(matches!(verbs[0], Verb::MoveTo(x, y) if (x, y) == points[0]));
+
+    for (verb, &point) in verbs[1..].iter().zip(&points[1..]) {
+        assert!(matches!(*verb, Verb::BezierTo(_, _, _, _, x, y) if (x, y) == point));
+    }
+
+    // The tangent at an inner point runs parallel to the line between its neighbours
+    if let (Verb::BezierTo(_, _, c2x, c2y, ..), Verb::BezierTo(c1x, c1y, ..)) = (verbs[1], verbs[2]) {
+        assert!((c2y - 10.0).abs() < 1e-4 && (c1y - 10.0).abs() < 1e-4);
+        assert!(c2x < 10.0 && c1x > 10.0);
+    }
+
+    let mut path = Path::new();
+    path.catmull_rom(&points[..2], 1.0);
+    assert!(matches!(path.verbs().last(), Some(Verb::LineTo(..))));
+
+    let mut path = Path::new();
+    path.catmull_rom(&[], 1.0);
+    assert!(path.is_empty());
+}
+
+#[test]
+fn dashed_path_follows_pattern_and_offset() {
+    let dashes = |path: &Path| {
+        let mut dashes = Vec::new();
+        for verb in path.verbs() {
+            match verb {
+                Verb::MoveTo(x, _) => dashes.push((x, x)),
+                Verb::LineTo(x, _) => dashes.last_mut().unwrap().1 = x,
+                _ => {}
+            }
+        }
+        dashes
+    };
+
+    let mut path = Path::new();
+    path.move_to(0.0, 0.0);
+    path.line_to(20.0, 0.0);
+
+    assert_eq!(dashes(&path.dashed(&[5.0, 5.0], 0.0, 0.25)), [(0.0, 5.0), (10.0, 15.0)]);
+    assert_eq!(
+        dashes(&path.dashed(&[5.0, 5.0], 2.0, 0.25)),
+        [(0.0, 3.0), (8.0, 13.0), (18.0, 20.0)]
+    );
+    assert_eq!(
+        dashes(&path.dashed(&[5.0, 5.0], -3.0, 0.25)),
+        [(3.0, 8.0), (13.0, 18.0)]
+    );
+
+    let paint = Paint::color(Color::white()).with_line_dash(&[4.0, 1.0, 2.0]);
+    assert_eq!(paint.line_dash(), [4.0, 1.0, 2.0, 4.0, 1.0, 2.0]);
+    assert!(paint.with_line_dash(&[1.0, -1.0]).line_dash().is_empty());
+}
+
+#[test]
+fn path_contains_point() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    // without setting size contains_point will early out on the bounds check and report false
+    canvas.set_size(100, 100, 1.0);
+
+    // Star - cancave & self crossing
+    let mut path = Path::new();
+    path.move_to(50.0, 0.0);
+    path.line_to(21.0, 90.0);
+    path.line_to(98.0, 35.0);
+    path.line_to(2.0, 35.0);
+    path.line_to(79.0, 90.0);
+    path.close();
+
+    // Center of the star should be hollow for even-odd rule
+    assert!(!canvas.contains_point(&path, 50.0, 45.0, FillRule::EvenOdd));
+    assert!(canvas.contains_point(&path, 50.0, 5.0, FillRule::EvenOdd));
+
+    // Center of the star should be fill for NonZero rule
+    assert!(canvas.contains_point(&path, 50.0, 45.0, FillRule::NonZero));
+    assert!(canvas.contains_point(&path, 50.0, 5.0, FillRule::NonZero));
+}
+
+#[test]
+fn hit_test_reports_subpath() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 10.0, 10.0);
+    // A degenerate sub-path doesn't shift the indices of the following ones
+    path.move_to(20.0, 20.0);
+    path.rect(40.0, 40.0, 40.0, 40.0);
+    path.circle(60.0, 60.0, 10.0);
+    path.solidity(Solidity::Hole);
+    path.circle(60.0, 60.0, 5.0);
+
+    assert_eq!(canvas.hit_test_subpath(&path, 5.0, 5.0, FillRule::NonZero), Some(0));
+    assert_eq!(canvas.hit_test_subpath(&path, 45.0, 45.0, FillRule::NonZero), Some(2));
+    assert_eq!(canvas.hit_test_subpath(&path, 60.0, 52.0, FillRule::NonZero), None);
+    assert_eq!(canvas.hit_test_subpath(&path, 60.0, 60.0, FillRule::NonZero), Some(4));
+    assert_eq!(canvas.hit_test_subpath(&path, 30.0, 30.0, FillRule::NonZero), None);
+
+    canvas.translate(10.0, 0.0);
+    assert_eq!(canvas.hit_test_subpath(&path, 15.0, 5.0, FillRule::EvenOdd), Some(0));
+}
+
+#[test]
+fn stroke_outline_covers_stroke() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut path = Path::new();
+    path.move_to(10.0, 50.0);
+    path.line_to(90.0, 50.0);
+
+    for cap in [
+        femtovg::LineCap::Butt,
+        femtovg::LineCap::Round,
+        femtovg::LineCap::Square,
+    ] {
+        let paint = Paint::color(Color::black()).with_line_width(10.0).with_line_cap(cap);
+        let outline = path.stroke_outline(&paint, 0.25);
+
+        assert!(canvas.contains_point(&outline, 50.0, 54.0, FillRule::NonZero));
+        assert!(!canvas.contains_point(&outline, 50.0, 56.0, FillRule::NonZero));
+        assert_eq!(
+            canvas.contains_point(&outline, 7.0, 50.0, FillRule::NonZero),
+            cap != femtovg::LineCap::Butt
+        );
+    }
+
+    // Round caps get more segments with a finer tolerance
+    let round = Paint::color(Color::black())
+        .with_line_width(10.0)
+        .with_line_cap(femtovg::LineCap::Round);
+    assert!(path.stroke_outline(&round, 0.01).verbs().count() > path.stroke_outline(&round, 1.0).verbs().count());
+
+    // Closed contours produce a ring with a hole in the middle
+    let mut path = Path::new();
+    path.rect(20.0, 20.0, 60.0, 60.0);
+    let outline = path.stroke_outline(&Paint::color(Color::black()).with_line_width(4.0), 0.25);
+
+    assert!(canvas.contains_point(&outline, 21.0, 50.0, FillRule::NonZero));
+    assert!(!canvas.contains_point(&outline, 50.0, 50.0, FillRule::NonZero));
+}
+
+#[test]
+fn simplify_removes_collinear_points() {
+    let mut path = Path::new();
+    path.move_to(0.0, 0.0);
+    for i in 1..=10 {
+        path.line_to(i as f32 * 10.0, 0.0);
+    }
+    path.line_to(100.0, 50.0);
+    // A flat curve is treated like a line
+    path.bezier_to(100.0, 60.0, 100.0, 70.0, 100.0, 100.0);
+    path.close();
+
+    let simplified = path.simplify(0.1);
+    let verbs: Vec<_> = simplified.verbs().collect();
+
+    assert_eq!(verbs.len(), 4);
+    assert!(matches!(verbs[0], Verb::MoveTo(x, y) if x == 0.0 && y == 0.0));
+    assert!(matches!(verbs[1], Verb::LineTo(x, y) if x == 100.0 && y == 0.0));
+    assert!(matches!(verbs[2], Verb::LineTo(x, y) if x == 100.0 && y == 100.0));
+    assert!(matches!(verbs[3], Verb::Close));
+}
+
+#[test]
+fn tessellate_returns_triangle_lists() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut path = Path::new();
+    path.rect(10.0, 10.0, 20.0, 20.0);
+
+    let fill = canvas.tessellate_fill(&path, FillRule::NonZero);
+    assert_eq!(fill.len() % 3, 0);
+    assert!(fill
+        .iter()
+        .all(|v| (9.0..=31.0).contains(&v.x) && (9.0..=31.0).contains(&v.y)));
+
+    // Four corners as a fan give two interior triangles, the fringe comes after them
+    let interior = fill.chunks(3).take_while(|t| t.iter().all(|v| v.u == 0.5)).count();
+    assert_eq!(interior, 2);
+    assert!(fill.len() > 6);
+
+    let stroke = canvas.tessellate_stroke(&path, &Paint::color(Color::black()).with_line_width(2.0));
+    assert!(!stroke.is_empty());
+    assert_eq!(stroke.len() % 3, 0);
+}
+
+#[test]
+fn tessellate_fill_applies_fill_rule() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    // Two overlapping squares wound the same way
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 20.0, 20.0);
+    path.rect(10.0, 10.0, 20.0, 20.0);
+
+    // A thin fringe barely moves the interior edges
+    canvas.set_antialias_feather(0.01);
+
+    let area = |fill_rule| {
+        canvas
+            .tessellate_fill(&path, fill_rule)
+            .chunks(3)
+            .filter(|t| t.iter().all(|v| v.u == 0.5))
+            .map(|t| ((t[1].x - t[0].x) * (t[2].y - t[0].y) - (t[2].x - t[0].x) * (t[1].y - t[0].y)).abs() * 0.5)
+            .sum::<f32>()
+    };
+
+    // Non-zero covers the overlap once, even-odd leaves it empty
+    assert!((area(FillRule::NonZero) - 700.0).abs() < 1.0);
+    assert!((area(FillRule::EvenOdd) - 600.0).abs() < 1.0);
+}
+
+#[test]
+fn appended_paths_fill_together() {
+    let canvas = Canvas::new(Void).unwrap();
+
+    let mut square = Path::new();
+    square.rect(0.0, 0.0, 10.0, 10.0);
+
+    let mut combined = Path::new();
+    combined.circle(50.0, 50.0, 5.0);
+    assert!(!canvas.contains_point(&combined, 5.0, 5.0, FillRule::NonZero));
+    let circle_verbs = combined.verbs().count();
+
+    combined.append(&square);
+    assert_eq!(combined.verbs().count(), circle_verbs + square.verbs().count());
+    assert!(canvas.contains_point(&combined, 5.0, 5.0, FillRule::NonZero));
+    assert!(canvas.contains_point(&combined, 50.0, 50.0, FillRule::NonZero));
+
+    let mut transform = Transform2D::identity();
+    transform.translate(100.0, 0.0);
+    combined.append_transformed(&square, &transform);
+    assert!(canvas.contains_point(&combined, 105.0, 5.0, FillRule::NonZero));
+    assert!(!canvas.contains_point(&combined, 95.0, 5.0, FillRule::NonZero));
+}
+
+#[test]
+fn cleared_path_can_be_reused() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut path = Path::new();
+    path.rect(0.0, 0.0, 10.0, 10.0);
+    assert!(canvas.contains_point(&path, 5.0, 5.0, FillRule::NonZero));
+    let size = path.size();
+
+    path.clear();
+    assert!(path.is_empty());
+    assert_eq!(path.size(), 0);
+    assert!(!canvas.contains_point(&path, 5.0, 5.0, FillRule::NonZero));
+
+    path.rect(20.0, 0.0, 10.0, 10.0);
+    assert_eq!(path.size(), size);
+    assert!(!canvas.contains_point(&path, 5.0, 5.0, FillRule::NonZero));
+    assert!(canvas.contains_point(&path, 25.0, 5.0, FillRule::NonZero));
+}
+
+#[test]
+fn path_with_capacity_behaves_like_new_path() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut path = Path::with_capacity(16);
+    assert!(path.is_empty());
+    assert_eq!(path.size(), 0);
+
+    path.rect(10.0, 10.0, 10.0, 10.0);
+    let mut reference = Path::new();
+    reference.rect(10.0, 10.0, 10.0, 10.0);
+
+    assert_eq!(path.size(), reference.size());
+    assert_eq!(canvas.path_bbox(&path), canvas.path_bbox(&reference));
+}
+
+#[test]
+fn reversed_path_flips_winding() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut path = Path::new();
+    path.move_to(0.0, 0.0);
+    path.line_to(10.0, 0.0);
+    path.bezier_to(15.0, 0.0, 15.0, 10.0, 10.0, 10.0);
+    path.line_to(0.0, 10.0);
+    path.close();
+    path.circle(50.0, 50.0, 10.0);
+    path.solidity(Solidity::Hole);
+
+    assert_eq!(path.is_clockwise(0, 0.25), Some(true));
+    let circle_clockwise = path.is_clockwise(1, 0.25).unwrap();
+    assert_eq!(path.is_clockwise(2, 0.25), None);
+
+    let verbs = |path: &Path| format!("{:?}", path.verbs().collect::<Vec<_>>());
+    let original = path.clone();
+
+    path.reverse();
+    assert_eq!(path.is_clockwise(0, 0.25), Some(false));
+    assert_eq!(path.is_clockwise(1, 0.25), Some(!circle_clockwise));
+    assert_eq!(
+        format!("{:?}", path.verbs().take(3).collect::<Vec<_>>()),
+        "[MoveTo(0.0, 10.0), LineTo(10.0, 10.0), BezierTo(15.0, 10.0, 15.0, 0.0, 10.0, 0.0)]"
+    );
+    assert_ne!(verbs(&path), verbs(&original));
+
+    // Filling doesn't depend on the direction
+    for (x, y) in [(5.0, 5.0), (12.0, 5.0), (50.0, 50.0), (42.0, 50.0)] {
+        assert_eq!(
+            canvas.contains_point(&path, x, y, FillRule::NonZero),
+            canvas.contains_point(&original, x, y, FillRule::NonZero)
+        );
+    }
+
+    path.reverse();
+    assert_eq!(verbs(&path), verbs(&original));
+}
+
+#[test]
+fn point_at_length_walks_along_path() {
+    let mut path = Path::new();
+    path.move_to(0.0, 0.0);
+    path.line_to(10.0, 0.0);
+    path.line_to(10.0, 20.0);
+    path.move_to(50.0, 50.0);
+    path.line_to(50.0, 40.0);
+
+    assert!((path.total_length(0.25) - 40.0).abs() < 1e-4);
+
+    let (x, y, angle) = path.point_at_length(5.0, 0.25).unwrap();
+    assert!((x - 5.0).abs() < 1e-4 && y.abs() < 1e-4 && angle.abs() < 1e-4);
+
+    let (x, y, angle) = path.point_at_length(20.0, 0.25).unwrap();
+    assert!((x - 10.0).abs() < 1e-4 && (y - 10.0).abs() < 1e-4);
+    assert!((angle - std::f32::consts::FRAC_PI_2).abs() < 1e-4);
+
+    // The jump to the second sub-path doesn't count
+    let (x, y, angle) = path.point_at_length(35.0, 0.25).unwrap();
+    assert!((x - 50.0).abs() < 1e-4 && (y - 45.0).abs() < 1e-4);
+    assert!((angle + std::f32::consts::FRAC_PI_2).abs() < 1e-4);
+
+    assert!(path.point_at_length(-1.0, 0.25).is_none());
+    assert!(path.point_at_length(41.0, 0.25).is_none());
+
+    let mut circle = Path::new();
+    circle.circle(0.0, 0.0, 10.0);
+    assert!((circle.total_length(0.25) - 20.0 * std::f32::consts::PI).abs() < 0.5);
+    // Coarser flattening cuts the corners
+    assert!(circle.total_length(2.0) < circle.total_length(0.01));
+}
+
+#[test]
+fn arrow_head_points_along_direction() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(100, 100, 1.0);
+
+    let mut line = Path::new();
+    line.move_to(10.0, 50.0);
+    line.line_to(90.0, 50.0);
+
+    let (x, y, angle) = line.point_at_length(line.total_length(0.25), 0.25).unwrap();
+    let mut head = Path::new();
+    head.arrow_head(x, y, angle, 10.0);
+
+    let bounds = canvas.path_bbox(&head);
+    assert!((bounds.minx - 80.0).abs() < 1e-4 && (bounds.maxx - 90.0).abs() < 1e-4);
+    assert!((bounds.miny - 45.0).abs() < 1e-4 && (bounds.maxy - 55.0).abs() < 1e-4);
+    assert!(canvas.contains_point(&head, 85.0, 50.0, FillRule::NonZero));
+    assert!(!canvas.contains_point(&head, 89.0, 54.0, FillRule::NonZero));
+
+    let mut up = Path::new();
+    up.arrow_head(50.0, 10.0, -std::f32::consts::FRAC_PI_2, 10.0);
+    let bounds = canvas.path_bbox(&up);
+    assert!((bounds.miny - 10.0).abs() < 1e-4 && (bounds.maxy - 20.0).abs() < 1e-4);
+}
+
+#[test]
+fn path_bytes_round_trip() {
+    let mut path = Path::new();
+    path.rounded_rect(1.5, 2.25, 40.0, 30.0, 4.0);
+    path.circle(20.0, 18.0, 1.0 / 3.0);
+    path.solidity(Solidity::Hole);
+    path.move_to(-0.0, f32::MAX);
+    path.line_to(f32::MIN_POSITIVE, 7.0);
+
+    let bytes = path.to_bytes();
+    let decoded = Path::from_bytes(&bytes).unwrap();
+
+    assert_eq!(
+        format!("{:?}", decoded.verbs().collect::<Vec<_>>()),
+        format!("{:?}", path.verbs().collect::<Vec<_>>())
+    );
+    assert_eq!(decoded.to_bytes(), bytes);
+    assert!(Path::from_bytes(&Path::new().to_bytes()).unwrap().is_empty());
+
+    let invalid = |bytes: &[u8]| matches!(Path::from_bytes(bytes), Err(ErrorKind::InvalidPathData));
+    assert!(invalid(&bytes[..bytes.len() - 1]));
+    assert!(invalid(&[bytes.as_slice(), &[0]].concat()));
+    assert!(invalid(b"not a path"));
+
+    let mut future_version = bytes.clone();
+    future_version[4] += 1;
+    assert!(invalid(&future_version));
+
+    let mut bad_verb = bytes;
+    bad_verb[9] = 42;
+    assert!(invalid(&bad_verb));
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "svg")]
+
+use femtovg::render_svg_tree;
+use rgb::RGBA8;
+use usvg::TreeParsing;
+
+mod common;
+use common::{pixel, render};
+
+fn parse(svg: &str) -> usvg::Tree {
+    usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap()
+}
+
+#[test]
+fn svg_bounds_include_stroke_width() {
+    let tree = parse(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40">
+            <rect x="10" y="10" width="20" height="20" fill="#ff0000" stroke="#0000ff" stroke-width="4"/>
+        </svg>"##,
+    );
+
+    let mut bounds = None;
+    let image = render(40, 40, |canvas| bounds = Some(render_svg_tree(canvas, &tree)));
+    let bounds = bounds.unwrap();
+
+    assert_eq!(pixel(&image, 20, 20), RGBA8::new(255, 0, 0, 255));
+    assert_eq!(pixel(&image, 10, 20), RGBA8::new(0, 0, 255, 255));
+    assert_eq!(pixel(&image, 4, 4), RGBA8::new(0, 0, 0, 255));
+
+    // Half of the stroke lies outside of the rect
+    assert!((bounds.minx - 8.0).abs() < 0.01, "{:?}", bounds);
+    assert!((bounds.miny - 8.0).abs() < 0.01, "{:?}", bounds);
+    assert!((bounds.maxx - 32.0).abs() < 0.01, "{:?}", bounds);
+    assert!((bounds.maxy - 32.0).abs() < 0.01, "{:?}", bounds);
+}
+
+#[test]
+fn svg_clip_path_masks_to_its_shape() {
+    let tree = parse(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="40">
+            <clipPath id="clip">
+                <circle cx="20" cy="20" r="10"/>
+            </clipPath>
+            <g clip-path="url(#clip)">
+                <rect width="40" height="40" fill="#ffffff"/>
+            </g>
+        </svg>"##,
+    );
+
+    let image = render(40, 40, |canvas| {
+        render_svg_tree(canvas, &tree);
+    });
+
+    assert_eq!(pixel(&image, 20, 20), RGBA8::new(255, 255, 255, 255));
+    assert_eq!(pixel(&image, 5, 5), RGBA8::new(0, 0, 0, 255));
+    // Inside the bounding box of the circle, but outside of the circle itself
+    assert_eq!(pixel(&image, 12, 12), RGBA8::new(0, 0, 0, 255));
+    assert_eq!(pixel(&image, 35, 20), RGBA8::new(0, 0, 0, 255));
+}
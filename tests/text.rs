@@ -0,0 +1,597 @@
+#![cfg(feature = "text")]
+
+use femtovg::{
+    renderer::{Software, Void},
+    Baseline, Canvas, Color, ErrorKind, FontId, Paint, Path, TextRendering,
+};
+
+mod common;
+
+use common::ROBOTO_REGULAR;
+
+#[test]
+fn text_location_respects_scale() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    canvas.add_font(ROBOTO_REGULAR).expect("Font not found");
+
+    let mut paint = Paint::color(Color::black());
+    paint.set_text_baseline(Baseline::Top);
+    canvas.scale(5.0, 5.0);
+
+    let res = canvas.measure_text(100.0, 100.0, "Hello", &paint).unwrap();
+
+    assert_eq!(res.x, 100.0);
+    assert_eq!(res.y, 100.0);
+}
+
+#[test]
+fn subpixel_positioning_controls_glyph_snapping() {
+    let render = |x: f32, y: f32, subpixel: bool| {
+        common::render(60, 40, |canvas| {
+            let font = canvas.add_font(ROBOTO_REGULAR).expect("Font not found");
+            let paint = Paint::color(Color::white())
+                .with_font(&[font])
+                .with_font_size(20.0)
+                .with_subpixel_positioning(subpixel);
+
+            canvas.fill_text(x, y, "H", &paint).unwrap();
+        })
+        .into_buf()
+    };
+
+    // Off by default, so glyph origins keep being rounded to whole pixels
+    assert!(!Paint::default().subpixel_positioning());
+
+    assert_eq!(render(10.0, 30.0, false), render(10.0, 30.3, false));
+    assert_eq!(render(10.0, 30.0, false), render(10.3, 30.0, false));
+    assert_ne!(render(10.0, 30.0, true), render(10.0, 30.3, true));
+    assert_ne!(render(10.0, 30.0, true), render(10.3, 30.0, true));
+}
+
+#[test]
+fn letter_and_word_spacing_change_advances() {
+    let text_context = femtovg::TextContext::default();
+
+    let font_id = text_context.add_font_file(ROBOTO_REGULAR).expect("Font not found");
+
+    let paint = Paint::default().with_font(&[font_id]).with_font_size(16.0);
+    let width = |paint: &Paint| text_context.measure_text(0., 0., "a b", paint).unwrap().width();
+
+    let base = width(&paint);
+    assert!((width(&paint.clone().with_word_spacing(10.0)) - (base + 10.0)).abs() < 0.01);
+    assert!((width(&paint.clone().with_letter_spacing(-1.0)) - (base - 3.0)).abs() < 0.01);
+}
+
+#[test]
+fn shape_text_maps_glyphs_to_clusters() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    let font = canvas.add_font(ROBOTO_REGULAR).expect("Font not found");
+    let paint = Paint::default().with_font(&[font]).with_font_size(16.0);
+
+    let run = canvas.shape_text("aé b", &paint).unwrap();
+
+    assert_eq!(run.glyphs.len(), 4);
+    assert_eq!(run.cluster_range(0), Some(0..1));
+    // The two byte 'é' forms one cluster
+    assert_eq!(run.cluster_range(1), Some(1..3));
+    assert_eq!(run.cluster_range(3), Some(4..5));
+    assert_eq!(run.cluster_range(4), None);
+    assert_eq!(run.glyphs[3].c, 'b');
+
+    let advances: f32 = run.glyphs.iter().map(|glyph| glyph.advance_x).sum();
+    assert!((advances - run.width()).abs() < 0.01);
+}
+
+#[test]
+fn add_font_reports_io_errors() {
+    let mut canvas = Canvas::new(Void).unwrap();
+
+    assert!(canvas.add_font(ROBOTO_REGULAR).is_ok());
+    assert!(matches!(
+        canvas.add_font("examples/assets/does-not-exist.ttf"),
+        Err(ErrorKind::IoError(_))
+    ));
+}
+
+#[test]
+fn add_font_mem_face_by_index() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    let data = std::fs::read(ROBOTO_REGULAR).unwrap();
+
+    assert_eq!(Canvas::<Void>::count_faces(&data), 1);
+    assert_eq!(Canvas::<Void>::count_faces(b"not a font"), 0);
+    assert!(canvas.add_font_mem_face(&data, 0).is_ok());
+    assert!(canvas.add_font_mem_face(&data, 1).is_err());
+}
+
+#[test]
+fn font_metrics_by_id() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    let font = canvas.add_font(ROBOTO_REGULAR).unwrap();
+
+    let small = canvas.font_metrics(font, 10.0).unwrap();
+    let large = canvas.font_metrics(font, 20.0).unwrap();
+    assert!(small.ascender() > 0.0);
+    assert!((large.ascender() - 2.0 * small.ascender()).abs() < 1e-3);
+
+    let paint = Paint::color(Color::black()).with_font(&[font]).with_font_size(20.0);
+    assert_eq!(canvas.measure_font(&paint).unwrap().ascender(), large.ascender());
+}
+
+#[test]
+fn find_font_picks_closest_weight() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    let light = canvas.add_font("examples/assets/Roboto-Light.ttf").unwrap();
+    let regular = canvas.add_font(ROBOTO_REGULAR).unwrap();
+    let bold = canvas.add_font("examples/assets/Roboto-Bold.ttf").unwrap();
+
+    assert_eq!(canvas.find_font("Roboto", 400, false), Some(regular));
+    assert_eq!(canvas.find_font("roboto", 250, false), Some(light));
+    assert_eq!(canvas.find_font("Roboto", 900, true), Some(bold));
+    assert_eq!(canvas.find_font("Amiri", 400, false), None);
+}
+
+#[test]
+fn font_list_falls_back_per_character() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    let roboto = canvas.add_font(ROBOTO_REGULAR).unwrap();
+    let icons = canvas.add_font("examples/assets/entypo.ttf").unwrap();
+
+    let paint = Paint::color(Color::black()).with_font(&[roboto, icons]);
+    let run = canvas.shape_text("a\u{E740}b", &paint).unwrap();
+
+    let fonts: Vec<_> = run.glyphs.iter().map(|glyph| glyph.font_id).collect();
+    assert_eq!(fonts, [roboto, icons, roboto]);
+    assert!(run.glyphs.iter().all(|glyph| glyph.codepoint != 0));
+    assert_eq!(run.cluster_range(1), Some(1..4));
+}
+
+#[test]
+fn per_character_fallback_is_limited_to_font_list() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    let roboto = canvas.add_font(ROBOTO_REGULAR).unwrap();
+    let amiri = canvas.add_font("examples/assets/amiri-regular.ttf").unwrap();
+
+    // A single word where only Roboto has the Cyrillic letter and only Amiri the transliteration letter
+    let fonts = |font_ids: &[FontId]| {
+        let paint = Paint::color(Color::black()).with_font(font_ids);
+        let run = canvas.shape_text("\u{436}\u{1E0F}", &paint).unwrap();
+        run.glyphs
+            .iter()
+            .map(|glyph| (glyph.font_id, glyph.codepoint != 0))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(fonts(&[roboto, amiri]), [(roboto, true), (amiri, true)]);
+    // Amiri is registered, but not part of the paint
+    assert_eq!(fonts(&[roboto]), [(roboto, true), (roboto, false)]);
+}
+
+#[test]
+fn text_decorations_span_text() {
+    let mut canvas = common::canvas(40, 40);
+    let font = canvas.add_font(ROBOTO_REGULAR).unwrap();
+    let metrics = canvas.font_metrics(font, 20.0).unwrap();
+
+    let coverage = |canvas: &mut Canvas<Software>, paint: &Paint, line: f32| {
+        let mut text = None;
+        let image = common::redraw(canvas, |canvas| {
+            // Only spaces, so any drawn pixels come from the decorations
+            text = Some(canvas.fill_text(4.0, 30.0, "    ", paint).unwrap());
+            canvas.flush();
+        });
+
+        let text = text.unwrap();
+        let x = (text.x + text.width() / 2.0) as usize;
+        let y = (30.0 - line) as usize;
+        image[(x, y)].r
+    };
+
+    let paint = Paint::color(Color::white()).with_font(&[font]).with_font_size(20.0);
+    let underline = metrics.underline_position() - metrics.underline_thickness() / 2.0;
+    let strikeout = metrics.strikeout_position() - metrics.strikeout_thickness() / 2.0;
+
+    assert_eq!(coverage(&mut canvas, &paint, underline), 0);
+    assert!(coverage(&mut canvas, &paint.clone().with_underline(true), underline) > 0);
+    assert_eq!(coverage(&mut canvas, &paint.clone().with_underline(true), strikeout), 0);
+    assert!(coverage(&mut canvas, &paint.clone().with_strikethrough(true), strikeout) > 0);
+}
+
+#[test]
+fn text_shadow_draws_blurred_copy() {
+    let mut canvas = common::canvas(60, 40);
+    let font = canvas.add_font(ROBOTO_REGULAR).unwrap();
+
+    let mut render = |paint: &Paint| {
+        common::redraw(&mut canvas, |canvas| {
+            canvas.fill_text(10.0, 30.0, "I", paint).unwrap();
+            canvas.flush();
+        })
+    };
+
+    let paint = Paint::color(Color::rgb(0, 255, 0))
+        .with_font(&[font])
+        .with_font_size(30.0);
+    let sharp = render(&paint.clone().with_text_shadow(20.0, 0.0, 0.0, Color::rgb(255, 0, 0)));
+    let blurred = render(&paint.clone().with_text_shadow(20.0, 0.0, 6.0, Color::rgb(255, 0, 0)));
+
+    let red_columns = |image: &imgref::ImgVec<rgb::RGBA8>| {
+        (0..60)
+            .filter(|&x| (0..40).any(|y| common::pixel(image, x, y).r > 0))
+            .count()
+    };
+
+    // The shadow is drawn beside the text and the blur spreads it out
+    assert!(red_columns(&sharp) > 0);
+    assert!(red_columns(&blurred) > red_columns(&sharp) + 4);
+    assert!((0..40).any(|y| common::pixel(&sharp, 14, y) == rgb::RGBA8::new(0, 255, 0, 255)));
+}
+
+#[test]
+fn text_measure_without_canvas() {
+    let text_context = femtovg::TextContext::default();
+
+    let font_id = text_context.add_font_file(ROBOTO_REGULAR).expect("Font not found");
+
+    let mut test_paint = femtovg::Paint::default();
+    test_paint.set_font(&[font_id]);
+    test_paint.set_font_size(16.);
+
+    let metrics = text_context
+        .measure_text(0., 0., "Hello World", &test_paint)
+        .expect("text shaping failed unexpectedly");
+
+    assert_eq!(metrics.width().ceil(), 83.);
+    assert_eq!(metrics.height().ceil(), 13.);
+}
+
+#[test]
+fn font_measure_without_canvas() {
+    let text_context = femtovg::TextContext::default();
+
+    let font_id = text_context.add_font_file(ROBOTO_REGULAR).expect("Font not found");
+
+    let mut test_paint = femtovg::Paint::default();
+    test_paint.set_font(&[font_id]);
+    test_paint.set_font_size(16.);
+
+    let metrics = text_context
+        .measure_font(&test_paint)
+        .expect("font measuring failed unexpectedly");
+
+    assert_eq!(metrics.ascender().ceil(), 17.);
+}
+
+#[test]
+fn break_text_without_canvas() {
+    let text_context = femtovg::TextContext::default();
+
+    let font_id = text_context.add_font_file(ROBOTO_REGULAR).expect("Font not found");
+
+    let mut test_paint = femtovg::Paint::default();
+    test_paint.set_font(&[font_id]);
+    test_paint.set_font_size(16.);
+
+    let text = "Multiple Lines Broken";
+
+    let breaks = text_context
+        .break_text_vec(60., text, &test_paint)
+        .expect("text shaping failed unexpectedly");
+
+    assert_eq!(
+        breaks
+            .iter()
+            .map(|range| &text[range.start..range.end])
+            .collect::<Vec<_>>(),
+        vec!["Multiple ", "Lines ", "Broken"]
+    );
+}
+
+#[test]
+fn text_on_path_follows_path_and_drops_overflow() {
+    let mut canvas = common::canvas_with_font(60, 100);
+
+    let paint = Paint::color(Color::white())
+        .with_font_size(16.0)
+        .with_text_baseline(Baseline::Middle);
+
+    // Returns the bounds of all lit pixels as (minx, miny, maxx, maxy)
+    let mut render = |length: f32| {
+        let mut path = Path::new();
+        path.move_to(30.0, 0.0);
+        path.line_to(30.0, length);
+
+        let screen = common::redraw(&mut canvas, |canvas| {
+            canvas.fill_text_on_path("HHHHHH", &path, &paint, 5.0).unwrap();
+        });
+
+        let mut bounds = (usize::MAX, usize::MAX, 0, 0);
+        for y in 0..100 {
+            for x in 0..60 {
+                if common::pixel(&screen, x, y).r > 128 {
+                    bounds = (bounds.0.min(x), bounds.1.min(y), bounds.2.max(x), bounds.3.max(y));
+                }
+            }
+        }
+        bounds
+    };
+
+    // The text runs downwards, centered on the path
+    let (minx, miny, maxx, maxy) = render(100.0);
+    assert!(minx >= 22 && maxx <= 38, "{minx} {maxx}");
+    assert!(miny >= 5 && maxy > 60, "{miny} {maxy}");
+
+    // Glyphs past the end of a shorter path are left out
+    let (_, _, _, short_maxy) = render(40.0);
+    assert!(short_maxy < 45, "{short_maxy}");
+}
+
+#[test]
+fn text_on_straight_path_keeps_shaping() {
+    let mut canvas = common::canvas(120, 40);
+    let font = canvas.add_font("examples/assets/amiri-regular.ttf").unwrap();
+
+    // Joined Arabic letters only look the same when the word is shaped as a whole
+    let paint = Paint::color(Color::white()).with_font(&[font]).with_font_size(20.0);
+    let text = "\u{645}\u{631}\u{62D}\u{628}\u{627}";
+
+    let mut path = Path::new();
+    path.move_to(10.0, 30.0);
+    path.line_to(110.0, 30.0);
+
+    let on_path = common::redraw(&mut canvas, |canvas| {
+        canvas.fill_text_on_path(text, &path, &paint, 0.0).unwrap();
+    });
+    let direct = common::redraw(&mut canvas, |canvas| {
+        canvas.fill_text(10.0, 30.0, text, &paint).unwrap();
+    });
+
+    assert_eq!(on_path.buf(), direct.buf());
+}
+
+#[test]
+fn tabs_advance_to_tab_stops() {
+    let mut canvas = common::canvas_with_font(100, 20);
+
+    let paint = Paint::color(Color::white()).with_font_size(14.0).with_tab_width(40.0);
+    assert_eq!(paint.tab_width(), 40.0);
+
+    let x_of = |canvas: &Canvas<Software>, text: &str, c: char| {
+        let metrics = canvas.measure_text(10.0, 10.0, text, &paint).unwrap();
+        let glyph = metrics.glyphs.iter().find(|glyph| glyph.c == c).unwrap();
+        glyph.x - glyph.bearing_x
+    };
+
+    let b = x_of(&canvas, "b", 'b');
+    assert!((x_of(&canvas, "a\tb", 'b') - (b + 40.0)).abs() < 0.01);
+    assert!((x_of(&canvas, "aaaa\tb", 'b') - (b + 40.0)).abs() < 0.01);
+    assert!((x_of(&canvas, "a\t\tb", 'b') - (b + 80.0)).abs() < 0.01);
+
+    let metrics = canvas.measure_text(0.0, 0.0, "a\t", &paint).unwrap();
+    assert!((metrics.width() - 40.0).abs() < 0.01);
+
+    // The tab itself draws nothing
+    let screen = common::redraw(&mut canvas, |canvas| {
+        canvas.fill_text(10.0, 15.0, "\t\t", &paint).unwrap();
+    });
+    assert!(screen.pixels().all(|pixel| pixel.r == 0));
+}
+
+#[test]
+fn ellipsized_text_fits_max_width() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.add_font(ROBOTO_REGULAR).expect("Font not found");
+
+    let paint = Paint::color(Color::white()).with_font_size(14.0);
+    let text = "The quick brown fox jumps over the lazy dog";
+    let full_width = canvas.measure_text(0.0, 0.0, text, &paint).unwrap().width();
+
+    let metrics = canvas.fill_text_ellipsized(0.0, 0.0, full_width, text, &paint).unwrap();
+    assert_eq!(metrics.glyphs.len(), text.chars().count());
+
+    let metrics = canvas.fill_text_ellipsized(0.0, 0.0, 100.0, text, &paint).unwrap();
+    assert!(metrics.width() <= 100.0);
+    assert!(metrics.width() > 80.0);
+    assert_eq!(metrics.glyphs.last().unwrap().c, '\u{2026}');
+    assert!(metrics.glyphs.len() < text.chars().count());
+
+    let metrics = canvas.fill_text_ellipsized(0.0, 0.0, 2.0, text, &paint).unwrap();
+    assert!(metrics.glyphs.is_empty());
+}
+
+#[test]
+fn sdf_text_matches_rasterized_text() {
+    let render = |text_rendering: TextRendering, scale: f32| {
+        let mut canvas = common::canvas_with_font(200, 100);
+        canvas.set_text_rendering(text_rendering);
+        assert_eq!(canvas.text_rendering(), text_rendering);
+
+        let screen = common::redraw(&mut canvas, |canvas| {
+            canvas.scale(scale, scale);
+            let paint = Paint::color(Color::white()).with_font_size(60.0 / scale);
+            canvas.fill_text(10.0 / scale, 70.0 / scale, "Oak", &paint).unwrap();
+        });
+        screen.pixels().map(|pixel| pixel.r as f32 / 255.0).collect::<Vec<_>>()
+    };
+
+    let raster = render(TextRendering::Raster, 1.0);
+    let ink: f32 = raster.iter().sum();
+    assert!(ink > 100.0);
+
+    // The field is rendered once and reused for other sizes and transforms
+    for scale in [1.0, 0.5, 2.0] {
+        let sdf = render(TextRendering::Sdf, scale);
+        let difference: f32 = raster.iter().zip(&sdf).map(|(a, b)| (a - b).abs()).sum();
+        assert!(difference < ink * 0.05, "scale {scale}: {difference} of {ink}");
+    }
+}
+
+#[test]
+fn font_names_are_read_from_name_table() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    let regular = canvas.add_font(ROBOTO_REGULAR).expect("Font not found");
+    let light = canvas
+        .add_font("examples/assets/Roboto-Light.ttf")
+        .expect("Font not found");
+
+    assert_eq!(canvas.font_family(regular).as_deref(), Some("Roboto"));
+    assert_eq!(canvas.font_family(light).as_deref(), Some("Roboto"));
+    assert_eq!(canvas.font_name(regular).as_deref(), Some("Roboto Regular"));
+    assert_eq!(canvas.font_name(light).as_deref(), Some("Roboto Light"));
+}
+
+#[test]
+fn kerning_can_be_disabled() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.add_font(ROBOTO_REGULAR).expect("Font not found");
+
+    let paint = Paint::color(Color::white()).with_font_size(40.0);
+    assert!(paint.kerning());
+
+    let width = |paint: &Paint, text: &str| canvas.measure_text(0.0, 0.0, text, paint).unwrap().width();
+    let unkerned = paint.clone().with_kerning(false);
+
+    // The pair is kerned closer together, the separate glyphs are not affected
+    assert!(width(&paint, "LT") < width(&unkerned, "LT") - 2.0);
+    assert!((width(&unkerned, "LT") - width(&paint, "L") - width(&paint, "T")).abs() < 0.01);
+}
+
+#[test]
+fn font_features_are_passed_to_the_shaper() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    let amiri = canvas
+        .add_font("examples/assets/amiri-regular.ttf")
+        .expect("Font not found");
+    let roboto = canvas.add_font(ROBOTO_REGULAR).expect("Font not found");
+
+    let glyphs = |paint: &Paint, text: &str| {
+        let metrics = canvas.measure_text(0.0, 0.0, text, paint).unwrap();
+        metrics.glyphs.iter().map(|glyph| glyph.codepoint).collect::<Vec<_>>()
+    };
+    let paint = Paint::color(Color::white()).with_font(&[amiri]);
+
+    // The standard ligatures are on by default
+    assert_eq!(glyphs(&paint, "fi").len(), 1);
+    let no_ligatures = paint.clone().with_font_features(&[("liga", 0)]);
+    assert_eq!(glyphs(&no_ligatures, "fi").len(), 2);
+    assert_ne!(
+        glyphs(&paint.clone().with_font_features(&[("pnum", 1)]), "1"),
+        glyphs(&paint, "1")
+    );
+
+    // Unknown and malformed tags are ignored
+    let paint = paint.with_font_features(&[("zzzz", 1), ("toolong", 1), ("", 1), ("liga", 0)]);
+    assert_eq!(paint.font_features(), &[("zzzz".to_owned(), 1), ("liga".to_owned(), 0)]);
+    assert_eq!(glyphs(&paint, "fi"), glyphs(&no_ligatures, "fi"));
+
+    // Kerning can also be turned off as a feature
+    let width = |paint: &Paint| canvas.measure_text(0.0, 0.0, "LT", paint).unwrap().width();
+    let paint = Paint::color(Color::white()).with_font(&[roboto]).with_font_size(20.0);
+    assert_eq!(
+        width(&paint.clone().with_font_features(&[("kern", 0)])),
+        width(&paint.with_kerning(false))
+    );
+}
+
+#[test]
+fn fill_text_returns_next_pen_position() {
+    let mut canvas = Canvas::new(Void).unwrap();
+    canvas.set_size(200, 100, 1.0);
+    let font = canvas.add_font(ROBOTO_REGULAR).unwrap();
+    let paint = Paint::color(Color::black()).with_font(&[font]).with_font_size(20.0);
+
+    let first = canvas.fill_text(10.0, 50.0, "Hello ", &paint).unwrap();
+    let second = canvas.fill_text(first.next_x(), 50.0, "world", &paint).unwrap();
+    let whole = canvas.measure_text(10.0, 50.0, "Hello world", &paint).unwrap();
+
+    assert_eq!(first.next_x(), 10.0 + first.width());
+    assert!((second.next_x() - whole.next_x()).abs() < 0.5);
+}
+
+#[test]
+fn glyphs_larger_than_an_atlas_page_are_drawn_as_paths() {
+    use femtovg::testing;
+
+    let text = |max_texture_size: Option<usize>, text_rendering: TextRendering| {
+        testing::render_to_image(120, 60, |canvas| {
+            canvas.set_max_texture_size(max_texture_size);
+            canvas.set_text_rendering(text_rendering);
+            let font = canvas.add_font(ROBOTO_REGULAR).unwrap();
+            let paint = Paint::color(Color::white()).with_font(&[font]).with_font_size(40.0);
+            canvas.fill_text(4.0, 45.0, "Hg", &paint).unwrap();
+
+            // The glyphs were drawn to the screen, so this must be too
+            let mut path = Path::new();
+            path.rect(100.0, 0.0, 20.0, 20.0);
+            canvas.fill_path(&path, &Paint::color(Color::white()));
+        })
+    };
+
+    for text_rendering in [TextRendering::Raster, TextRendering::Sdf] {
+        let limited = text(Some(32), text_rendering);
+        let lit = limited.chunks(4).filter(|pixel| pixel[0] > 128).count();
+        let difference = testing::pixel_difference(&limited, &text(None, text_rendering), 64);
+
+        // Besides the 400 pixels of the rectangle, the glyphs must show up and look about the same
+        assert!(lit > 700, "{lit}");
+        assert!(difference < lit / 10, "{difference} of {lit}");
+    }
+}
+
+#[test]
+fn rasterized_glyph_matches_drawn_text() {
+    let mut canvas = common::canvas(80, 80);
+    canvas.clear(Color::rgba(0, 0, 0, 0));
+    let font = canvas.add_font(ROBOTO_REGULAR).unwrap();
+    let paint = Paint::color(Color::white()).with_font(&[font]).with_font_size(40.0);
+
+    let shaped = canvas.measure_text(0.0, 0.0, "H ", &paint).unwrap();
+    let (h, space) = (shaped.glyphs[0].codepoint, shaped.glyphs[1].codepoint);
+
+    assert!(canvas.rasterize_glyph(font, space, 40.0).is_none());
+
+    let (metrics, coverage) = canvas.rasterize_glyph(font, h, 40.0).unwrap();
+    assert_eq!(
+        (metrics.width as usize, metrics.height as usize),
+        (coverage.width(), coverage.height())
+    );
+    assert!(metrics.bearing_x <= shaped.glyphs[0].bearing_x);
+    assert!(metrics.bearing_y >= 40.0 * 0.7 && metrics.bearing_y < 40.0 * 0.8);
+
+    // Fully covered inside the left stem, empty in the border and between the stems above the bar
+    let at = |x: f32, y: f32| coverage[((x - metrics.bearing_x) as usize, (metrics.bearing_y - y) as usize)];
+    assert_eq!(at(metrics.bearing_x + 1.0 + 3.0, 5.0), 255);
+    assert_eq!(at(metrics.bearing_x + 1.0 + metrics.width / 2.0, 25.0), 0);
+    assert!(coverage.rows().next().unwrap().iter().all(|c| *c == 0));
+
+    canvas.fill_text(10.0, 60.0, "H", &paint).unwrap();
+    let screen = canvas.screenshot().unwrap();
+    let drawn: u32 = screen.pixels().map(|pixel| pixel.a as u32).sum();
+    let rasterized: u32 = coverage.pixels().map(|c| c as u32).sum();
+    assert!(drawn.abs_diff(rasterized) < drawn / 20, "{drawn} {rasterized}");
+}
+
+#[test]
+fn glyph_run_draws_like_fill_text() {
+    use femtovg::testing;
+
+    let draw = |pre_shaped: bool| {
+        testing::render_to_image(160, 50, |canvas| {
+            let font = canvas.add_font(ROBOTO_REGULAR).unwrap();
+            let paint = Paint::color(Color::white()).with_font(&[font]).with_font_size(24.0);
+            canvas.scale(1.5, 1.5);
+
+            if pre_shaped {
+                let run = canvas.shape_text("Shaped once", &paint).unwrap();
+                canvas.fill_glyph_run(4.5, 22.0, &run.glyphs, &paint).unwrap();
+            } else {
+                canvas.fill_text(4.5, 22.0, "Shaped once", &paint).unwrap();
+            }
+        })
+    };
+
+    let image = draw(true);
+    assert!(image.chunks(4).filter(|pixel| pixel[3] > 128).count() > 200);
+    assert_eq!(testing::pixel_difference(&image, &draw(false), 0), 0);
+}